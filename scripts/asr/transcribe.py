@@ -7,9 +7,9 @@ Communicates progress via JSON line protocol on stdout
 
 import sys
 import json
+import math
 import argparse
-from pathlib import Path
-from typing import Optional, Dict, Any
+from typing import Optional, Dict, Any, List
 import time
 
 
@@ -23,13 +23,47 @@ def send_progress(processed: float, total: float):
     print(json.dumps(progress), flush=True)
 
 
-def send_complete(output_path: str):
-    """Send completion notification"""
+def send_complete(transcript: Dict[str, Any]):
+    """Send the finished transcript; the Rust backend writes the output files"""
     complete = {
         "type": "complete",
-        "output_path": output_path
+        "transcript": transcript
     }
-    print(json.dumps(complete), flush=True)
+    print(json.dumps(complete, ensure_ascii=False), flush=True)
+
+
+def make_segment(start: float, end: float, text: str,
+                 confidence: Optional[float] = None,
+                 words: Optional[List[Dict[str, Any]]] = None) -> Dict[str, Any]:
+    """Build a segment of the shared transcript model"""
+    return {
+        "start": start,
+        "end": end,
+        "text": text.strip(),
+        "speaker": None,
+        "confidence": confidence,
+        "words": words or []
+    }
+
+
+def whisper_transcript(result: Dict[str, Any]) -> Dict[str, Any]:
+    """Convert a Whisper result to the shared transcript model"""
+    segments = []
+    for segment in result.get("segments", []):
+        words = [
+            {
+                "start": word["start"],
+                "end": word["end"],
+                "text": word["word"].strip(),
+                "speaker": None,
+                "confidence": word.get("probability")
+            }
+            for word in segment.get("words", [])
+        ]
+        avg_logprob = segment.get("avg_logprob")
+        confidence = min(1.0, math.exp(avg_logprob)) if avg_logprob is not None else None
+        segments.append(make_segment(segment["start"], segment["end"], segment["text"], confidence, words))
+    return {"language": result.get("language"), "segments": segments}
 
 
 def send_error(message: str):
@@ -41,15 +75,14 @@ def send_error(message: str):
     print(json.dumps(error), flush=True)
 
 
-def transcribe_with_whisper(config: Dict[str, Any]) -> str:
+def transcribe_with_whisper(config: Dict[str, Any]) -> Dict[str, Any]:
     """
     Transcribe audio using OpenAI Whisper
-    Returns: output file path
+    Returns: transcript
     """
     try:
         import torch
         import whisper
-        from whisper.utils import get_writer
     except ImportError as e:
         send_error(f"Missing dependency: {str(e)}. Please install whisper: pip install openai-whisper")
         sys.exit(1)
@@ -57,7 +90,6 @@ def transcribe_with_whisper(config: Dict[str, Any]) -> str:
     input_file = config["input_file"]
     model_name = config["model"]
     language = None if config["language"] == "auto" else config["language"]
-    output_format = config["output_format"]
     hardware_mode = config["hardware_mode"]
 
     # Determine device
@@ -95,40 +127,19 @@ def transcribe_with_whisper(config: Dict[str, Any]) -> str:
             input_file,
             language=language,
             verbose=False,
-            task="transcribe"
+            task="transcribe",
+            word_timestamps=True
         )
 
         # Progress updates (simulate progress during transcription)
         # In real implementation, we'd hook into Whisper's internal progress
         send_progress(total_duration * 0.5, total_duration)
 
-        # Prepare output
-        input_path = Path(input_file)
-        output_dir = config.get("output_dir", input_path.parent)
-        output_dir = Path(output_dir)
-        output_dir.mkdir(parents=True, exist_ok=True)
-
-        base_name = input_path.stem
-        output_files = []
-
-        # Generate SRT
-        if output_format in ["srt", "both"]:
-            srt_path = output_dir / f"{base_name}.srt"
-            writer = get_writer("srt", str(output_dir))
-            writer(result, str(input_path.stem))
-            output_files.append(str(srt_path))
-
-        # Generate TXT
-        if output_format in ["txt", "both"]:
-            txt_path = output_dir / f"{base_name}.txt"
-            with open(txt_path, "w", encoding="utf-8") as f:
-                f.write(result["text"])
-            output_files.append(str(txt_path))
+        transcript = whisper_transcript(result)
 
         send_progress(total_duration, total_duration)
 
-        # Return first output file (or both if needed)
-        return output_files[0] if output_files else ""
+        return transcript
 
     except MemoryError:
         send_error("記憶體不足，請嘗試較小的模型")
@@ -138,10 +149,10 @@ def transcribe_with_whisper(config: Dict[str, Any]) -> str:
         sys.exit(1)
 
 
-def transcribe_with_qwen(config: Dict[str, Any]) -> str:
+def transcribe_with_qwen(config: Dict[str, Any]) -> Dict[str, Any]:
     """
     Transcribe audio using Qwen3-ASR
-    Returns: output file path
+    Returns: transcript
     """
     try:
         from funasr import AutoModel
@@ -153,9 +164,7 @@ def transcribe_with_qwen(config: Dict[str, Any]) -> str:
     input_file = config["input_file"]
     model_name = config["model"]
     language = config["language"]
-    output_format = config["output_format"]
     enable_punctuation = config.get("enable_punctuation", True)
-    traditional_chinese = config.get("traditional_chinese", False)
 
     # Determine device
     device = "cpu"
@@ -205,48 +214,28 @@ def transcribe_with_qwen(config: Dict[str, Any]) -> str:
 
         # Extract text from result
         if isinstance(result, list) and len(result) > 0:
-            text = result[0].get("text", "")
+            first = result[0]
+            text = first.get("text", "")
+            sentences = first.get("sentence_info", [])
         else:
             text = str(result)
+            sentences = []
 
-        # Convert to traditional Chinese if requested
-        if traditional_chinese and language == "zh":
-            try:
-                from opencc import OpenCC
-                cc = OpenCC('s2t')
-                text = cc.convert(text)
-            except ImportError:
-                pass  # Skip conversion if OpenCC not available
-
-        # Prepare output
-        input_path = Path(input_file)
-        output_dir = config.get("output_dir", input_path.parent)
-        output_dir = Path(output_dir)
-        output_dir.mkdir(parents=True, exist_ok=True)
-
-        base_name = input_path.stem
-        output_files = []
-
-        # For Qwen, we primarily generate text
-        # SRT generation would require timestamps which Qwen doesn't provide by default
-        if output_format in ["txt", "both"]:
-            txt_path = output_dir / f"{base_name}.txt"
-            with open(txt_path, "w", encoding="utf-8") as f:
-                f.write(text)
-            output_files.append(str(txt_path))
-
-        if output_format in ["srt", "both"]:
-            # Generate simple SRT with single timestamp
-            srt_path = output_dir / f"{base_name}.srt"
-            with open(srt_path, "w", encoding="utf-8") as f:
-                f.write("1\n")
-                f.write("00:00:00,000 --> 99:59:59,999\n")
-                f.write(text + "\n")
-            output_files.append(str(srt_path))
+        # Traditional/Simplified conversion is applied by the Rust backend
+
+        # Sentence timestamps (in milliseconds) are only present for models
+        # that produce them; otherwise the text is one segment over the file.
+        if sentences:
+            segments = [
+                make_segment(s["start"] / 1000.0, s["end"] / 1000.0, s.get("text", ""))
+                for s in sentences
+            ]
+        else:
+            segments = [make_segment(0.0, total_duration, text)]
 
         send_progress(total_duration, total_duration)
 
-        return output_files[0] if output_files else ""
+        return {"language": None if language == "auto" else language, "segments": segments}
 
     except MemoryError:
         send_error("記憶體不足，請嘗試較小的模型")
@@ -282,15 +271,15 @@ def main():
 
     # Route to appropriate engine
     if engine == "whisper":
-        output_path = transcribe_with_whisper(config)
+        transcript = transcribe_with_whisper(config)
     elif engine == "qwen":
-        output_path = transcribe_with_qwen(config)
+        transcript = transcribe_with_qwen(config)
     else:
         send_error(f"Unknown engine: {engine}")
         sys.exit(1)
 
     # Send completion
-    send_complete(output_path)
+    send_complete(transcript)
 
 
 if __name__ == "__main__":
@@ -1,18 +1,23 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio, Child};
-use std::sync::{Arc, Mutex};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::{HashMap, HashSet};
 use tauri::{AppHandle, Manager, Emitter, WindowEvent};
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState};
 use regex::Regex;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 use tokio::sync::watch;
+use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
+use tokio::process::{Child as TokioChild, Command as TokioCommand};
+use tokio_util::sync::CancellationToken;
 use futures_util::{SinkExt, StreamExt};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
 
 // Global state for force quit and monitoring pause
 static FORCE_QUIT: AtomicBool = AtomicBool::new(false);
@@ -1552,6 +1557,69 @@ async fn send_scheduled_notification(
     }
 }
 
+/// How often the cookie profiles used by scheduled presets are checked.
+const COOKIE_CHECK_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// Warns that a cookie profile will not authenticate (or soon won't), so the
+/// user can refresh it before a scheduled recording fails. Returns whether a
+/// warning was sent.
+async fn warn_cookie_profile(app: &AppHandle, profile: &CookieProfile, status: &CookieProfileStatus) -> bool {
+    let expires_at = status.auth_cookies.first()
+        .and_then(|c| c.expires_at.as_deref())
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    let (body_key, body_os) = match status.status.as_str() {
+        "expiring" => ("backend.notification.cookiesExpiringBody", format!("Cookie 設定檔「{}」即將過期", profile.name)),
+        "expired" => ("backend.notification.cookiesExpiredBody", format!("Cookie 設定檔「{}」已過期", profile.name)),
+        "no_auth_cookies" | "unreadable" => ("backend.notification.cookiesInvalidBody", format!("Cookie 設定檔「{}」無法使用", profile.name)),
+        _ => return false,
+    };
+    log::warn!("[Cookies] Profile {} is {}", profile.name, status.status);
+    send_scheduled_notification(
+        app,
+        "backend.notification.cookiesWarningTitle",
+        "Cookie 需要更新",
+        body_key,
+        &body_os,
+        Some(serde_json::json!({ "name": profile.name, "expires_at": expires_at })),
+        if status.status == "expiring" { "warning" } else { "critical" },
+    ).await;
+    true
+}
+
+/// Periodically checks the cookie profiles of enabled presets and warns once
+/// for each problem a profile runs into.
+async fn run_cookie_expiry_watch(app: AppHandle) {
+    let mut warned: HashMap<String, String> = HashMap::new();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(COOKIE_CHECK_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        let presets = get_scheduled_presets(app.clone()).unwrap_or_default();
+        let in_use: HashSet<String> = presets.iter()
+            .filter(|p| p.enabled)
+            .filter_map(|p| p.cookie_profile_id.clone())
+            .collect();
+        if in_use.is_empty() {
+            continue;
+        }
+        for profile in get_cookie_profiles(app.clone()).unwrap_or_default() {
+            if !in_use.contains(&profile.id) {
+                continue;
+            }
+            let status = check_cookie_profile(&profile);
+            if warned.get(&profile.id) == Some(&status.status) {
+                continue;
+            }
+            if warn_cookie_profile(&app, &profile, &status).await {
+                warned.insert(profile.id.clone(), status.status.clone());
+            } else {
+                warned.remove(&profile.id);
+            }
+        }
+    }
+}
+
 /// Core trigger function: called when a stream-up event is received.
 /// Finds a matching preset, checks duplicates/cooldown, and enqueues a download.
 async fn trigger_scheduled_download(
@@ -1753,57 +1821,111 @@ async fn trigger_scheduled_download(
         });
     }
 
-    // 10. Check queue capacity and try to start the download.
-    process_scheduled_queue(app, tasks);
+    // 10. Build the recording task and put it in the shared download queue.
+    enqueue_scheduled_task(app, tasks, sched_task).await;
 }
 
-/// Process the scheduled download queue: start queued tasks if under the
-/// `max_concurrent_downloads` limit.
-/// Spawn a background task that checks the queue and starts the next download
-/// if capacity allows. This is a plain function (not async) to avoid type cycles.
-fn process_scheduled_queue(app: AppHandle, tasks: DownloadTasks) {
-    tokio::spawn(process_scheduled_queue_inner(app, tasks));
+/// Priority of live recordings (manual and scheduled) in the download queue.
+/// Stream content is lost while a recording waits, so it goes first.
+const LIVE_TASK_PRIORITY: i32 = 10;
+
+/// Monotonic counter giving queued tasks their FIFO position.
+static QUEUE_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+fn next_queue_seq() -> u64 {
+    QUEUE_SEQ.fetch_add(1, Ordering::SeqCst)
 }
 
-async fn process_scheduled_queue_inner(app: AppHandle, tasks: DownloadTasks) {
-    let config = match load_config(app.clone()) {
-        Ok(c) => c,
-        Err(_) => return,
-    };
-    let max_concurrent = config.max_concurrent_downloads;
+/// Queued task ids in dispatch order: higher priority first, then FIFO.
+fn queued_task_order(tasks: &HashMap<String, DownloadTask>) -> Vec<String> {
+    let mut queued: Vec<(&String, &DownloadTask)> = tasks
+        .iter()
+        .filter(|(_, t)| t.progress.status == "queued")
+        .collect();
+    queued.sort_by(|a, b| {
+        b.1.progress.priority
+            .cmp(&a.1.progress.priority)
+            .then(a.1.queue_seq.cmp(&b.1.queue_seq))
+    });
+    queued.into_iter().map(|(id, _)| id.clone()).collect()
+}
 
-    // Count currently active (recording/downloading) tasks.
-    let active_count = {
-        let tasks_guard = tasks.lock().unwrap();
-        tasks_guard.values().filter(|t| {
+/// Spawn a pass over the download queue shared by manual and scheduled tasks:
+/// start queued tasks while fewer than `max_concurrent_downloads` are active.
+/// This is a plain function (not async) to avoid type cycles.
+fn process_download_queue(app: AppHandle, tasks: DownloadTasks) {
+    tokio::spawn(process_download_queue_inner(app, tasks));
+}
+
+async fn process_download_queue_inner(app: AppHandle, tasks: DownloadTasks) {
+    let max_concurrent = load_config(app.clone())
+        .map(|c| c.max_concurrent_downloads)
+        .unwrap_or_else(|_| default_max_concurrent_downloads())
+        .max(1);
+
+    // Counting and claiming happen under one lock so concurrent passes
+    // cannot start more tasks than there are free slots.
+    let to_start: Vec<(String, bool, Option<String>, Option<u32>)> = {
+        let mut tasks_guard = tasks.lock().await;
+        let active_count = tasks_guard.values().filter(|t| {
             let s = &t.progress.status;
-            s == "downloading" || s == "recording" || s == "processing"
-        }).count()
-    };
+            s == "downloading" || s == "recording" || s == "reconnecting" || s == "processing"
+        }).count();
 
-    if active_count >= max_concurrent {
-        log::debug!(
-            "[Queue] At capacity ({}/{}); not starting new scheduled download",
-            active_count,
-            max_concurrent
-        );
-        // Still emit queue update so the UI knows the position.
-        emit_queue_update(&app).await;
-        return;
-    }
+        let free = max_concurrent.saturating_sub(active_count);
+        if free == 0 {
+            log::debug!("[Queue] At capacity ({}/{}); not starting new downloads", active_count, max_concurrent);
+        }
 
-    // Find the next queued task.
-    let next_task = {
-        let state = scheduled_download_state().lock().await;
-        state.queue.iter()
-            .find(|t| t.status == "queued")
-            .cloned()
+        let mut to_start = Vec::new();
+        for id in queued_task_order(&tasks_guard).into_iter().take(free) {
+            if let Some(task) = tasks_guard.get_mut(&id) {
+                let is_recording = task.progress.is_recording == Some(true);
+                task.progress.status = if is_recording { "recording" } else { "downloading" }.to_string();
+                task.progress.queue_position = None;
+                app.emit("download-progress", &task.progress).ok();
+                // A suspended process is continued rather than started again.
+                let suspended_pid = if task.suspended { task.process_id } else { None };
+                task.suspended = false;
+                to_start.push((id, is_recording, task.scheduled_task_id.clone(), suspended_pid));
+            }
+        }
+
+        // Refresh the position of every task still waiting.
+        for (index, id) in queued_task_order(&tasks_guard).into_iter().enumerate() {
+            if let Some(task) = tasks_guard.get_mut(&id) {
+                let position = Some(index as u32 + 1);
+                if task.progress.queue_position != position {
+                    task.progress.queue_position = position;
+                    app.emit("download-progress", &task.progress).ok();
+                }
+            }
+        }
+
+        to_start
     };
 
-    if let Some(task) = next_task {
-        start_scheduled_task(app, tasks, task).await;
-    } else {
-        emit_queue_update(&app).await;
+    for (id, is_recording, sched_task_id, suspended_pid) in to_start {
+        if let Some(pid) = suspended_pid {
+            log::info!("[Queue] Continuing suspended task {}", id);
+            // If the process is gone its executor reports the outcome.
+            if let Err(e) = continue_download_process(pid) {
+                log::warn!("[Queue] Failed to continue task {}: {}", id, e);
+            }
+            continue;
+        }
+        log::info!("[Queue] Starting task {}", id);
+        match sched_task_id {
+            Some(sched_id) => {
+                tokio::spawn(run_scheduled_task(app.clone(), tasks.clone(), id, sched_id));
+            }
+            None if is_recording => {
+                tokio::spawn(execute_recording(app.clone(), tasks.clone(), id));
+            }
+            None => {
+                tokio::spawn(execute_download(app.clone(), tasks.clone(), id));
+            }
+        }
     }
 }
 
@@ -1816,8 +1938,9 @@ async fn emit_queue_update(app: &AppHandle) {
     let _ = app.emit("scheduled-download-queue-update", serde_json::json!({ "queue": queue }));
 }
 
-/// Start an actual recording for a scheduled download task.
-async fn start_scheduled_task(
+/// Build the recording task for a scheduled download and add it to the shared
+/// download queue. The dispatcher starts it via `run_scheduled_task`.
+async fn enqueue_scheduled_task(
     app: AppHandle,
     tasks: DownloadTasks,
     sched_task: ScheduledDownloadTask,
@@ -1840,38 +1963,12 @@ async fn start_scheduled_task(
         None => {
             // Preset was deleted; fail this task.
             mark_scheduled_task_failed(&app, &task_id, "找不到對應的預設").await;
-            // Process next queued item.
-            process_scheduled_queue(app.clone(), tasks.clone());
             return;
         }
     };
 
-    // Mark as "downloading" (starting).
-    let now_str = Utc::now().to_rfc3339();
-    {
-        let mut state = scheduled_download_state().lock().await;
-        if let Some(t) = state.queue.iter_mut().find(|t| t.id == task_id) {
-            t.status = "downloading".to_string();
-            t.started_at = Some(now_str.clone());
-        }
-    }
-    emit_queue_update(&app).await;
-
     // Build VideoInfo (minimal — is_live = true)
-    let expanded_dir = {
-        let d = &preset.output_dir;
-        if d.starts_with('~') {
-            if let Some(home) = std::env::var("HOME").ok()
-                .or_else(|| std::env::var("USERPROFILE").ok())
-            {
-                d.replacen('~', &home, 1)
-            } else {
-                d.clone()
-            }
-        } else {
-            d.clone()
-        }
-    };
+    let expanded_dir = expand_home_dir(&preset.output_dir);
 
     let video_info = VideoInfo {
         id: sched_task.stream_id.clone(),
@@ -1883,8 +1980,24 @@ async fn start_scheduled_task(
         content_type: "stream".to_string(),
         is_live: true,
         qualities: vec![],
+        created_at: None,
+        start_time: None,
     };
 
+    // The recording still starts, but a dead cookie profile is the likely
+    // cause if it fails, so say so up front.
+    if let Some(ref profile_id) = preset.cookie_profile_id {
+        let profile = get_cookie_profiles(app.clone())
+            .ok()
+            .and_then(|profiles| profiles.into_iter().find(|p| &p.id == profile_id));
+        if let Some(profile) = profile {
+            let status = check_cookie_profile(&profile);
+            if status.status != "expiring" {
+                warn_cookie_profile(&app, &profile, &status).await;
+            }
+        }
+    }
+
     let format_id = quality_to_format(&preset.quality, &preset.content_type);
 
     // Determine which template to use: preset template → global default (F10.5, F10.6)
@@ -1938,14 +2051,18 @@ async fn start_scheduled_task(
         output_folder: expanded_dir,
         container_format: preset.container_format.clone(),
         time_range: None,
+        download_archive: None,
+        precise_cut: None,
+        recording_limits: preset.recording_limits.clone(),
+        cookie_profile_id: preset.cookie_profile_id.clone(),
     };
 
-    // Use the existing recording infrastructure by inserting a task directly
-    // and spawning execute_recording.
+    // Use the existing recording infrastructure by queueing a regular task;
+    // live recordings go ahead of VOD downloads.
     let dl_task_id = Uuid::new_v4().to_string();
     let progress = DownloadProgress {
         task_id: dl_task_id.clone(),
-        status: "recording".to_string(),
+        status: "queued".to_string(),
         title: download_config.video_info.title.clone(),
         percentage: 0.0,
         speed: "0 B/s".to_string(),
@@ -1957,17 +2074,19 @@ async fn start_scheduled_task(
         is_recording: Some(true),
         recorded_duration: Some("00:00:00".to_string()),
         bitrate: Some("N/A".to_string()),
+        retry_attempt: None,
+        max_retries: None,
+        priority: LIVE_TASK_PRIORITY,
+        queue_position: None,
+        processing_step: None,
+        segments: Vec::new(),
     };
 
-    let dl_task = DownloadTask {
-        config: download_config.clone(),
-        progress: progress.clone(),
-        process: None,
-        paused: false,
-    };
+    let mut dl_task = DownloadTask::new(download_config.clone(), progress.clone());
+    dl_task.scheduled_task_id = Some(task_id.clone());
 
     {
-        let mut tasks_guard = tasks.lock().unwrap();
+        let mut tasks_guard = tasks.lock().await;
         tasks_guard.insert(dl_task_id.clone(), dl_task);
     }
     app.emit("download-progress", &progress).ok();
@@ -1981,114 +2100,147 @@ async fn start_scheduled_task(
     }
     emit_queue_update(&app).await;
 
-    // Spawn recording and monitor for completion.
-    let app2 = app.clone();
-    let tasks2 = tasks.clone();
-    let dl_task_id2 = dl_task_id.clone();
-    let sched_task_id = task_id.clone();
-    let app3 = app.clone();
+    process_download_queue(app, tasks);
+}
 
-    tokio::spawn(async move {
-        // Run the recording.
-        execute_recording(app2.clone(), tasks2.clone(), dl_task_id2.clone()).await;
-
-        // After recording finishes, update scheduled task status.
-        let final_status = {
-            let tasks_guard = tasks2.lock().unwrap();
-            tasks_guard.get(&dl_task_id2)
-                .map(|t| (t.progress.status.clone(), t.progress.output_path.clone(), t.progress.error_message.clone()))
-        };
+/// Run a dispatched scheduled recording and report its outcome to the
+/// scheduled queue (status, notifications).
+async fn run_scheduled_task(
+    app: AppHandle,
+    tasks: DownloadTasks,
+    dl_task_id: String,
+    sched_task_id: String,
+) {
+    // Mark as "downloading" (starting).
+    {
+        let mut state = scheduled_download_state().lock().await;
+        if let Some(t) = state.queue.iter_mut().find(|t| t.id == sched_task_id) {
+            t.status = "downloading".to_string();
+            t.started_at = Some(Utc::now().to_rfc3339());
+        }
+    }
+    emit_queue_update(&app).await;
 
-        let completed_at = Utc::now().to_rfc3339();
+    // Run the recording, including any automatic retries.
+    execute_recording(app.clone(), tasks.clone(), dl_task_id.clone()).await;
+    wait_for_task_settled(&tasks, &dl_task_id).await;
 
-        if let Some((dl_status, output_path, error_msg)) = final_status {
-            let (new_status, error) = match dl_status.as_str() {
-                "completed" | "stream_interrupted" => ("completed", None),
-                "failed" | "cancelled" => ("failed", error_msg),
-                _ => ("completed", None),
-            };
+    // After recording finishes, update scheduled task status.
+    let final_status = {
+        let tasks_guard = tasks.lock().await;
+        tasks_guard.get(&dl_task_id)
+            .map(|t| (t.progress.status.clone(), t.progress.output_path.clone(), t.progress.error_message.clone()))
+    };
 
-            // Get file size.
-            let file_size = output_path.as_ref().and_then(|p| {
-                std::fs::metadata(p).ok().map(|m| m.len())
-            });
+    let completed_at = Utc::now().to_rfc3339();
 
-            {
-                let mut state = scheduled_download_state().lock().await;
-                if let Some(t) = state.queue.iter_mut().find(|t| t.id == sched_task_id) {
-                    t.status = new_status.to_string();
-                    t.completed_at = Some(completed_at.clone());
-                    t.file_path = output_path.clone();
-                    t.file_size = file_size;
-                    t.error_message = error.clone();
-                }
-            }
+    if let Some((dl_status, output_path, error_msg)) = final_status {
+        let (new_status, error) = match dl_status.as_str() {
+            "completed" | "stream_interrupted" => ("completed", None),
+            "failed" | "cancelled" => ("failed", error_msg),
+            _ => ("completed", None),
+        };
 
-            let channel_name2 = {
-                let state = scheduled_download_state().lock().await;
-                state.queue.iter()
-                    .find(|t| t.id == sched_task_id)
-                    .map(|t| t.channel_name.clone())
-                    .unwrap_or_default()
-            };
+        // Get file size.
+        let file_size = output_path.as_ref().and_then(|p| {
+            std::fs::metadata(p).ok().map(|m| m.len())
+        });
 
-            if new_status == "completed" {
-                let _ = app2.emit(
-                    "scheduled-download-complete",
-                    serde_json::json!({
-                        "task_id": sched_task_id,
-                        "channel_name": channel_name2,
-                        "file_size": file_size,
-                    }),
-                );
-                // Notify user: download completed.
-                let size_str = file_size.map(|s| format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)))
-                    .unwrap_or_else(|| "?".to_string());
-                send_scheduled_notification(
-                    &app2,
-                    "backend.notification.completeTitle",
-                    "排程下載完成",
-                    "backend.notification.completeBody",
-                    &format!("{} 的直播錄製已完成（{}）", channel_name2, size_str),
-                    Some(serde_json::json!({ "channel": channel_name2, "size": size_str })),
-                    "info",
-                ).await;
-            } else {
-                let err_msg = error.unwrap_or_else(|| "errors.download.recordingFailed".to_string());
-                // Wrap the error in a LocalizedMessage if it looks like a known i18n key,
-                // otherwise pass it as a plain string for backward compatibility.
-                let err_localized = if err_msg.contains('.') && !err_msg.contains(' ') {
-                    serde_json::json!({ "key": err_msg })
-                } else {
-                    serde_json::json!(err_msg)
-                };
-                let _ = app2.emit(
-                    "scheduled-download-failed",
-                    serde_json::json!({
-                        "task_id": sched_task_id,
-                        "channel_name": channel_name2,
-                        "error": err_localized,
-                    }),
-                );
-                // Notify user: download failed.
-                // Truncate error to a short summary.
-                let err_summary: String = err_msg.chars().take(60).collect();
-                send_scheduled_notification(
-                    &app2,
-                    "backend.notification.failedTitle",
-                    "排程下載失敗",
-                    "backend.notification.failedBody",
-                    &format!("{} 的下載失敗：{}", channel_name2, err_summary),
-                    Some(serde_json::json!({ "channel": channel_name2, "error": err_summary })),
-                    "warning",
-                ).await;
+        {
+            let mut state = scheduled_download_state().lock().await;
+            if let Some(t) = state.queue.iter_mut().find(|t| t.id == sched_task_id) {
+                t.status = new_status.to_string();
+                t.completed_at = Some(completed_at.clone());
+                t.file_path = output_path.clone();
+                t.file_size = file_size;
+                t.error_message = error.clone();
             }
+        }
 
-            emit_queue_update(&app2).await;
+        let channel_name2 = {
+            let state = scheduled_download_state().lock().await;
+            state.queue.iter()
+                .find(|t| t.id == sched_task_id)
+                .map(|t| t.channel_name.clone())
+                .unwrap_or_default()
+        };
+
+        if new_status == "completed" {
+            let _ = app.emit(
+                "scheduled-download-complete",
+                serde_json::json!({
+                    "task_id": sched_task_id,
+                    "channel_name": channel_name2,
+                    "file_size": file_size,
+                }),
+            );
+            // Notify user: download completed.
+            let size_str = file_size.map(|s| format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)))
+                .unwrap_or_else(|| "?".to_string());
+            send_scheduled_notification(
+                &app,
+                "backend.notification.completeTitle",
+                "排程下載完成",
+                "backend.notification.completeBody",
+                &format!("{} 的直播錄製已完成（{}）", channel_name2, size_str),
+                Some(serde_json::json!({ "channel": channel_name2, "size": size_str })),
+                "info",
+            ).await;
+        } else {
+            let err_msg = error.unwrap_or_else(|| "errors.download.recordingFailed".to_string());
+            // Wrap the error in a LocalizedMessage if it looks like a known i18n key,
+            // otherwise pass it as a plain string for backward compatibility.
+            let err_localized = if err_msg.contains('.') && !err_msg.contains(' ') {
+                serde_json::json!({ "key": err_msg })
+            } else {
+                serde_json::json!(err_msg)
+            };
+            let _ = app.emit(
+                "scheduled-download-failed",
+                serde_json::json!({
+                    "task_id": sched_task_id,
+                    "channel_name": channel_name2,
+                    "error": err_localized,
+                }),
+            );
+            // Notify user: download failed.
+            // Truncate error to a short summary.
+            let err_summary: String = err_msg.chars().take(60).collect();
+            send_scheduled_notification(
+                &app,
+                "backend.notification.failedTitle",
+                "排程下載失敗",
+                "backend.notification.failedBody",
+                &format!("{} 的下載失敗：{}", channel_name2, err_summary),
+                Some(serde_json::json!({ "channel": channel_name2, "error": err_summary })),
+                "warning",
+            ).await;
         }
 
-        emit_queue_update(&app3).await;
-    });
+        emit_queue_update(&app).await;
+    }
+
+    emit_queue_update(&app).await;
+}
+
+/// Wait until a download task reaches a final state. Retries run in their own
+/// spawned task, so the first executor returning does not mean the task ended.
+async fn wait_for_task_settled(tasks: &DownloadTasks, task_id: &str) {
+    loop {
+        let running = {
+            let tasks_guard = tasks.lock().await;
+            tasks_guard.get(task_id).map(|t| {
+                matches!(
+                    t.progress.status.as_str(),
+                    "queued" | "downloading" | "recording" | "reconnecting" | "processing" | "paused" | "retrying"
+                )
+            }).unwrap_or(false)
+        };
+        if !running {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
 }
 
 /// Mark a scheduled task as failed and emit events.
@@ -2168,20 +2320,18 @@ async fn cancel_scheduled_download(
     if let Some(dl_id) = dl_task_id {
         let tasks_arc = tasks.inner().clone();
         let config_opt = {
-            let mut tasks_guard = tasks_arc.lock().unwrap();
+            let mut tasks_guard = tasks_arc.lock().await;
             if let Some(task) = tasks_guard.get_mut(&dl_id) {
-                if let Some(ref mut child) = task.process {
-                    child.kill().ok();
-                }
+                task.cancel.cancel();
                 task.progress.status = "cancelled".to_string();
                 app.emit("download-progress", &task.progress).ok();
-                Some(task.config.clone())
+                Some((task.config.clone(), task.finish_attempt("cancelled", None)))
             } else {
                 None
             }
         };
-        if let Some(cfg) = config_opt {
-            save_download_history(&app, &cfg, "", "cancelled", None).await;
+        if let Some((cfg, attempts)) = config_opt {
+            save_download_history(&app, &cfg, "", "cancelled", None, attempts, &[]).await;
         }
     }
 
@@ -2235,7 +2385,7 @@ async fn retry_scheduled_download(
     );
     emit_queue_update(&app).await;
 
-    process_scheduled_queue(app, tasks_clone);
+    enqueue_scheduled_task(app, tasks_clone, new_task).await;
 
     Ok(())
 }
@@ -2253,6 +2403,33 @@ pub struct DownloadConfig {
     pub output_folder: String,
     pub container_format: String, // "auto", "mp4", "mkv"
     pub time_range: Option<TimeRange>,
+    /// yt-dlp `--download-archive` file the finished download is recorded in.
+    #[serde(default)]
+    pub download_archive: Option<String>,
+    /// Frame-accurate trimming of `time_range`: "boundaries" re-encodes only
+    /// the GOPs at each cut, "full" the whole clip. `None` cuts at keyframes.
+    #[serde(default)]
+    pub precise_cut: Option<String>,
+    /// Segmenting and automatic stop for live recordings.
+    #[serde(default)]
+    pub recording_limits: RecordingLimits,
+    /// Cookie profile to authenticate with instead of the auth settings.
+    #[serde(default)]
+    pub cookie_profile_id: Option<String>,
+}
+
+/// Live recording output limits. Zero disables a limit.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RecordingLimits {
+    /// Start a new segment every N minutes.
+    pub split_every_minutes: u32,
+    /// Start a new segment every N GB written.
+    pub split_every_gb: f64,
+    /// Stop recording after N minutes.
+    pub max_duration_minutes: u32,
+    /// Stop recording after N GB.
+    pub max_size_gb: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -2264,7 +2441,7 @@ pub struct TimeRange {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DownloadProgress {
     pub task_id: String,
-    pub status: String, // "queued", "downloading", "recording", "processing", "completed", "failed", "cancelled", "paused", "stream_interrupted"
+    pub status: String, // "queued", "downloading", "recording", "reconnecting", "processing", "completed", "failed", "cancelled", "paused", "stream_interrupted"
     pub title: String,
     pub percentage: f64,
     pub speed: String,
@@ -2277,6 +2454,22 @@ pub struct DownloadProgress {
     pub is_recording: Option<bool>,
     pub recorded_duration: Option<String>,
     pub bitrate: Option<String>,
+    // Retry state ("retrying" status): current retry number and the configured maximum
+    #[serde(default)]
+    pub retry_attempt: Option<u32>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    // Download queue: higher priority starts first; position is 1-based while queued
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub queue_position: Option<u32>,
+    // Post-processing ("processing" status): current step, "remux" or "verify"
+    #[serde(default)]
+    pub processing_step: Option<String>,
+    // Segmented recordings: files written so far, in order
+    #[serde(default)]
+    pub segments: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -2294,16 +2487,97 @@ pub struct DownloadHistoryEntry {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub error_message: Option<String>,
+    /// Every run of the task, including retried failures.
+    #[serde(default)]
+    pub attempts: Vec<DownloadAttempt>,
+    /// Files of a segmented recording, in order; `file_path` is the first.
+    #[serde(default)]
+    pub segments: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadAttempt {
+    pub attempt: u32,
+    pub started_at: String,
+    pub ended_at: String,
+    pub status: String, // "completed", "failed", "cancelled", "stream_interrupted"
+    pub error_message: Option<String>,
 }
 
 struct DownloadTask {
     config: DownloadConfig,
     progress: DownloadProgress,
-    process: Option<Child>,
+    /// Pid of the running yt-dlp or ffmpeg process. The executor owns the
+    /// process; pause and stop signal it through this id.
+    process_id: Option<u32>,
+    /// Cancelled to kill the running process. Runs stopped while the task
+    /// goes on (pause fallback, bandwidth restart) leave a fresh token.
+    cancel: CancellationToken,
     paused: bool,
+    /// The process is stopped in place (SIGSTOP). The queue continues it
+    /// once the task is given a download slot again.
+    suspended: bool,
+    /// `--limit-rate` (bytes/s) the current process was started with.
+    rate_limit: Option<u64>,
+    /// 1-based number of the current attempt (retries increment it).
+    attempt: u32,
+    attempt_started_at: String,
+    /// Finished attempts, written to history with the final entry.
+    attempts: Vec<DownloadAttempt>,
+    /// FIFO position within the same priority in the download queue.
+    queue_seq: u64,
+    /// Set for recordings started by a scheduled preset.
+    scheduled_task_id: Option<String>,
+}
+
+impl DownloadTask {
+    fn new(config: DownloadConfig, progress: DownloadProgress) -> Self {
+        Self {
+            config,
+            progress,
+            process_id: None,
+            cancel: CancellationToken::new(),
+            paused: false,
+            suspended: false,
+            rate_limit: None,
+            attempt: 1,
+            attempt_started_at: Utc::now().to_rfc3339(),
+            attempts: Vec::new(),
+            queue_seq: next_queue_seq(),
+            scheduled_task_id: None,
+        }
+    }
+
+    /// Close the current attempt and return the full attempt log.
+    fn finish_attempt(&mut self, status: &str, error: Option<&str>) -> Vec<DownloadAttempt> {
+        self.attempts.push(DownloadAttempt {
+            attempt: self.attempt,
+            started_at: self.attempt_started_at.clone(),
+            ended_at: Utc::now().to_rfc3339(),
+            status: status.to_string(),
+            error_message: error.map(String::from),
+        });
+        self.attempts.clone()
+    }
+
+    /// Kill the running process without ending the task; the next run gets
+    /// a fresh token.
+    fn interrupt_run(&mut self) {
+        std::mem::replace(&mut self.cancel, CancellationToken::new()).cancel();
+        self.process_id = None;
+    }
+}
+
+/// Close the current attempt of a task (see `DownloadTask::finish_attempt`).
+async fn finish_task_attempt(tasks: &DownloadTasks, task_id: &str, status: &str, error: Option<&str>) -> Vec<DownloadAttempt> {
+    let mut tasks_guard = tasks.lock().await;
+    tasks_guard
+        .get_mut(task_id)
+        .map(|task| task.finish_attempt(status, error))
+        .unwrap_or_default()
 }
 
-type DownloadTasks = Arc<Mutex<HashMap<String, DownloadTask>>>;
+type DownloadTasks = Arc<tokio::sync::Mutex<HashMap<String, DownloadTask>>>;
 
 /// State for tray menu items, allowing dynamic label updates on language change (F9.3).
 struct TrayMenuItems {
@@ -2340,10 +2614,20 @@ struct AppConfig {
     // Download settings
     #[serde(default)]
     enable_transcoder: bool,
+    #[serde(default = "default_transcode_codec")]
+    transcode_codec: String,
+    #[serde(default = "default_transcode_crf")]
+    transcode_crf: u32,
+    #[serde(default)]
+    transcode_bitrate_kbps: u32, // 0 = constant quality (transcode_crf)
+    #[serde(default = "default_transcode_resolution")]
+    transcode_resolution: String,
     #[serde(default = "default_video_quality")]
     default_video_quality: String,
     #[serde(default = "default_output_container")]
     output_container: String,
+    #[serde(default = "default_precise_cut_mode")]
+    precise_cut_mode: String, // "off" | "boundaries" | "full"
     #[serde(default = "default_max_concurrent_downloads")]
     max_concurrent_downloads: usize,
     #[serde(default = "default_true")]
@@ -2351,7 +2635,23 @@ struct AppConfig {
     #[serde(default = "default_max_retry_count")]
     max_retry_count: u32,
     #[serde(default)]
-    download_speed_limit: u32, // MB/s, 0 = unlimited
+    download_speed_limit: u32, // MB/s, 0 = unlimited, shared by all active tasks
+    #[serde(default = "default_true")]
+    prioritize_live_recordings: bool,
+    #[serde(default)]
+    auto_resume_downloads: bool,
+    // Live recording post-processing steps
+    #[serde(default = "default_true")]
+    recording_remux: bool,
+    #[serde(default = "default_true")]
+    recording_faststart: bool,
+    #[serde(default = "default_true")]
+    recording_verify: bool,
+    // Keep polling a dropped live stream and continue in a new part
+    #[serde(default = "default_recording_reconnect_grace_minutes")]
+    recording_reconnect_grace_minutes: u32, // 0 = do not reconnect
+    #[serde(default = "default_true")]
+    recording_reconnect_concat: bool,
     #[serde(default)]
     show_codec_options: bool,
 
@@ -2465,6 +2765,26 @@ fn default_clip_offset() -> u32 {
     10
 }
 
+fn default_transcode_codec() -> String {
+    "h264".to_string()
+}
+
+fn default_transcode_crf() -> u32 {
+    23
+}
+
+fn default_transcode_resolution() -> String {
+    "original".to_string()
+}
+
+fn default_recording_reconnect_grace_minutes() -> u32 {
+    5
+}
+
+fn default_precise_cut_mode() -> String {
+    "off".to_string()
+}
+
 fn default_hardware_encoder() -> String {
     "auto".to_string()
 }
@@ -2493,7 +2813,7 @@ fn default_video_cache_count() -> u32 {
     5
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AuthConfig {
     pub twitch_token: Option<String>,
     pub youtube_cookies_path: Option<String>,
@@ -2502,6 +2822,39 @@ pub struct AuthConfig {
     pub elevenlabs_api_key: Option<String>,
 }
 
+/// A named set of cookies for one account on one platform: an exported
+/// Netscape cookies file, or a browser profile yt-dlp reads the cookies from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CookieProfile {
+    pub id: String,
+    pub name: String,
+    pub platform: String, // "youtube" | "twitch"
+    pub source: String,   // "file" | "browser"
+    #[serde(default)]
+    pub cookies_path: Option<String>,
+    /// `--cookies-from-browser` argument, e.g. "firefox" or "chrome:Profile 1".
+    #[serde(default)]
+    pub browser: Option<String>,
+    pub created_at: String, // ISO 8601
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AuthCookieExpiry {
+    pub name: String,
+    pub domain: String,
+    pub expires_at: Option<String>, // ISO 8601, None for session cookies
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CookieProfileStatus {
+    pub profile_id: String,
+    /// "valid" | "expiring" | "expired" | "no_auth_cookies" | "unreadable" |
+    /// "unchecked" (browser sources, whose cookie store is not read here)
+    pub status: String,
+    /// Auth cookies found in the file, soonest-expiring first.
+    pub auth_cookies: Vec<AuthCookieExpiry>,
+}
+
 // Records management structures
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Record {
@@ -2644,6 +2997,10 @@ pub struct DownloadPreset {
     pub created_at: String,       // ISO 8601
     pub last_triggered_at: Option<String>,
     pub trigger_count: u32,
+    #[serde(default)]
+    pub recording_limits: RecordingLimits,
+    #[serde(default)]
+    pub cookie_profile_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -2694,12 +3051,24 @@ impl Default for AppConfig {
             language: default_language(),
             timezone: default_timezone(),
             enable_transcoder: false,
+            transcode_codec: default_transcode_codec(),
+            transcode_crf: default_transcode_crf(),
+            transcode_bitrate_kbps: 0,
+            transcode_resolution: default_transcode_resolution(),
             default_video_quality: default_video_quality(),
             output_container: default_output_container(),
+            precise_cut_mode: default_precise_cut_mode(),
             max_concurrent_downloads: default_max_concurrent_downloads(),
             auto_retry: true,
             max_retry_count: default_max_retry_count(),
             download_speed_limit: 0,
+            prioritize_live_recordings: true,
+            auto_resume_downloads: false,
+            recording_remux: true,
+            recording_faststart: true,
+            recording_verify: true,
+            recording_reconnect_grace_minutes: default_recording_reconnect_grace_minutes(),
+            recording_reconnect_concat: true,
             show_codec_options: false,
             theme: default_theme(),
             animation: true,
@@ -3149,10 +3518,20 @@ fn run_config_migration(app: AppHandle) -> Result<MigrationResult, String> {
     let history_path = tidemark_dir.join("history.json");
     let (_, history_corrupted) = migrate_versioned_array::<DownloadHistoryEntry>(&history_path);
 
+    // In-flight task snapshots are disposable; a corrupt file just restores nothing.
+    let _ = migrate_versioned_array::<PersistedDownloadTask>(&tidemark_dir.join("download_tasks.json"));
+    let _ = migrate_versioned_array::<ScheduledDownloadTask>(&tidemark_dir.join("scheduled_queue.json"));
+
     // Migrate records.json (equivalent of records.db _meta config_version)
     let records_path = tidemark_dir.join("records.json");
     migrate_records_data(&records_path);
 
+    // Move plaintext tokens and API keys into the encrypted secret store. A
+    // locked passphrase store is retried on the next start.
+    if let Err(e) = migrate_plaintext_secrets(&tidemark_dir) {
+        log::warn!("[Migration] Secrets not migrated: {}", e);
+    }
+
     Ok(MigrationResult {
         config_corrupted,
         presets_corrupted,
@@ -3186,6 +3565,19 @@ fn get_auth_config_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(tidemark_dir.join("auth_config.json"))
 }
 
+fn get_cookie_profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+
+    let tidemark_dir = app_data_dir.join("tidemark");
+    fs::create_dir_all(&tidemark_dir)
+        .map_err(|e| format!("Failed to create tidemark dir: {}", e))?;
+
+    Ok(tidemark_dir.join("cookie_profiles.json"))
+}
+
 fn get_records_path(app: &AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app
         .path()
@@ -3225,37 +3617,380 @@ fn get_scheduled_presets_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(tidemark_dir.join("scheduled_presets.json"))
 }
 
-#[tauri::command]
-fn load_config(app: AppHandle) -> Result<AppConfig, String> {
-    let config_path = get_config_path(&app)?;
-
-    if !config_path.exists() {
-        return Ok(AppConfig::default());
-    }
+fn get_secret_store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
+    let tidemark_dir = app_data_dir.join("tidemark");
+    fs::create_dir_all(&tidemark_dir)
+        .map_err(|e| format!("Failed to create tidemark dir: {}", e))?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))
+    Ok(tidemark_dir.join("secret_store.json"))
 }
 
-#[tauri::command]
-fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
-    let config_path = get_config_path(&app)?;
-
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+fn get_secret_key_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_secret_store_path(app)?.with_file_name("secret_store.key"))
+}
 
-    fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
+// ── Secret store ──────────────────────────────────────────────────────────────
 
-    Ok(())
+/// Secrets kept out of the plaintext config files. `auth_config.json` and
+/// `sync_state.json` are written with these fields cleared.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+struct Secrets {
+    twitch_token: Option<String>,
+    openai_api_key: Option<String>,
+    groq_api_key: Option<String>,
+    elevenlabs_api_key: Option<String>,
+    sync_jwt: Option<String>,
 }
 
-// Video info structures
+/// On-disk secret store: the `Secrets` JSON sealed with AES-256-GCM.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct VideoQuality {
+struct SecretStoreFile {
+    version: u32,
+    /// "keyfile" (random key in `secret_store.key`) or "passphrase" (PBKDF2).
+    key_source: String,
+    #[serde(default)]
+    salt: Option<String>, // base64, passphrase only
+    #[serde(default)]
+    iterations: Option<u32>,
+    nonce: String,      // base64
+    ciphertext: String, // base64
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretStoreStatus {
+    pub key_source: String, // "keyfile" | "passphrase"
+    /// A passphrase store stays locked until `unlock_secret_store`.
+    pub locked: bool,
+}
+
+const SECRET_STORE_VERSION: u32 = 1;
+const SECRET_KEY_LEN: usize = 32;
+const PASSPHRASE_ITERATIONS: u32 = 600_000;
+
+/// Key of the secret store for this session, once read or unlocked.
+static SECRET_KEY: std::sync::Mutex<Option<[u8; SECRET_KEY_LEN]>> = std::sync::Mutex::new(None);
+
+/// Set once the user has been told that the store is locked this session.
+static SECRET_LOCKED_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Shows a toast, once per session, when secrets could not be read because
+/// the passphrase store is still locked: Twitch, PubSub and sync keep running
+/// signed out until it is unlocked.
+fn warn_if_secret_store_locked(app: &AppHandle, error: &str) {
+    if error != "errors.secrets.locked" || SECRET_LOCKED_WARNED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    app.emit(
+        "scheduled-notification-toast",
+        serde_json::json!({
+            "title": { "key": "backend.notification.secretsLockedTitle" },
+            "body": { "key": "backend.notification.secretsLockedBody" },
+            "level": "warning",
+        }),
+    )
+    .ok();
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    use ring::rand::SecureRandom;
+    let mut bytes = [0u8; N];
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+    Ok(bytes)
+}
+
+fn derive_secret_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; SECRET_KEY_LEN] {
+    let mut key = [0u8; SECRET_KEY_LEN];
+    let iterations = std::num::NonZeroU32::new(iterations.max(1)).unwrap();
+    ring::pbkdf2::derive(ring::pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    key
+}
+
+/// `derive_secret_key` on the blocking pool: at `PASSPHRASE_ITERATIONS` it
+/// takes long enough to stall the async runtime.
+async fn derive_secret_key_blocking(passphrase: String, salt: Vec<u8>, iterations: u32) -> Result<[u8; SECRET_KEY_LEN], String> {
+    tokio::task::spawn_blocking(move || derive_secret_key(&passphrase, &salt, iterations))
+        .await
+        .map_err(|e| format!("Key derivation failed: {}", e))
+}
+
+/// Encrypts `secrets`, returning the base64 nonce and ciphertext.
+fn seal_secrets(key: &[u8; SECRET_KEY_LEN], secrets: &Secrets) -> Result<(String, String), String> {
+    use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+    let sealing_key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| "Invalid secret key".to_string())?);
+    let nonce = random_bytes::<12>()?;
+    let mut data = serde_json::to_vec(secrets)
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    sealing_key
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| "Failed to encrypt secrets".to_string())?;
+    Ok((BASE64.encode(nonce), BASE64.encode(data)))
+}
+
+/// Decrypts a sealed `Secrets`. Fails on a wrong key or a tampered file.
+fn open_secrets(key: &[u8; SECRET_KEY_LEN], nonce: &str, ciphertext: &str) -> Result<Secrets, String> {
+    use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+    let opening_key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| "Invalid secret key".to_string())?);
+    let nonce = BASE64.decode(nonce).ok()
+        .and_then(|n| Nonce::try_assume_unique_for_key(&n).ok())
+        .ok_or_else(|| "errors.secrets.corrupted".to_string())?;
+    let mut data = BASE64.decode(ciphertext).map_err(|_| "errors.secrets.corrupted".to_string())?;
+    let plaintext = opening_key
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| "errors.secrets.decrypt_failed".to_string())?;
+    serde_json::from_slice(plaintext).map_err(|_| "errors.secrets.corrupted".to_string())
+}
+
+/// Writes `contents` readable by the current user only (0600 on Unix),
+/// through a temporary file so a crash never leaves a torn store behind.
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
+fn read_secret_store_file(app: &AppHandle) -> Result<Option<SecretStoreFile>, String> {
+    read_secret_store_at(&get_secret_store_path(app)?)
+}
+
+fn read_secret_store_at(path: &Path) -> Result<Option<SecretStoreFile>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read secret store: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|_| "errors.secrets.corrupted".to_string())
+}
+
+/// The machine-local key, created on first use. Any failure other than a
+/// missing file is returned, since replacing the key would orphan the store.
+fn read_or_create_key_file(path: &Path) -> Result<[u8; SECRET_KEY_LEN], String> {
+    match fs::read(path) {
+        Ok(bytes) => return bytes.try_into().map_err(|_| "errors.secrets.corrupted".to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to read secret key: {}", e)),
+    }
+    let key = random_bytes::<SECRET_KEY_LEN>()?;
+    write_private_file(path, &key)
+        .map_err(|e| format!("Failed to write secret key: {}", e))?;
+    Ok(key)
+}
+
+/// The key for the store's current key source. A passphrase store must have
+/// been unlocked this session.
+fn secret_store_key(key_path: &Path, store: Option<&SecretStoreFile>) -> Result<[u8; SECRET_KEY_LEN], String> {
+    if let Some(key) = *SECRET_KEY.lock().unwrap() {
+        return Ok(key);
+    }
+    if store.is_some_and(|s| s.key_source == "passphrase") {
+        return Err("errors.secrets.locked".to_string());
+    }
+    let key = read_or_create_key_file(key_path)?;
+    *SECRET_KEY.lock().unwrap() = Some(key);
+    Ok(key)
+}
+
+fn load_secrets(app: &AppHandle) -> Result<Secrets, String> {
+    load_secrets_at(&get_secret_store_path(app)?, &get_secret_key_path(app)?)
+}
+
+fn load_secrets_at(store_path: &Path, key_path: &Path) -> Result<Secrets, String> {
+    let Some(store) = read_secret_store_at(store_path)? else {
+        return Ok(Secrets::default());
+    };
+    let key = secret_store_key(key_path, Some(&store))?;
+    open_secrets(&key, &store.nonce, &store.ciphertext)
+}
+
+fn save_secrets(app: &AppHandle, secrets: &Secrets) -> Result<(), String> {
+    save_secrets_at(&get_secret_store_path(app)?, &get_secret_key_path(app)?, secrets)
+}
+
+/// Seals `secrets` with the store's current key source, or the key file for
+/// a new store.
+fn save_secrets_at(store_path: &Path, key_path: &Path, secrets: &Secrets) -> Result<(), String> {
+    let existing = read_secret_store_at(store_path)?;
+    let key = secret_store_key(key_path, existing.as_ref())?;
+    let (nonce, ciphertext) = seal_secrets(&key, secrets)?;
+    let store = match existing {
+        Some(existing) => SecretStoreFile { nonce, ciphertext, ..existing },
+        None => SecretStoreFile {
+            version: SECRET_STORE_VERSION,
+            key_source: "keyfile".to_string(),
+            salt: None,
+            iterations: None,
+            nonce,
+            ciphertext,
+        },
+    };
+    write_secret_store_at(store_path, &store)
+}
+
+fn write_secret_store_file(app: &AppHandle, store: &SecretStoreFile) -> Result<(), String> {
+    write_secret_store_at(&get_secret_store_path(app)?, store)
+}
+
+fn write_secret_store_at(path: &Path, store: &SecretStoreFile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize secret store: {}", e))?;
+    write_private_file(path, content.as_bytes())
+        .map_err(|e| format!("Failed to write secret store: {}", e))
+}
+
+/// The auth config with its secrets filled in from the secret store. Secrets
+/// still in the plaintext file (not yet migrated) are used as a fallback.
+fn read_auth_config(app: &AppHandle) -> Result<AuthConfig, String> {
+    let auth_config_path = get_auth_config_path(app)?;
+    let mut config = if auth_config_path.exists() {
+        let content = fs::read_to_string(&auth_config_path)
+            .map_err(|e| format!("Failed to read auth config: {}", e))?;
+        serde_json::from_str::<AuthConfig>(&content)
+            .map_err(|e| format!("Failed to parse auth config: {}", e))?
+    } else {
+        AuthConfig::default()
+    };
+
+    match load_secrets(app) {
+        Ok(secrets) => {
+            config.twitch_token = secrets.twitch_token.or(config.twitch_token);
+            config.openai_api_key = secrets.openai_api_key.or(config.openai_api_key);
+            config.groq_api_key = secrets.groq_api_key.or(config.groq_api_key);
+            config.elevenlabs_api_key = secrets.elevenlabs_api_key.or(config.elevenlabs_api_key);
+        }
+        Err(e) => {
+            log::warn!("[Secrets] Auth secrets unavailable: {}", e);
+            warn_if_secret_store_locked(app, &e);
+        }
+    }
+    Ok(config)
+}
+
+/// Saves the auth config: secrets into the secret store, the rest into
+/// `auth_config.json`.
+fn write_auth_config(app: &AppHandle, config: &AuthConfig) -> Result<(), String> {
+    let mut secrets = load_secrets(app)?;
+    secrets.twitch_token = config.twitch_token.clone();
+    secrets.openai_api_key = config.openai_api_key.clone();
+    secrets.groq_api_key = config.groq_api_key.clone();
+    secrets.elevenlabs_api_key = config.elevenlabs_api_key.clone();
+    save_secrets(app, &secrets)?;
+
+    write_public_auth_config(&get_auth_config_path(app)?, config)
+}
+
+/// Writes `auth_config.json` with the secrets left out.
+fn write_public_auth_config(path: &Path, config: &AuthConfig) -> Result<(), String> {
+    let public = AuthConfig {
+        youtube_cookies_path: config.youtube_cookies_path.clone(),
+        ..AuthConfig::default()
+    };
+    let content = serde_json::to_string_pretty(&public)
+        .map_err(|e| format!("Failed to serialize auth config: {}", e))?;
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write auth config: {}", e))
+}
+
+/// Moves secrets still stored in plaintext (`auth_config.json`,
+/// `sync_state.json`) in `tidemark_dir` into its secret store. No backup is
+/// taken, since that would leave the plaintext copy on disk.
+fn migrate_plaintext_secrets(tidemark_dir: &Path) -> Result<(), String> {
+    let auth_config_path = tidemark_dir.join("auth_config.json");
+    let sync_state_path = tidemark_dir.join("sync_state.json");
+    let store_path = tidemark_dir.join("secret_store.json");
+    let key_path = tidemark_dir.join("secret_store.key");
+    let auth: Option<AuthConfig> = fs::read_to_string(&auth_config_path).ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    let sync: Option<SyncState> = fs::read_to_string(&sync_state_path).ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    let auth_has_secrets = auth.as_ref().is_some_and(|a| {
+        a.twitch_token.is_some() || a.openai_api_key.is_some()
+            || a.groq_api_key.is_some() || a.elevenlabs_api_key.is_some()
+    });
+    let sync_has_secrets = sync.as_ref().is_some_and(|s| s.jwt.is_some());
+    if !auth_has_secrets && !sync_has_secrets {
+        return Ok(());
+    }
+
+    let mut secrets = load_secrets_at(&store_path, &key_path)?;
+    if let Some(ref auth) = auth {
+        secrets.twitch_token = auth.twitch_token.clone().or(secrets.twitch_token);
+        secrets.openai_api_key = auth.openai_api_key.clone().or(secrets.openai_api_key);
+        secrets.groq_api_key = auth.groq_api_key.clone().or(secrets.groq_api_key);
+        secrets.elevenlabs_api_key = auth.elevenlabs_api_key.clone().or(secrets.elevenlabs_api_key);
+    }
+    if let Some(ref sync) = sync {
+        secrets.sync_jwt = sync.jwt.clone().or(secrets.sync_jwt);
+    }
+    save_secrets_at(&store_path, &key_path, &secrets)?;
+
+    if let (true, Some(auth)) = (auth_has_secrets, auth) {
+        write_public_auth_config(&auth_config_path, &auth)?;
+    }
+    if let (true, Some(sync)) = (sync_has_secrets, sync) {
+        write_public_sync_state(&sync_state_path, &sync)?;
+    }
+    log::info!("[Secrets] Moved plaintext secrets into the secret store");
+    Ok(())
+}
+
+#[tauri::command]
+fn load_config(app: AppHandle) -> Result<AppConfig, String> {
+    let config_path = get_config_path(&app)?;
+
+    if !config_path.exists() {
+        return Ok(AppConfig::default());
+    }
+
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse config: {}", e))
+}
+
+#[tauri::command]
+fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
+    let config_path = get_config_path(&app)?;
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    fs::write(&config_path, content)
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+
+    Ok(())
+}
+
+// Video info structures
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VideoQuality {
     pub format_id: String,
     pub quality: String,
     pub ext: String,
@@ -3275,232 +4010,302 @@ pub struct VideoInfo {
     pub content_type: String,
     pub is_live: bool,
     pub qualities: Vec<VideoQuality>,
+    /// Publish time: RFC 3339, or a bare date when only that is known.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Start offset in seconds embedded in the URL (`t=`/`start=`).
+    #[serde(default)]
+    pub start_time: Option<i64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Platform {
     YouTube,
     Twitch,
+    /// Any other site; the URL is handed to yt-dlp as-is.
+    Other,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ContentType {
     Video,
     Stream,
     Clip,
+    Playlist,
+    /// A channel's uploads (YouTube) or past broadcasts (Twitch).
+    Channel,
 }
 
+#[derive(Debug)]
 pub struct ParsedUrl {
     pub platform: Platform,
     pub content_type: ContentType,
+    /// Video/clip/playlist id, channel for Twitch streams, the channel path
+    /// (`@handle`, `channel/UC...`) for YouTube channel live pages, or the
+    /// full URL for `Platform::Other`.
     pub id: String,
+    /// Start offset in seconds from a `t=` or `start=` parameter.
+    pub start_time: Option<i64>,
+}
+
+/// Value of a query (or fragment) parameter, e.g. `t` in `?v=abc&t=90`.
+fn url_query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let params = &url[url.find(['?', '#'])? + 1..];
+    params
+        .split(['&', '?', '#'])
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Parse a URL timestamp such as `90`, `90s`, `1m30s` or `1h2m3s` into seconds.
+fn parse_url_timestamp(value: &str) -> Option<i64> {
+    if value.is_empty() {
+        return None;
+    }
+    let re = Regex::new(r"^(?:(\d+)h)?(?:(\d+)m)?(?:(\d+)s?)?$").unwrap();
+    let caps = re.captures(value)?;
+    let part = |i: usize| caps.get(i).and_then(|m| m.as_str().parse::<i64>().ok()).unwrap_or(0);
+    Some(part(1) * 3600 + part(2) * 60 + part(3))
+}
+
+fn is_youtube_video_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
 fn parse_url(url: &str) -> Result<ParsedUrl, String> {
     let url = url.trim();
+    let start_time = url_query_param(url, "t")
+        .or_else(|| url_query_param(url, "start"))
+        .and_then(parse_url_timestamp);
 
-    // YouTube patterns
-    let youtube_watch = Regex::new(r"(?:youtube\.com|youtu\.be)/watch\?v=([a-zA-Z0-9_-]{11})").unwrap();
+    let parsed = |platform: Platform, content_type: ContentType, id: &str| ParsedUrl {
+        platform,
+        content_type,
+        id: id.to_string(),
+        start_time,
+    };
+
+    // YouTube patterns (www., m. and music. hosts all contain "youtube.com")
+    let youtube_watch = Regex::new(r"youtube\.com/watch\?(?:[^#]*&)?v=([a-zA-Z0-9_-]{11})").unwrap();
     let youtube_short = Regex::new(r"youtu\.be/([a-zA-Z0-9_-]{11})").unwrap();
+    let youtube_shorts = Regex::new(r"youtube\.com/(?:shorts|embed)/([a-zA-Z0-9_-]{11})").unwrap();
     let youtube_live = Regex::new(r"youtube\.com/live/([a-zA-Z0-9_-]{11})").unwrap();
+    let youtube_channel_live = Regex::new(r"youtube\.com/((?:@|channel/|c/)[^/?#]+)/live(?:[/?#]|$)").unwrap();
+    let youtube_playlist = Regex::new(r"youtube\.com/playlist\?(?:[^#]*&)?list=([a-zA-Z0-9_-]+)").unwrap();
+    let youtube_channel = Regex::new(r"youtube\.com/((?:@|channel/|c/|user/)[^/?#]+)(?:/(?:videos|streams|shorts|featured))?/?(?:[?#]|$)").unwrap();
 
     // Twitch patterns
-    let twitch_video = Regex::new(r"twitch\.tv/videos/(\d+)").unwrap();
-    let twitch_clip = Regex::new(r"(?:twitch\.tv/[^/]+/clip/([a-zA-Z0-9_-]+)|clips\.twitch\.tv/([a-zA-Z0-9_-]+))").unwrap();
-    let twitch_channel = Regex::new(r"twitch\.tv/([a-zA-Z0-9_-]+)(?:/|$)").unwrap();
+    let twitch_video = Regex::new(r"twitch\.tv/(?:[^/?#]+/)?videos?/(\d+)").unwrap();
+    let twitch_clip = Regex::new(r"(?:twitch\.tv/(?:[^/?#]+/)?clip/([a-zA-Z0-9_-]+)|clips\.twitch\.tv/([a-zA-Z0-9_-]+))").unwrap();
+    let twitch_channel_videos = Regex::new(r"twitch\.tv/([a-zA-Z0-9_-]+)/videos/?(?:[?#]|$)").unwrap();
+    let twitch_channel = Regex::new(r"twitch\.tv/([a-zA-Z0-9_-]+)(?:[/?#]|$)").unwrap();
+
+    // Anything else yt-dlp may support
+    let generic_url = Regex::new(r"^https?://[^\s/$.?#][^\s]*$").unwrap();
 
     // Check YouTube
-    if let Some(caps) = youtube_watch.captures(url).or_else(|| youtube_short.captures(url)) {
-        return Ok(ParsedUrl {
-            platform: Platform::YouTube,
-            content_type: ContentType::Video,
-            id: caps[1].to_string(),
-        });
+    if let Some(caps) = youtube_watch.captures(url)
+        .or_else(|| youtube_short.captures(url))
+        .or_else(|| youtube_shorts.captures(url))
+    {
+        return Ok(parsed(Platform::YouTube, ContentType::Video, &caps[1]));
     }
 
-    if let Some(caps) = youtube_live.captures(url) {
-        return Ok(ParsedUrl {
-            platform: Platform::YouTube,
-            content_type: ContentType::Stream,
-            id: caps[1].to_string(),
-        });
+    if let Some(caps) = youtube_live.captures(url).or_else(|| youtube_channel_live.captures(url)) {
+        return Ok(parsed(Platform::YouTube, ContentType::Stream, &caps[1]));
+    }
+
+    if let Some(caps) = youtube_playlist.captures(url) {
+        return Ok(parsed(Platform::YouTube, ContentType::Playlist, &caps[1]));
+    }
+
+    if let Some(caps) = youtube_channel.captures(url) {
+        return Ok(parsed(Platform::YouTube, ContentType::Channel, &caps[1]));
     }
 
     // Check Twitch
     if let Some(caps) = twitch_video.captures(url) {
-        return Ok(ParsedUrl {
-            platform: Platform::Twitch,
-            content_type: ContentType::Video,
-            id: caps[1].to_string(),
-        });
+        return Ok(parsed(Platform::Twitch, ContentType::Video, &caps[1]));
     }
 
     if let Some(caps) = twitch_clip.captures(url) {
         let clip_id = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
-        return Ok(ParsedUrl {
-            platform: Platform::Twitch,
-            content_type: ContentType::Clip,
-            id: clip_id.to_string(),
-        });
+        return Ok(parsed(Platform::Twitch, ContentType::Clip, clip_id));
+    }
+
+    if let Some(caps) = twitch_channel_videos.captures(url) {
+        return Ok(parsed(Platform::Twitch, ContentType::Channel, &caps[1]));
     }
 
     if let Some(caps) = twitch_channel.captures(url) {
-        return Ok(ParsedUrl {
-            platform: Platform::Twitch,
-            content_type: ContentType::Stream,
-            id: caps[1].to_string(),
-        });
+        return Ok(parsed(Platform::Twitch, ContentType::Stream, &caps[1]));
+    }
+
+    if generic_url.is_match(url) {
+        return Ok(parsed(Platform::Other, ContentType::Video, url));
     }
 
     Err("不支援的連結格式".to_string())
 }
 
-async fn fetch_youtube_info(video_id: &str) -> Result<VideoInfo, String> {
-    // Try to use yt-dlp to fetch metadata
-    let output = Command::new("yt-dlp")
-        .args([
-            "--dump-json",
-            "--no-playlist",
-            &format!("https://www.youtube.com/watch?v={}", video_id),
-        ])
+/// Run `yt-dlp --dump-json` for a single video and parse the result.
+async fn ytdlp_dump_json(url: &str, auth_args: &[String]) -> Result<serde_json::Value, String> {
+    let output = TokioCommand::new("yt-dlp")
+        .args(["--dump-json", "--no-playlist"])
+        .args(auth_args)
+        .arg(url)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .output();
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "找不到 yt-dlp，請安裝後再試".to_string())?;
 
-    match output {
-        Ok(result) => {
-            if !result.status.success() {
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                if stderr.contains("Video unavailable") || stderr.contains("Private video") {
-                    return Err("errors.download.not_found".to_string());
-                }
-                return Err(format!("yt-dlp error: {}", stderr));
-            }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if ytdlp_failure_key(&stderr) == Some("twitch_auth_failed") {
+            return Err("errors.download.twitch_auth_failed".to_string());
+        }
+        if stderr.contains("Video unavailable")
+            || stderr.contains("Private video")
+            || stderr.contains("does not exist")
+            || stderr.contains("HTTP Error 404")
+        {
+            return Err("errors.download.not_found".to_string());
+        }
+        return Err(format!("yt-dlp error: {}", stderr));
+    }
 
-            let json_str = String::from_utf8_lossy(&result.stdout);
-            let json: serde_json::Value = serde_json::from_str(&json_str)
-                .map_err(|e| format!("Failed to parse yt-dlp output: {}", e))?;
+    let json_str = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse yt-dlp output: {}", e))
+}
 
-            let is_live = json.get("is_live")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-
-            let mut qualities = Vec::new();
-            if let Some(formats) = json.get("formats").and_then(|v| v.as_array()) {
-                for format in formats {
-                    if let (Some(format_id), Some(ext)) = (
-                        format.get("format_id").and_then(|v| v.as_str()),
-                        format.get("ext").and_then(|v| v.as_str()),
-                    ) {
-                        let quality = format.get("format_note")
-                            .and_then(|v| v.as_str())
-                            .or_else(|| format.get("height").and_then(|v| v.as_i64()).map(|h| {
-                                if h >= 2160 { "4K" }
-                                else if h >= 1440 { "1440p" }
-                                else if h >= 1080 { "1080p" }
-                                else if h >= 720 { "720p" }
-                                else if h >= 480 { "480p" }
-                                else { "360p" }
-                            }))
-                            .unwrap_or("audio only");
-
-                        qualities.push(VideoQuality {
-                            format_id: format_id.to_string(),
-                            quality: quality.to_string(),
-                            ext: ext.to_string(),
-                            vcodec: format.get("vcodec").and_then(|v| v.as_str()).map(String::from),
-                            acodec: format.get("acodec").and_then(|v| v.as_str()).map(String::from),
-                            filesize: format.get("filesize").and_then(|v| v.as_i64()),
-                        });
-                    }
-                }
-            }
+/// `ytdlp_dump_json` signed in with the auth settings for `platform`, so
+/// subscriber-only Twitch VODs resolve too.
+async fn signed_in_dump_json(app: &AppHandle, platform: &str, url: &str) -> Result<serde_json::Value, String> {
+    let (auth_args, _twitch_cookies) = settings_auth_args(app, platform, &Uuid::new_v4().to_string()).await;
+    ytdlp_dump_json(url, &auth_args).await
+}
 
-            Ok(VideoInfo {
-                id: video_id.to_string(),
-                title: json.get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
-                channel: json.get("uploader")
-                    .or_else(|| json.get("channel"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
-                thumbnail: json.get("thumbnail")
+/// Build `VideoInfo` from yt-dlp's `--dump-json` output.
+fn video_info_from_ytdlp_json(json: &serde_json::Value, id: &str, platform: &str, content_type: &str) -> VideoInfo {
+    let is_live = json.get("is_live")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let mut qualities = Vec::new();
+    if let Some(formats) = json.get("formats").and_then(|v| v.as_array()) {
+        for format in formats {
+            if let (Some(format_id), Some(ext)) = (
+                format.get("format_id").and_then(|v| v.as_str()),
+                format.get("ext").and_then(|v| v.as_str()),
+            ) {
+                // Twitch format ids are already readable ("1080p60", "Audio_Only").
+                let twitch_label = (platform == "twitch").then_some(format_id);
+                let quality = format.get("format_note")
                     .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                duration: json.get("duration").and_then(|v| v.as_i64()),
-                platform: "youtube".to_string(),
-                content_type: if is_live { "stream" } else { "video" }.to_string(),
-                is_live,
-                qualities,
-            })
+                    .or(twitch_label)
+                    .or_else(|| format.get("height").and_then(|v| v.as_i64()).map(|h| {
+                        if h >= 2160 { "4K" }
+                        else if h >= 1440 { "1440p" }
+                        else if h >= 1080 { "1080p" }
+                        else if h >= 720 { "720p" }
+                        else if h >= 480 { "480p" }
+                        else { "360p" }
+                    }))
+                    .unwrap_or("audio only");
+
+                qualities.push(VideoQuality {
+                    format_id: format_id.to_string(),
+                    quality: quality.to_string(),
+                    ext: ext.to_string(),
+                    vcodec: format.get("vcodec").and_then(|v| v.as_str()).map(String::from),
+                    acodec: format.get("acodec").and_then(|v| v.as_str()).map(String::from),
+                    filesize: format.get("filesize")
+                        .or_else(|| format.get("filesize_approx"))
+                        .and_then(|v| v.as_i64()),
+                });
+            }
         }
-        Err(_) => {
-            Err("找不到 yt-dlp，請安裝後再試".to_string())
+    }
+
+    // Prefer the exact timestamp; fall back to the YYYYMMDD upload date.
+    let created_at = json.get("timestamp")
+        .and_then(|v| v.as_i64())
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.to_rfc3339())
+        .or_else(|| {
+            json.get("upload_date")
+                .and_then(|v| v.as_str())
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+                .map(|d| d.format("%Y-%m-%d").to_string())
+        });
+
+    VideoInfo {
+        id: id.to_string(),
+        title: json.get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string(),
+        channel: json.get("uploader")
+            .or_else(|| json.get("channel"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string(),
+        thumbnail: json.get("thumbnail")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        // Clip durations are fractional seconds.
+        duration: json.get("duration").and_then(|v| v.as_f64()).map(|d| d.round() as i64),
+        platform: platform.to_string(),
+        content_type: if is_live { "stream" } else { content_type }.to_string(),
+        is_live,
+        qualities,
+        created_at,
+        start_time: None,
+    }
+}
+
+async fn fetch_youtube_info(content_type: &ContentType, id: &str) -> Result<VideoInfo, String> {
+    match content_type {
+        ContentType::Playlist | ContentType::Channel => Err("errors.download.is_playlist".to_string()),
+        // Channel live page: yt-dlp resolves it to the current stream's video id.
+        ContentType::Stream if !is_youtube_video_id(id) => {
+            let json = ytdlp_dump_json(&format!("https://www.youtube.com/{}/live", id), &[]).await?;
+            let video_id = json.get("id").and_then(|v| v.as_str()).unwrap_or(id).to_string();
+            Ok(video_info_from_ytdlp_json(&json, &video_id, "youtube", "stream"))
+        }
+        _ => {
+            let json = ytdlp_dump_json(&format!("https://www.youtube.com/watch?v={}", id), &[]).await?;
+            Ok(video_info_from_ytdlp_json(&json, id, "youtube", "video"))
         }
     }
 }
 
-async fn fetch_twitch_info(content_type: &ContentType, id: &str) -> Result<VideoInfo, String> {
-    // For MVP, we'll use a simplified Twitch GQL approach
-    // In production, this would need proper OAuth and GQL queries
+/// Metadata for a URL from any other site yt-dlp has an extractor for.
+async fn fetch_other_info(url: &str) -> Result<VideoInfo, String> {
+    let json = ytdlp_dump_json(url, &[]).await.map_err(|e| {
+        if e.contains("Unsupported URL") {
+            "不支援的連結格式".to_string()
+        } else {
+            e
+        }
+    })?;
+    let id = json.get("id").and_then(|v| v.as_str()).unwrap_or(url).to_string();
+    Ok(video_info_from_ytdlp_json(&json, &id, "other", "video"))
+}
 
+async fn fetch_twitch_info(app: &AppHandle, content_type: &ContentType, id: &str) -> Result<VideoInfo, String> {
     match content_type {
         ContentType::Video => {
-            // VOD
-            Ok(VideoInfo {
-                id: id.to_string(),
-                title: format!("Twitch VOD {}", id),
-                channel: "Unknown Channel".to_string(),
-                thumbnail: "".to_string(),
-                duration: None,
-                platform: "twitch".to_string(),
-                content_type: "video".to_string(),
-                is_live: false,
-                qualities: vec![
-                    VideoQuality {
-                        format_id: "source".to_string(),
-                        quality: "Source".to_string(),
-                        ext: "mp4".to_string(),
-                        vcodec: Some("h264".to_string()),
-                        acodec: Some("aac".to_string()),
-                        filesize: None,
-                    },
-                    VideoQuality {
-                        format_id: "720p60".to_string(),
-                        quality: "720p60".to_string(),
-                        ext: "mp4".to_string(),
-                        vcodec: Some("h264".to_string()),
-                        acodec: Some("aac".to_string()),
-                        filesize: None,
-                    },
-                ],
-            })
+            let json = signed_in_dump_json(app, "twitch", &format!("https://www.twitch.tv/videos/{}", id)).await?;
+            Ok(video_info_from_ytdlp_json(&json, id, "twitch", "video"))
         }
         ContentType::Clip => {
-            Ok(VideoInfo {
-                id: id.to_string(),
-                title: format!("Twitch Clip {}", id),
-                channel: "Unknown Channel".to_string(),
-                thumbnail: "".to_string(),
-                duration: None,
-                platform: "twitch".to_string(),
-                content_type: "clip".to_string(),
-                is_live: false,
-                qualities: vec![
-                    VideoQuality {
-                        format_id: "source".to_string(),
-                        quality: "Source".to_string(),
-                        ext: "mp4".to_string(),
-                        vcodec: Some("h264".to_string()),
-                        acodec: Some("aac".to_string()),
-                        filesize: None,
-                    },
-                ],
-            })
+            let json = signed_in_dump_json(app, "twitch", &format!("https://clips.twitch.tv/{}", id)).await?;
+            Ok(video_info_from_ytdlp_json(&json, id, "twitch", "clip"))
         }
         ContentType::Stream => {
             Ok(VideoInfo {
@@ -3522,576 +4327,3143 @@ async fn fetch_twitch_info(content_type: &ContentType, id: &str) -> Result<Video
                         filesize: None,
                     },
                 ],
+                created_at: None,
+                start_time: None,
             })
         }
+        ContentType::Playlist | ContentType::Channel => Err("errors.download.is_playlist".to_string()),
     }
 }
 
 #[tauri::command]
-async fn fetch_video_info(url: String) -> Result<VideoInfo, String> {
+async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo, String> {
     let parsed = parse_url(&url)?;
 
-    match parsed.platform {
-        Platform::YouTube => fetch_youtube_info(&parsed.id).await,
-        Platform::Twitch => fetch_twitch_info(&parsed.content_type, &parsed.id).await,
-    }
+    let mut info = match parsed.platform {
+        Platform::YouTube => fetch_youtube_info(&parsed.content_type, &parsed.id).await,
+        Platform::Twitch => fetch_twitch_info(&app, &parsed.content_type, &parsed.id).await,
+        Platform::Other => fetch_other_info(&parsed.id).await,
+    }?;
+    info.start_time = parsed.start_time;
+    Ok(info)
 }
 
-// Time range validation functions
-fn parse_time_to_seconds(time: &str) -> Result<i64, String> {
-    let time = time.trim();
-
-    // Pure seconds
-    if let Ok(seconds) = time.parse::<i64>() {
-        return Ok(seconds);
-    }
+// ── Playlist / Channel Bulk Download ────────────────────────────────────────
 
-    // HH:MM:SS or MM:SS
-    let parts: Vec<&str> = time.split(':').collect();
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaylistFilter {
+    /// Only entries published on or after this date (YYYY-MM-DD).
+    #[serde(default)]
+    pub date_after: Option<String>,
+    /// Only entries published on or before this date (YYYY-MM-DD).
+    #[serde(default)]
+    pub date_before: Option<String>,
+    /// Case-insensitive text the title must contain.
+    #[serde(default)]
+    pub title_contains: Option<String>,
+    /// Case-insensitive text that excludes an entry when its title contains it.
+    #[serde(default)]
+    pub title_excludes: Option<String>,
+    /// Skip entries already completed in the download history.
+    #[serde(default = "default_true")]
+    pub skip_downloaded: bool,
+    /// yt-dlp `--download-archive` file; entries listed in it are skipped.
+    #[serde(default)]
+    pub archive_file: Option<String>,
+}
 
-    match parts.len() {
-        2 => {
-            // MM:SS
-            let minutes = parts[0].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
-            let seconds = parts[1].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
-            Ok(minutes * 60 + seconds)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaylistEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub channel: String,
+    pub duration: Option<i64>,
+    /// YYYY-MM-DD, when yt-dlp reports it.
+    pub upload_date: Option<String>,
+    pub platform: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlaylistExpansion {
+    pub title: Option<String>,
+    /// Entries left after filtering and skipping, in playlist order.
+    pub entries: Vec<PlaylistEntry>,
+    pub total: usize,
+    pub skipped_downloaded: usize,
+    pub filtered_out: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaylistDownloadOptions {
+    pub quality: String, // "best", "1080p", "720p", "480p", "360p"
+    pub content_type: String,
+    pub output_folder: String,
+    pub container_format: String,
+    #[serde(default)]
+    pub filename_template: String,
+    /// Record finished downloads in this yt-dlp archive file.
+    #[serde(default)]
+    pub archive_file: Option<String>,
+}
+
+/// URL to expand for a playlist or channel link. Bare channel pages are
+/// pointed at their uploads (YouTube) or past broadcasts (Twitch) so yt-dlp
+/// lists videos rather than channel tabs.
+fn playlist_source_url(url: &str) -> String {
+    let url = url.trim();
+    match parse_url(url) {
+        Ok(ParsedUrl { platform: Platform::Twitch, content_type: ContentType::Stream, id, .. }) => {
+            format!("https://www.twitch.tv/{}/videos?filter=archives", id)
         }
-        3 => {
-            // HH:MM:SS
-            let hours = parts[0].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
-            let minutes = parts[1].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
-            let seconds = parts[2].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
-            Ok(hours * 3600 + minutes * 60 + seconds)
+        Ok(ParsedUrl { platform: Platform::YouTube, content_type: ContentType::Channel, id, .. })
+            if !Regex::new(r"/(?:videos|streams|shorts)/?(?:[?#]|$)").unwrap().is_match(url) =>
+        {
+            format!("https://www.youtube.com/{}/videos", id)
         }
-        _ => Err("請輸入有效時間格式".to_string()),
+        _ => url.to_string(),
     }
 }
 
-fn normalize_time_to_hhmmss(seconds: i64) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-    let secs = seconds % 60;
-    format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+fn platform_name(platform: &Platform) -> &'static str {
+    match platform {
+        Platform::YouTube => "youtube",
+        Platform::Twitch => "twitch",
+        Platform::Other => "other",
+    }
 }
 
-fn validate_time_range(config: &DownloadConfig) -> Result<(), String> {
-    if let Some(ref time_range) = config.time_range {
-        let start_seconds = if let Some(ref start) = time_range.start {
-            Some(parse_time_to_seconds(start)?)
-        } else {
-            None
-        };
+/// Id used to match an entry against history: the id `parse_url` extracts
+/// from its URL, so YouTube and Twitch links of any shape compare equal.
+fn canonical_video_id(url: &str) -> Option<String> {
+    parse_url(url).ok().filter(|p| p.platform != Platform::Other).map(|p| p.id)
+}
 
-        let end_seconds = if let Some(ref end) = time_range.end {
-            Some(parse_time_to_seconds(end)?)
-        } else {
-            None
+/// Entries of a yt-dlp archive file ("<extractor> <id>" per line).
+fn read_download_archive(path: &str) -> HashSet<String> {
+    let path = expand_home_dir(path);
+    fs::read_to_string(path)
+        .map(|content| content.lines().map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+fn expand_home_dir(path: &str) -> String {
+    if path.starts_with('~') {
+        if let Some(home) = std::env::var("HOME").ok().or_else(|| std::env::var("USERPROFILE").ok()) {
+            return path.replacen('~', &home, 1);
+        }
+    }
+    path.to_string()
+}
+
+/// Whether an entry passes the date and title filters. Entries without a
+/// known date are kept, since flat playlists do not always report one.
+fn playlist_entry_matches(entry: &PlaylistEntry, filter: &PlaylistFilter) -> bool {
+    let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+    if let Some(date) = entry.upload_date.as_deref() {
+        if non_empty(&filter.date_after).is_some_and(|after| date < after.as_str()) {
+            return false;
+        }
+        if non_empty(&filter.date_before).is_some_and(|before| date > before.as_str()) {
+            return false;
+        }
+    }
+
+    let title = entry.title.to_lowercase();
+    if non_empty(&filter.title_contains).is_some_and(|text| !title.contains(&text.to_lowercase())) {
+        return false;
+    }
+    if non_empty(&filter.title_excludes).is_some_and(|text| title.contains(&text.to_lowercase())) {
+        return false;
+    }
+    true
+}
+
+/// Expand a playlist or channel URL into its videos with
+/// `yt-dlp --flat-playlist`, then apply the filter and skip entries that
+/// were already downloaded.
+#[tauri::command]
+async fn expand_playlist(app: AppHandle, url: String, filter: PlaylistFilter) -> Result<PlaylistExpansion, String> {
+    let source_url = playlist_source_url(&url);
+
+    let mut args = vec!["-J", "--flat-playlist"];
+    if filter.date_after.is_some() || filter.date_before.is_some() {
+        // Flat YouTube listings only carry dates with this option.
+        args.extend(["--extractor-args", "youtubetab:approximate_date"]);
+    }
+    args.push(&source_url);
+
+    let output = TokioCommand::new("yt-dlp")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "找不到 yt-dlp，請安裝後再試".to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("does not exist") || stderr.contains("HTTP Error 404") {
+            return Err("errors.download.not_found".to_string());
+        }
+        return Err(format!("yt-dlp error: {}", stderr));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse yt-dlp output: {}", e))?;
+
+    let playlist_channel = json.get("channel")
+        .or_else(|| json.get("uploader"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+
+    let history_ids: HashSet<String> = if filter.skip_downloaded {
+        get_download_history(app.clone())
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|h| h.status == "completed")
+            .filter_map(|h| canonical_video_id(&h.url))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let archive = filter.archive_file.as_deref().map(read_download_archive).unwrap_or_default();
+
+    let raw_entries = json.get("entries").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let total = raw_entries.len();
+    let mut entries = Vec::new();
+    let mut skipped_downloaded = 0;
+    let mut filtered_out = 0;
+
+    for raw in &raw_entries {
+        let Some(raw_id) = raw.get("id").and_then(|v| v.as_str()) else { continue };
+        let Some(entry_url) = raw.get("url").or_else(|| raw.get("webpage_url")).and_then(|v| v.as_str()) else { continue };
+
+        let parsed = parse_url(entry_url).ok();
+        let upload_date = raw.get("timestamp")
+            .and_then(|v| v.as_i64())
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .or_else(|| {
+                raw.get("upload_date")
+                    .and_then(|v| v.as_str())
+                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+            });
+
+        let entry = PlaylistEntry {
+            id: parsed.as_ref().map(|p| p.id.clone()).unwrap_or_else(|| raw_id.to_string()),
+            url: entry_url.to_string(),
+            title: raw.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string(),
+            channel: raw.get("channel")
+                .or_else(|| raw.get("uploader"))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| playlist_channel.clone()),
+            duration: raw.get("duration").and_then(|v| v.as_f64()).map(|d| d.round() as i64),
+            upload_date,
+            platform: parsed.as_ref().map(|p| platform_name(&p.platform)).unwrap_or("other").to_string(),
         };
 
-        // Validate end > start
-        if let (Some(start), Some(end)) = (start_seconds, end_seconds) {
-            if end <= start {
-                return Err("errors.download.end_before_start".to_string());
-            }
+        let archive_key = raw.get("ie_key")
+            .and_then(|v| v.as_str())
+            .map(|ie| format!("{} {}", ie, raw_id).to_lowercase());
+        let in_archive = archive_key.is_some_and(|key| archive.contains(&key));
 
-            // Validate against video duration if available
-            if let Some(duration) = config.video_info.duration {
-                if start > duration {
-                    return Err("errors.download.time_exceeds_duration".to_string());
-                }
-                if end > duration {
-                    return Err("errors.download.time_exceeds_duration".to_string());
-                }
-            }
+        if in_archive || history_ids.contains(&entry.id) {
+            skipped_downloaded += 1;
+        } else if !playlist_entry_matches(&entry, &filter) {
+            filtered_out += 1;
+        } else {
+            entries.push(entry);
         }
     }
 
-    Ok(())
+    Ok(PlaylistExpansion {
+        title: json.get("title").and_then(|v| v.as_str()).map(String::from),
+        entries,
+        total,
+        skipped_downloaded,
+        filtered_out,
+    })
 }
 
-// Download management commands
+/// `{date}` and `{datetime}` for a video: its upload or stream start
+/// (RFC 3339 or YYYY-MM-DD, as in `VideoInfo::created_at`) when known,
+/// otherwise `now`.
+fn template_date_vars(created_at: Option<&str>, now: DateTime<Utc>) -> (String, String) {
+    let when = created_at
+        .map(str::trim)
+        .and_then(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|dt| dt.and_utc())
+                })
+        })
+        .unwrap_or(now);
+    (when.format("%Y-%m-%d").to_string(), when.format("%Y-%m-%d_%H%M%S").to_string())
+}
+
+fn format_template_duration(seconds: i64) -> String {
+    let (h, m, s) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if h > 0 {
+        format!("{:02}h{:02}m{:02}s", h, m, s)
+    } else {
+        format!("{:02}m{:02}s", m, s)
+    }
+}
+
+/// Queue playlist entries from `expand_playlist` as regular download tasks.
 #[tauri::command]
-async fn start_download(
+async fn enqueue_playlist_entries(
     app: AppHandle,
-    config: DownloadConfig,
+    entries: Vec<PlaylistEntry>,
+    options: PlaylistDownloadOptions,
     tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<Vec<String>, String> {
+    let config = load_config(app.clone()).unwrap_or_default();
+    let template = if options.filename_template.trim().is_empty() {
+        config.default_filename_template.clone()
+    } else {
+        options.filename_template.clone()
+    };
+    let output_folder = expand_home_dir(&options.output_folder);
+    let format_id = quality_to_format(&options.quality, &options.content_type);
+    let ext = match options.container_format.as_str() {
+        "mp4" => "mp4",
+        "mkv" => "mkv",
+        _ if options.content_type == "audio_only" => "m4a",
+        _ => "mp4",
+    };
+
+    let now = Utc::now();
+    let mut task_ids = Vec::new();
+
+    for entry in entries {
+        let mut vars = HashMap::new();
+        vars.insert("channel".to_string(), entry.channel.replace(' ', "_"));
+        vars.insert("channel_name".to_string(), entry.channel.clone());
+        vars.insert("platform".to_string(), entry.platform.clone());
+        let (date, datetime) = template_date_vars(entry.upload_date.as_deref(), now);
+        vars.insert("date".to_string(), date);
+        vars.insert("datetime".to_string(), datetime);
+        vars.insert("title".to_string(), entry.title.clone());
+        vars.insert("id".to_string(), entry.id.clone());
+        vars.insert("type".to_string(), "video".to_string());
+        vars.insert("resolution".to_string(), options.quality.clone());
+        vars.insert(
+            "duration".to_string(),
+            entry.duration.map(format_template_duration).unwrap_or_else(|| "unknown".to_string()),
+        );
+
+        let sanitized = sanitize_filename_str(&expand_variables(&template, &vars));
+        let filename = if sanitized.is_empty() { make_fallback_filename() } else { sanitized };
+
+        let download_config = DownloadConfig {
+            url: entry.url.clone(),
+            video_info: VideoInfo {
+                id: entry.id.clone(),
+                title: entry.title.clone(),
+                channel: entry.channel.clone(),
+                thumbnail: String::new(),
+                duration: entry.duration,
+                platform: entry.platform.clone(),
+                content_type: "video".to_string(),
+                is_live: false,
+                qualities: vec![],
+                created_at: entry.upload_date.clone(),
+                start_time: None,
+            },
+            format_id: format_id.clone(),
+            content_type: options.content_type.clone(),
+            video_codec: None,
+            audio_codec: None,
+            output_filename: format!("{}.{}", filename, ext),
+            output_folder: output_folder.clone(),
+            container_format: options.container_format.clone(),
+            time_range: None,
+            download_archive: options.archive_file.as_deref().map(expand_home_dir),
+            precise_cut: None,
+            recording_limits: RecordingLimits::default(),
+            cookie_profile_id: None,
+        };
+
+        task_ids.push(enqueue_download(&app, tasks.inner(), download_config).await);
+    }
+
+    process_download_queue(app, tasks.inner().clone());
+    Ok(task_ids)
+}
+
+// ============================================================
+// Record Clip Downloads
+// ============================================================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecordClipFailure {
+    pub record_id: String,
+    pub topic: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecordClipSummary {
+    pub task_ids: Vec<String>,
+    pub failures: Vec<RecordClipFailure>,
+}
+
+/// Where a record's VOD can be found: a direct video URL, or a channel
+/// archive listing to search by the record's wall-clock timestamp (the
+/// stream was still live when the record was made).
+enum RecordVodSource {
+    Video(String),
+    ChannelArchive(String),
+}
+
+fn record_vod_source(channel_url: &str) -> Result<RecordVodSource, String> {
+    let parsed = parse_url(channel_url).map_err(|_| "errors.records.unsupported_url".to_string())?;
+    let id = parsed.id;
+
+    match (parsed.platform, parsed.content_type) {
+        // A `/live/<id>` URL keeps its id once the stream becomes a VOD.
+        (Platform::YouTube, ContentType::Video | ContentType::Stream) if is_youtube_video_id(&id) => {
+            Ok(RecordVodSource::Video(format!("https://www.youtube.com/watch?v={}", id)))
+        }
+        (Platform::YouTube, ContentType::Stream | ContentType::Channel) => {
+            Ok(RecordVodSource::ChannelArchive(format!("https://www.youtube.com/{}/streams", id)))
+        }
+        (Platform::Twitch, ContentType::Video) => {
+            Ok(RecordVodSource::Video(format!("https://www.twitch.tv/videos/{}", id)))
+        }
+        (Platform::Twitch, ContentType::Stream | ContentType::Channel) => {
+            Ok(RecordVodSource::ChannelArchive(format!("https://www.twitch.tv/{}/videos?filter=archives", id)))
+        }
+        _ => Err("errors.records.unsupported_url".to_string()),
+    }
+}
+
+/// Pick the archive covering `recorded_at` from a flat playlist listing:
+/// the broadcast whose start ≤ `recorded_at` ≤ start + duration, otherwise
+/// the latest one that started before it. Entries without a timestamp are
+/// ignored.
+fn select_archive_for_record(entries: &[serde_json::Value], recorded_at: DateTime<Utc>) -> Option<String> {
+    let recorded_at = recorded_at.timestamp();
+    let mut latest_before: Option<(i64, &str)> = None;
+
+    for entry in entries {
+        let Some(url) = entry.get("url").or_else(|| entry.get("webpage_url")).and_then(|v| v.as_str()) else { continue };
+        let Some(start) = entry.get("timestamp").and_then(|v| v.as_i64()) else { continue };
+        if start > recorded_at {
+            continue;
+        }
+        let duration = entry.get("duration").and_then(|v| v.as_f64()).map(|d| d as i64);
+        if duration.is_some_and(|d| recorded_at <= start + d) {
+            return Some(url.to_string());
+        }
+        if !matches!(latest_before, Some((ts, _)) if ts >= start) {
+            latest_before = Some((start, url));
+        }
+    }
+
+    latest_before.map(|(_, url)| url.to_string())
+}
+
+/// Channel archive listings fetched during one batch, keyed by listing URL,
+/// so records from the same channel share a single yt-dlp run.
+type ChannelArchiveCache = HashMap<String, Result<Vec<serde_json::Value>, String>>;
+
+async fn list_channel_archives(listing_url: &str) -> Result<Vec<serde_json::Value>, String> {
+    let output = TokioCommand::new("yt-dlp")
+        .args(["-J", "--flat-playlist", "--playlist-end", "50"])
+        .args(["--extractor-args", "youtubetab:approximate_date"])
+        .arg(listing_url)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "找不到 yt-dlp，請安裝後再試".to_string())?;
+
+    if !output.status.success() {
+        return Err("errors.records.vod_not_found".to_string());
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse yt-dlp output: {}", e))?;
+    Ok(json.get("entries").and_then(|v| v.as_array()).cloned().unwrap_or_default())
+}
+
+async fn find_channel_archive(
+    listing_url: &str,
+    recorded_at: DateTime<Utc>,
+    cache: &mut ChannelArchiveCache,
 ) -> Result<String, String> {
-    // Validate time range
-    validate_time_range(&config)?;
-    let task_id = Uuid::new_v4().to_string();
+    if !cache.contains_key(listing_url) {
+        let listing = list_channel_archives(listing_url).await;
+        cache.insert(listing_url.to_string(), listing);
+    }
+    let entries = cache[listing_url].as_ref().map_err(|e| e.clone())?;
 
-    let progress = DownloadProgress {
-        task_id: task_id.clone(),
-        status: "queued".to_string(),
-        title: config.video_info.title.clone(),
-        percentage: 0.0,
-        speed: "0 B/s".to_string(),
-        eta: "計算中...".to_string(),
-        downloaded_bytes: 0,
-        total_bytes: 0,
-        output_path: None,
-        error_message: None,
-        is_recording: None,
-        recorded_duration: None,
-        bitrate: None,
+    select_archive_for_record(entries, recorded_at).ok_or_else(|| "errors.records.vod_not_found".to_string())
+}
+
+/// Clip range around `live_time`, clamped to the start of the VOD and, when
+/// known, to its duration.
+fn record_clip_range(live_time: &str, before: u32, after: u32, duration: Option<i64>) -> Result<TimeRange, String> {
+    let marker = parse_time_to_seconds(live_time).map_err(|_| "errors.records.invalid_live_time".to_string())?;
+    let start = (marker - before as i64).max(0);
+    let mut end = marker + after as i64;
+    if let Some(duration) = duration {
+        if start >= duration {
+            return Err(time_exceeds_duration_error(duration));
+        }
+        end = end.min(duration);
+    }
+
+    Ok(TimeRange {
+        start: Some(normalize_time_to_hhmmss(start)),
+        end: Some(normalize_time_to_hhmmss(end)),
+    })
+}
+
+/// Resolve a record to its VOD and build the download of the clip around
+/// its live time, named by the default filename template.
+async fn record_clip_config(
+    app: &AppHandle,
+    record: &Record,
+    config: &AppConfig,
+    archives: &mut ChannelArchiveCache,
+) -> Result<DownloadConfig, String> {
+    let vod_url = match record_vod_source(&record.channel_url)? {
+        RecordVodSource::Video(url) => url,
+        RecordVodSource::ChannelArchive(listing_url) => {
+            let recorded_at = DateTime::parse_from_rfc3339(&record.timestamp)
+                .map_err(|_| "errors.records.vod_not_found".to_string())?
+                .with_timezone(&Utc);
+            find_channel_archive(&listing_url, recorded_at, archives).await?
+        }
     };
 
-    let task = DownloadTask {
-        config: config.clone(),
-        progress: progress.clone(),
-        process: None,
-        paused: false,
+    let parsed = parse_url(&vod_url)?;
+    let platform = platform_name(&parsed.platform);
+    let json = signed_in_dump_json(app, platform, &vod_url).await?;
+    let live_status = json.get("live_status").and_then(|v| v.as_str()).unwrap_or("");
+    if matches!(live_status, "is_live" | "is_upcoming" | "post_live") {
+        return Err("errors.records.vod_still_live".to_string());
+    }
+
+    let video_info = video_info_from_ytdlp_json(&json, &parsed.id, platform, "video");
+    if video_info.is_live {
+        return Err("errors.records.vod_still_live".to_string());
+    }
+
+    let time_range = record_clip_range(
+        &record.live_time,
+        config.download_clip_before_offset,
+        config.download_clip_after_offset,
+        video_info.duration,
+    )?;
+
+    let content_type = "video+audio";
+    let container_format = config.output_container.to_lowercase();
+    let title = if record.topic.trim().is_empty() { record.title.clone() } else { record.topic.clone() };
+
+    let (date, datetime) = template_date_vars(video_info.created_at.as_deref(), Utc::now());
+    let mut vars = HashMap::new();
+    vars.insert("channel".to_string(), video_info.channel.replace(' ', "_"));
+    vars.insert("channel_name".to_string(), video_info.channel.clone());
+    vars.insert("platform".to_string(), platform.to_string());
+    vars.insert("date".to_string(), date);
+    vars.insert("datetime".to_string(), datetime);
+    vars.insert("title".to_string(), title);
+    vars.insert("id".to_string(), parsed.id.clone());
+    vars.insert("type".to_string(), "clip".to_string());
+    vars.insert("resolution".to_string(), config.default_video_quality.clone());
+    vars.insert(
+        "duration".to_string(),
+        format_template_duration((config.download_clip_before_offset + config.download_clip_after_offset) as i64),
+    );
+
+    let sanitized = sanitize_filename_str(&expand_variables(&config.default_filename_template, &vars));
+    let filename = if sanitized.is_empty() { make_fallback_filename() } else { sanitized };
+    let ext = if container_format == "mkv" { "mkv" } else { "mp4" };
+
+    let download_config = DownloadConfig {
+        url: vod_url,
+        video_info,
+        format_id: quality_to_format(&config.default_video_quality.to_lowercase(), content_type),
+        content_type: content_type.to_string(),
+        video_codec: None,
+        audio_codec: None,
+        output_filename: format!("{}.{}", filename, ext),
+        output_folder: expand_home_dir(&config.default_download_folder),
+        container_format,
+        time_range: Some(time_range),
+        download_archive: None,
+        precise_cut: Some(config.precise_cut_mode.clone()).filter(|mode| mode != "off"),
+        recording_limits: RecordingLimits::default(),
+        cookie_profile_id: None,
     };
+    validate_time_range(&download_config)?;
+
+    Ok(download_config)
+}
+
+/// Queue the clip for a record. The caller kicks `process_download_queue`.
+async fn enqueue_record_clip(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    record: &Record,
+    config: &AppConfig,
+    archives: &mut ChannelArchiveCache,
+) -> Result<String, String> {
+    let download_config = record_clip_config(app, record, config, archives).await?;
+    Ok(enqueue_download(app, tasks, download_config).await)
+}
+
+/// Queue a trimmed download of one record's clip.
+#[tauri::command]
+async fn download_record_clip(
+    app: AppHandle,
+    record_id: String,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<String, String> {
+    let data = get_local_records(app.clone())?;
+    let record = data.records.into_iter()
+        .find(|r| r.id == record_id)
+        .ok_or_else(|| "errors.records.record_not_found".to_string())?;
+    let config = load_config(app.clone()).unwrap_or_default();
+
+    let task_id = enqueue_record_clip(&app, tasks.inner(), &record, &config, &mut HashMap::new()).await?;
+
+    process_download_queue(app, tasks.inner().clone());
+    Ok(task_id)
+}
+
+/// Records in a folder. `folder_id` also accepts the virtual "all-records"
+/// and "uncategorized" folders of the records page.
+fn records_in_folder(data: RecordsData, folder_id: &str) -> Vec<Record> {
+    data.records.into_iter()
+        .filter(|r| match folder_id {
+            "all-records" => true,
+            "uncategorized" => r.folder_id.is_none(),
+            id => r.folder_id.as_deref() == Some(id),
+        })
+        .collect()
+}
+
+/// Queue clips for every record in a folder. Records that cannot be
+/// resolved are reported instead of failing the whole batch.
+#[tauri::command]
+async fn download_folder_clips(
+    app: AppHandle,
+    folder_id: String,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<RecordClipSummary, String> {
+    let records = records_in_folder(get_local_records(app.clone())?, &folder_id);
+    let config = load_config(app.clone()).unwrap_or_default();
+
+    let mut summary = RecordClipSummary { task_ids: Vec::new(), failures: Vec::new() };
+    let mut archives = ChannelArchiveCache::new();
+    for record in &records {
+        match enqueue_record_clip(&app, tasks.inner(), record, &config, &mut archives).await {
+            Ok(task_id) => summary.task_ids.push(task_id),
+            Err(error) => summary.failures.push(RecordClipFailure {
+                record_id: record.id.clone(),
+                topic: record.topic.clone(),
+                error,
+            }),
+        }
+    }
+
+    process_download_queue(app, tasks.inner().clone());
+    Ok(summary)
+}
+
+// ============================================================
+// Highlight Reels
+// ============================================================
+
+const HIGHLIGHT_POLL_INTERVAL_SECS: u64 = 2;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HighlightExportProgress {
+    pub job_id: String,
+    pub folder_id: String,
+    /// "resolving" | "downloading" | "concatenating" | "completed" | "failed"
+    pub status: String,
+    /// Records resolved while "resolving", clips finished while "downloading".
+    pub completed_clips: usize,
+    pub total_clips: usize,
+    pub output_path: Option<String>,
+    pub cue_path: Option<String>,
+    pub error: Option<String>,
+    /// Records whose clip could not be resolved or downloaded.
+    pub failures: Vec<RecordClipFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HighlightChapter {
+    title: String,
+    start_ms: i64,
+    end_ms: i64,
+}
+
+/// Lay chapters end to end from each clip's duration.
+fn highlight_chapters(clips: &[(String, f64)]) -> Vec<HighlightChapter> {
+    let mut start_ms = 0;
+    clips.iter()
+        .map(|(title, duration)| {
+            let end_ms = start_ms + (duration * 1000.0).round() as i64;
+            let chapter = HighlightChapter { title: title.clone(), start_ms, end_ms };
+            start_ms = end_ms;
+            chapter
+        })
+        .collect()
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// FFmpeg metadata file carrying the reel's title and chapters.
+fn ffmetadata_chapters(title: &str, chapters: &[HighlightChapter]) -> String {
+    let mut out = format!(";FFMETADATA1\ntitle={}\n", escape_ffmetadata(title));
+    for chapter in chapters {
+        out.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            chapter.start_ms,
+            chapter.end_ms,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    out
+}
+
+/// CUE sheet with one track per chapter. Index times are MM:SS:FF with 75
+/// frames per second; minutes are not capped at 99.
+fn cue_sheet(title: &str, media_file: &str, chapters: &[HighlightChapter]) -> String {
+    let quote = |s: &str| s.replace('"', "'");
+    let mut out = format!("TITLE \"{}\"\nFILE \"{}\" MP4\n", quote(title), quote(media_file));
+    for (index, chapter) in chapters.iter().enumerate() {
+        let frames = chapter.start_ms * 75 / 1000;
+        out.push_str(&format!(
+            "  TRACK {:02} VIDEO\n    TITLE \"{}\"\n    INDEX 01 {:02}:{:02}:{:02}\n",
+            index + 1,
+            quote(&chapter.title),
+            frames / (75 * 60),
+            (frames / 75) % 60,
+            frames % 75
+        ));
+    }
+    out
+}
+
+fn emit_highlight_progress(app: &AppHandle, progress: &HighlightExportProgress) {
+    app.emit("highlight-export-progress", progress).ok();
+}
+
+/// Concatenate `clips` into `output` with chapters from `metadata_path`.
+/// Clips with the same video codec and size are stream-copied; otherwise,
+/// or if copying fails, they are re-encoded to H.264/AAC.
+async fn concat_highlight_clips(work_dir: &Path, clips: &[(String, MediaProbe)], metadata_path: &Path, output: &Path) -> Result<(), String> {
+    let list_path = work_dir.join("concat.txt");
+    let list: String = clips.iter()
+        .map(|(path, _)| format!("file '{}'\n", path.replace('\'', "'\\''")))
+        .collect();
+    fs::write(&list_path, list).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let list_path = list_path.as_path();
+    let run = |reencode: bool| async move {
+        let mut command = ffmpeg_command();
+        command
+            .args(["-f", "concat", "-safe", "0"])
+            .arg("-i")
+            .arg(list_path)
+            .arg("-i")
+            .arg(metadata_path)
+            .args(["-map", "0:v?", "-map", "0:a?", "-map_metadata", "1", "-map_chapters", "1"]);
+        if reencode {
+            command.args(["-c:v", "libx264", "-crf", "20", "-preset", "medium", "-c:a", "aac", "-b:a", "192k"]);
+        } else {
+            command.args(["-c", "copy"]);
+        }
+        command.args(["-movflags", "+faststart"]).arg(output);
+
+        let result = TokioCommand::from(command)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        if result.status.success() {
+            Ok(())
+        } else {
+            Err(format!("ffmpeg concat failed: {}", String::from_utf8_lossy(&result.stderr).trim()))
+        }
+    };
+
+    let first_format = clips.first().and_then(|(_, probe)| probe.video_format.clone());
+    let uniform = first_format.is_some() && clips.iter().all(|(_, probe)| probe.video_format == first_format);
+    if uniform && run(false).await.is_ok() {
+        return Ok(());
+    }
+    run(true).await
+}
+
+/// Queue a clip download for every record, wait for them, then stitch the
+/// finished ones into the reel.
+async fn run_highlight_export(
+    app: AppHandle,
+    tasks: DownloadTasks,
+    mut progress: HighlightExportProgress,
+    records: Vec<Record>,
+    title: String,
+    work_dir: PathBuf,
+    output: PathBuf,
+) {
+    let config = load_config(app.clone()).unwrap_or_default();
+    let mut clip_tasks = Vec::new();
+    let mut archives = ChannelArchiveCache::new();
+    for (index, record) in records.into_iter().enumerate() {
+        match record_clip_config(&app, &record, &config, &mut archives).await {
+            Ok(mut clip_config) => {
+                // Uniform MP4 clips give the concat step the best chance to stream-copy.
+                clip_config.output_folder = work_dir.to_string_lossy().to_string();
+                clip_config.output_filename = format!("{:03}_{}.mp4", index + 1, record.id);
+                clip_config.container_format = "mp4".to_string();
+                let task_id = enqueue_download(&app, &tasks, clip_config).await;
+                clip_tasks.push((record, task_id));
+            }
+            Err(error) => progress.failures.push(RecordClipFailure {
+                record_id: record.id.clone(),
+                topic: record.topic.clone(),
+                error,
+            }),
+        }
+        progress.completed_clips = index + 1;
+        emit_highlight_progress(&app, &progress);
+    }
+
+    if clip_tasks.is_empty() {
+        fs::remove_dir_all(&work_dir).ok();
+        progress.status = "failed".to_string();
+        progress.error = Some("errors.highlights.no_clips".to_string());
+        emit_highlight_progress(&app, &progress);
+        return;
+    }
+
+    progress.status = "downloading".to_string();
+    progress.completed_clips = 0;
+    progress.total_clips = clip_tasks.len();
+    emit_highlight_progress(&app, &progress);
+    process_download_queue(app.clone(), tasks.clone());
+
+    // Poll until every clip task has finished one way or another.
+    let finished = loop {
+        let states: Vec<(String, Option<String>, Option<String>)> = {
+            let tasks_guard = tasks.lock().await;
+            clip_tasks.iter()
+                .map(|(_, task_id)| match tasks_guard.get(task_id) {
+                    Some(task) => (task.progress.status.clone(), task.progress.output_path.clone(), task.progress.error_message.clone()),
+                    None => ("cancelled".to_string(), None, None),
+                })
+                .collect()
+        };
+
+        let done = states.iter().filter(|(status, _, _)| matches!(status.as_str(), "completed" | "failed" | "cancelled")).count();
+        if done != progress.completed_clips {
+            progress.completed_clips = done;
+            emit_highlight_progress(&app, &progress);
+        }
+        if done == clip_tasks.len() {
+            break states;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(HIGHLIGHT_POLL_INTERVAL_SECS)).await;
+    };
+
+    let mut clips = Vec::new();
+    for ((record, _), (status, output_path, error)) in clip_tasks.iter().zip(finished) {
+        let probe = match output_path.clone().filter(|_| status == "completed") {
+            Some(path) => {
+                let probed = probe_media(Path::new(&path)).await;
+                Some((path, probed))
+            }
+            None => None,
+        };
+        match probe {
+            Some((path, Ok(probe))) if probe.duration.is_some() => clips.push((record.topic.clone(), path, probe)),
+            _ => progress.failures.push(RecordClipFailure {
+                record_id: record.id.clone(),
+                topic: record.topic.clone(),
+                error: error.unwrap_or_else(|| format!("errors.highlights.clip_{}", if status == "completed" { "unreadable" } else { status.as_str() })),
+            }),
+        }
+    }
+
+    if clips.is_empty() {
+        progress.status = "failed".to_string();
+        progress.error = Some("errors.highlights.no_clips".to_string());
+        emit_highlight_progress(&app, &progress);
+        return;
+    }
+
+    progress.status = "concatenating".to_string();
+    emit_highlight_progress(&app, &progress);
+
+    let chapters = highlight_chapters(
+        &clips.iter().map(|(topic, _, probe)| (topic.clone(), probe.duration.unwrap_or(0.0))).collect::<Vec<_>>(),
+    );
+    let metadata_path = work_dir.join("chapters.ffmeta");
+    let clips: Vec<(String, MediaProbe)> = clips.into_iter().map(|(_, path, probe)| (path, probe)).collect();
+
+    let result = match fs::write(&metadata_path, ffmetadata_chapters(&title, &chapters)) {
+        Ok(()) => concat_highlight_clips(&work_dir, &clips, &metadata_path, &output).await,
+        Err(e) => Err(format!("Failed to write chapters: {}", e)),
+    };
+
+    match result {
+        Ok(()) => {
+            let cue_path = output.with_extension("cue");
+            let media_file = output.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if let Err(e) = fs::write(&cue_path, cue_sheet(&title, media_file, &chapters)) {
+                log::warn!("[Highlights] Failed to write cue sheet: {}", e);
+            } else {
+                progress.cue_path = Some(cue_path.to_string_lossy().to_string());
+            }
+            fs::remove_dir_all(&work_dir).ok();
+
+            progress.status = "completed".to_string();
+            progress.output_path = Some(output.to_string_lossy().to_string());
+        }
+        Err(e) => {
+            // Keep the downloaded clips so nothing has to be fetched again.
+            log::error!("[Highlights] {}", e);
+            progress.status = "failed".to_string();
+            progress.error = Some(e);
+        }
+    }
+    emit_highlight_progress(&app, &progress);
+}
+
+/// Download the clip of every record in a folder and concatenate them, in
+/// the order they were recorded, into one highlight reel with a chapter per
+/// record topic and a `.cue` sidecar. Returns the job id used by
+/// `highlight-export-progress` events right away; the records are resolved to
+/// clips in the background.
+#[tauri::command]
+async fn export_folder_highlights(
+    app: AppHandle,
+    folder_id: String,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<String, String> {
+    let data = get_local_records(app.clone())?;
+    let title = match folder_id.as_str() {
+        "all-records" | "uncategorized" => "Highlights".to_string(),
+        id => data.folders.iter()
+            .find(|f| f.id == id)
+            .map(|f| f.name.clone())
+            .ok_or_else(|| "errors.highlights.folder_not_found".to_string())?,
+    };
+    let mut records = records_in_folder(data, &folder_id);
+    if records.is_empty() {
+        return Err("errors.highlights.no_clips".to_string());
+    }
+    records.sort_by_key(|r| (r.timestamp.clone(), parse_time_to_seconds(&r.live_time).unwrap_or(0)));
+
+    let config = load_config(app.clone()).unwrap_or_default();
+    let job_id = Uuid::new_v4().to_string();
+    let output_folder = PathBuf::from(expand_home_dir(&config.default_download_folder));
+    let basename = sanitize_filename_str(&format!("{} {}", title, Utc::now().format("%Y-%m-%d_%H%M%S")));
+    let work_dir = output_folder.join(format!(".{}_clips", basename));
+    fs::create_dir_all(&work_dir).map_err(|e| format!("Failed to create work directory: {}", e))?;
+
+    let progress = HighlightExportProgress {
+        job_id: job_id.clone(),
+        folder_id,
+        status: "resolving".to_string(),
+        completed_clips: 0,
+        total_clips: records.len(),
+        output_path: None,
+        cue_path: None,
+        error: None,
+        failures: Vec::new(),
+    };
+    emit_highlight_progress(&app, &progress);
+
+    let output = output_folder.join(format!("{}.mp4", basename));
+    tokio::spawn(run_highlight_export(app, tasks.inner().clone(), progress, records, title, work_dir, output));
+
+    Ok(job_id)
+}
+
+// Time range validation functions
+fn parse_time_to_seconds(time: &str) -> Result<i64, String> {
+    let time = time.trim();
+
+    // Pure seconds
+    if let Ok(seconds) = time.parse::<i64>() {
+        return Ok(seconds);
+    }
+
+    // HH:MM:SS or MM:SS
+    let parts: Vec<&str> = time.split(':').collect();
+
+    match parts.len() {
+        2 => {
+            // MM:SS
+            let minutes = parts[0].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
+            let seconds = parts[1].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
+            Ok(minutes * 60 + seconds)
+        }
+        3 => {
+            // HH:MM:SS
+            let hours = parts[0].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
+            let minutes = parts[1].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
+            let seconds = parts[2].parse::<i64>().map_err(|_| "無效的時間格式".to_string())?;
+            Ok(hours * 3600 + minutes * 60 + seconds)
+        }
+        _ => Err("請輸入有效時間格式".to_string()),
+    }
+}
+
+fn normalize_time_to_hhmmss(seconds: i64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+}
+
+fn validate_time_range(config: &DownloadConfig) -> Result<(), String> {
+    if let Some(ref time_range) = config.time_range {
+        let start_seconds = if let Some(ref start) = time_range.start {
+            Some(parse_time_to_seconds(start)?)
+        } else {
+            None
+        };
+
+        let end_seconds = if let Some(ref end) = time_range.end {
+            Some(parse_time_to_seconds(end)?)
+        } else {
+            None
+        };
+
+        // Validate end > start
+        if let (Some(start), Some(end)) = (start_seconds, end_seconds) {
+            if end <= start {
+                return Err("errors.download.end_before_start".to_string());
+            }
+        }
+
+        // Validate against video duration if available; either end of the
+        // range may be given on its own.
+        if let Some(duration) = config.video_info.duration {
+            if start_seconds.into_iter().chain(end_seconds).any(|t| t > duration) {
+                return Err(time_exceeds_duration_error(duration));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn time_exceeds_duration_error(duration: i64) -> String {
+    serde_json::json!({
+        "key": "errors.download.time_exceeds_duration",
+        "params": { "duration": normalize_time_to_hhmmss(duration) },
+    })
+    .to_string()
+}
+
+// Download management commands
+#[tauri::command]
+async fn start_download(
+    app: AppHandle,
+    config: DownloadConfig,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<String, String> {
+    // Validate time range
+    validate_time_range(&config)?;
+    let task_id = enqueue_download(&app, tasks.inner(), config).await;
+
+    // Start when a download slot is free
+    process_download_queue(app, tasks.inner().clone());
+
+    Ok(task_id)
+}
+
+/// Add a VOD download to the task list as "queued" and return its id. The
+/// caller kicks `process_download_queue`.
+async fn enqueue_download(app: &AppHandle, tasks: &DownloadTasks, config: DownloadConfig) -> String {
+    let task_id = Uuid::new_v4().to_string();
+
+    let progress = DownloadProgress {
+        task_id: task_id.clone(),
+        status: "queued".to_string(),
+        title: config.video_info.title.clone(),
+        percentage: 0.0,
+        speed: "0 B/s".to_string(),
+        eta: "計算中...".to_string(),
+        downloaded_bytes: 0,
+        total_bytes: 0,
+        output_path: None,
+        error_message: None,
+        is_recording: None,
+        recorded_duration: None,
+        bitrate: None,
+        retry_attempt: None,
+        max_retries: None,
+        priority: 0,
+        queue_position: None,
+        processing_step: None,
+        segments: Vec::new(),
+    };
+
+    let task = DownloadTask::new(config, progress.clone());
+
+    {
+        let mut tasks_guard = tasks.lock().await;
+        tasks_guard.insert(task_id.clone(), task);
+    }
+
+    // Emit initial progress
+    app.emit("download-progress", &progress).ok();
+
+    task_id
+}
+
+#[tauri::command]
+async fn start_recording(
+    app: AppHandle,
+    config: DownloadConfig,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<String, String> {
+    if !config.video_info.is_live {
+        return Err("errors.download.not_livestream".to_string());
+    }
+
+    let task_id = Uuid::new_v4().to_string();
+
+    let progress = DownloadProgress {
+        task_id: task_id.clone(),
+        status: "queued".to_string(),
+        title: config.video_info.title.clone(),
+        percentage: 0.0,
+        speed: "0 B/s".to_string(),
+        eta: "直播錄製中...".to_string(),
+        downloaded_bytes: 0,
+        total_bytes: 0,
+        output_path: None,
+        error_message: None,
+        is_recording: Some(true),
+        recorded_duration: Some("00:00:00".to_string()),
+        bitrate: Some("N/A".to_string()),
+        retry_attempt: None,
+        max_retries: None,
+        priority: LIVE_TASK_PRIORITY,
+        queue_position: None,
+        processing_step: None,
+        segments: Vec::new(),
+    };
+
+    let task = DownloadTask::new(config.clone(), progress.clone());
+
+    {
+        let mut tasks_guard = tasks.lock().await;
+        tasks_guard.insert(task_id.clone(), task);
+    }
+
+    // Emit initial progress
+    app.emit("download-progress", &progress).ok();
+
+    // Start when a download slot is free
+    process_download_queue(app, tasks.inner().clone());
+
+    Ok(task_id)
+}
+
+async fn execute_download(app: AppHandle, tasks: DownloadTasks, task_id: String) {
+    let config = {
+        let tasks_guard = tasks.lock().await;
+        match tasks_guard.get(&task_id) {
+            Some(task) => task.config.clone(),
+            None => return,
+        }
+    };
+
+    // Update status to downloading
+    {
+        let mut tasks_guard = tasks.lock().await;
+        if let Some(task) = tasks_guard.get_mut(&task_id) {
+            task.progress.status = "downloading".to_string();
+            app.emit("download-progress", &task.progress).ok();
+        }
+    }
+
+    // Build yt-dlp command
+    let output_path = output_file_path(&config);
+    let output_template = output_path.to_str().unwrap();
+
+    let mut args = vec![
+        "--newline",
+        "--progress",
+        "-f", &config.format_id,
+        "-o", output_template,
+    ];
+
+    // Add authentication arguments
+    let (auth_args, twitch_cookies) = download_auth_args(&app, &config, &task_id).await;
+    args.extend(auth_args.iter().map(String::as_str));
+
+    // Add time range if specified
+    let section_seconds = config.time_range.as_ref().and_then(|time_range| {
+        match (&time_range.start, &time_range.end) {
+            (Some(start), Some(end)) => Some((
+                parse_time_to_seconds(start).unwrap_or(0),
+                parse_time_to_seconds(end).unwrap_or(0),
+            )),
+            _ => None,
+        }
+    });
+    // Precise cuts download a padded section and trim it afterwards.
+    let precise_cut = section_seconds.zip(config.precise_cut.clone());
+    let download_sections = section_seconds.map(|(start, end)| {
+        let (start, end) = if precise_cut.is_some() {
+            precise_cut_padding(start, end, config.video_info.duration)
+        } else {
+            (start, end)
+        };
+        format!("*{}-{}", normalize_time_to_hhmmss(start), normalize_time_to_hhmmss(end))
+    });
+    if let Some(ref sections) = download_sections {
+        args.push("--download-sections");
+        args.push(sections);
+    }
+
+    if let Some(ref archive) = config.download_archive {
+        args.push("--download-archive");
+        args.push(archive);
+    }
+
+    // Add container format
+    if config.container_format != "auto" {
+        args.push("--remux-video");
+        args.push(&config.container_format);
+    }
+
+    // Always continue from an existing .part file so a resumed task picks up
+    // where the paused one stopped instead of starting over.
+    args.push("--continue");
+
+    // Share of the global bandwidth budget
+    let rate_limit = assign_task_rate(&app, &tasks, &task_id).await;
+    let rate_limit_storage;
+    if let Some(rate) = rate_limit {
+        rate_limit_storage = rate.to_string();
+        args.push("--limit-rate");
+        args.push(&rate_limit_storage);
+    }
+
+    args.push(&config.url);
+    log_task_command(&app, &task_id, "yt-dlp", &args);
+
+    // Spawn yt-dlp process
+    let mut command = TokioCommand::new("yt-dlp");
+    command
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Put yt-dlp in its own process group so pause/cancel also reach the
+    // ffmpeg children it spawns for merging.
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let msg = format!("{{\"key\":\"errors.download.cannotStartYtdlp\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
+            update_download_error(&app, &tasks, &task_id, &msg).await;
+            return;
+        }
+    };
+
+    // Read stdout for progress (before storing process)
+    let stdout = match child.stdout.take() {
+        Some(stdout) => stdout,
+        None => {
+            update_download_error(&app, &tasks, &task_id, "{\"key\":\"errors.download.cannotReadOutput\"}").await;
+            return;
+        }
+    };
+    let stderr_capture = StderrCapture::start(&mut child, task_log(&app, &task_id, "yt-dlp"));
+
+    // Store process handle
+    let Some(cancel) = attach_task_process(&tasks, &task_id, &child).await else {
+        kill_download_process(&mut child);
+        child.wait().await.ok();
+        return;
+    };
+    rebalance_bandwidth(&app, &tasks).await;
+
+    let mut lines = AsyncBufReader::new(stdout).lines();
+    while let Some(line) = next_output_line(&mut lines, &cancel).await {
+        // Parse progress line
+        if let Some(progress_info) = parse_ytdlp_progress(&line) {
+            let total_bytes = parse_ytdlp_total_bytes(&line);
+            let mut tasks_guard = tasks.lock().await;
+            if let Some(task) = tasks_guard.get_mut(&task_id) {
+                task.progress.percentage = progress_info.0;
+                task.progress.speed = progress_info.1;
+                task.progress.eta = progress_info.2;
+                if let Some(total) = total_bytes {
+                    task.progress.total_bytes = total;
+                    task.progress.downloaded_bytes = (total as f64 * progress_info.0 / 100.0) as u64;
+                }
+                app.emit("download-progress", &task.progress).ok();
+            }
+        }
+    }
+
+    // Wait for process to complete; the token file is not needed after it
+    let result = wait_task_process(&tasks, &task_id, &mut child, &cancel).await;
+    drop(twitch_cookies);
+    let (stopped, restart) = {
+        let tasks_guard = tasks.lock().await;
+        match tasks_guard.get(&task_id) {
+            Some(task) if task.paused || task.progress.status == "cancelled" => (true, false),
+            // Killed for a new rate limit, or by a pause fallback that was
+            // resumed and queued again meanwhile.
+            Some(task) if cancel.is_cancelled() => (false, task.rate_limit != rate_limit),
+            Some(_) => (false, false),
+            None => return,
+        }
+    };
+    if cancel.is_cancelled() && !stopped {
+        if restart {
+            restart_download(app, tasks, task_id);
+        }
+        return;
+    }
+
+    match result {
+        Ok(_) if stopped => {
+            // Process was stopped by pause_download or cancel_download;
+            // the status was already set there.
+        }
+        Ok(status) => {
+            if status.success() {
+                // Download completed successfully
+                let mut output_path_str = output_path.to_str().unwrap().to_string();
+                if let Some(((start, end), mode)) = precise_cut.as_ref() {
+                    let (padded_start, _) = precise_cut_padding(*start, *end, config.video_info.duration);
+                    let cut = PreciseCut {
+                        offset: (start - padded_start) as f64,
+                        duration: (end - start) as f64,
+                        mode: mode.clone(),
+                    };
+                    output_path_str = precise_cut_output(&app, &tasks, &task_id, &output_path, &cut).await;
+                    if is_task_cancelled(&tasks, &task_id).await {
+                        return;
+                    }
+                }
+                if let Some(settings) = load_config(app.clone()).ok().and_then(|c| TranscodeSettings::from_config(&c)) {
+                    output_path_str = transcode_output(&app, &tasks, &task_id, Path::new(&output_path_str), &settings).await;
+                    if is_task_cancelled(&tasks, &task_id).await {
+                        return;
+                    }
+                }
+
+                {
+                    let mut tasks_guard = tasks.lock().await;
+                    if let Some(task) = tasks_guard.get_mut(&task_id) {
+                        task.progress.status = "completed".to_string();
+                        task.progress.percentage = 100.0;
+                        task.progress.output_path = Some(output_path_str.clone());
+                        task.progress.processing_step = None;
+                        app.emit("download-progress", &task.progress).ok();
+                    }
+                }
+
+                // Save to history
+                let attempts = finish_task_attempt(&tasks, &task_id, "completed", None).await;
+                save_download_history(&app, &config, &output_path_str, "completed", None, attempts, &[]).await;
+            } else {
+                let stderr_output = stderr_capture.finish().await;
+                let error = ytdlp_error_message(&stderr_output, "{\"key\":\"errors.download.failed\"}");
+                if !schedule_download_retry(&app, &tasks, &task_id, &stderr_output, &error).await {
+                    update_download_error(&app, &tasks, &task_id, &error).await;
+                }
+            }
+        }
+        Err(e) => {
+            let msg = format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
+            update_download_error(&app, &tasks, &task_id, &msg).await;
+        }
+    }
+
+    // Hand the freed slot and bandwidth to the remaining tasks.
+    rebalance_bandwidth(&app, &tasks).await;
+    process_download_queue(app, tasks);
+}
+
+async fn execute_recording(app: AppHandle, tasks: DownloadTasks, task_id: String) {
+    let config = {
+        let tasks_guard = tasks.lock().await;
+        match tasks_guard.get(&task_id) {
+            Some(task) => task.config.clone(),
+            None => return,
+        }
+    };
+
+    let app_config = load_config(app.clone()).ok();
+    let post_process = app_config.as_ref()
+        .map(RecordingPostProcess::from_config)
+        .unwrap_or_default();
+    let reconnect = app_config.as_ref()
+        .map(RecordingReconnect::from_config)
+        .unwrap_or_default();
+
+    // Split or capped recordings are piped through an ffmpeg segmenter
+    let limits = config.recording_limits.clone();
+    let piped = limits.is_active();
+
+    // Build output path
+    let output_path = output_file_path(&config);
+
+    // Share of the global bandwidth budget. Recordings keep this rate for
+    // their whole lifetime; only VOD downloads are rebalanced later.
+    let rate_limit = assign_task_rate(&app, &tasks, &task_id).await;
+    let (auth_args, twitch_cookies) = download_auth_args(&app, &config, &task_id).await;
+    // Add container format; the post-processing remux handles it otherwise
+    let remux_video = config.container_format != "auto" && !post_process.remux && !piped;
+
+    // Record until the stream ends; a dropped stream that comes back within
+    // the grace window continues in a new part.
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut totals = RecordingTotals::default();
+    let mut part: u32 = 1;
+    let finished = loop {
+        let args = recording_ytdlp_args(&config, part, piped, remux_video, rate_limit, &auth_args);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let run = if piped {
+            record_piped(&app, &tasks, &task_id, &args, &config, part, totals).await
+        } else {
+            record_direct(&app, &tasks, &task_id, &args, &recording_part_path(&output_path, part), totals).await
+        };
+
+        let run = match run {
+            Ok(Some(run)) => run,
+            Ok(None) => return,
+            Err(msg) if part == 1 => {
+                update_download_error(&app, &tasks, &task_id, &msg).await;
+                rebalance_bandwidth(&app, &tasks).await;
+                process_download_queue(app, tasks);
+                return;
+            }
+            Err(msg) => {
+                log::warn!("[Recording] Cannot continue recording part {}: {}", part, msg);
+                break false;
+            }
+        };
+        files.extend(run.files);
+        totals = run.totals;
+        if run.finished {
+            break true;
+        }
+
+        // Check if stream was interrupted (exit code may vary)
+        let stderr_output = run.stderr;
+
+        let is_interrupted = stderr_output.contains("Stream ended")
+            || stderr_output.contains("connection")
+            || stderr_output.contains("interrupt");
+
+        // Nothing recorded yet: the stream never started, so a transient
+        // failure can simply be retried from scratch.
+        let nothing_recorded = totals.bytes == 0;
+        let error = ytdlp_error_message(&stderr_output, "{\"key\":\"errors.download.recordingFailed\"}");
+
+        if nothing_recorded && schedule_download_retry(&app, &tasks, &task_id, &stderr_output, &error).await {
+            // Retry scheduled
+            rebalance_bandwidth(&app, &tasks).await;
+            process_download_queue(app, tasks);
+            return;
+        }
+        // A failed continuation still leaves the earlier parts to keep.
+        if !is_interrupted && part == 1 {
+            update_download_error(&app, &tasks, &task_id, &error).await;
+            rebalance_bandwidth(&app, &tasks).await;
+            process_download_queue(app, tasks);
+            return;
+        }
+
+        if !wait_for_stream_return(&app, &tasks, &task_id, &config.url, &auth_args, reconnect.grace).await {
+            break false;
+        }
+        part += 1;
+    };
+    drop(twitch_cookies);
+    if is_task_cancelled(&tasks, &task_id).await {
+        return;
+    }
+
+    if finished {
+        // Recording completed - remux and verify the output
+        let transcode = app_config.as_ref().and_then(TranscodeSettings::from_config);
+        let outputs = finish_recording_files(
+            &app, &tasks, &task_id, &files, &config, &post_process, reconnect.concat, transcode.as_ref(),
+        ).await;
+        if is_task_cancelled(&tasks, &task_id).await {
+            return;
+        }
+        let output_path_str = outputs.first().cloned()
+            .unwrap_or_else(|| output_path.to_str().unwrap().to_string());
+        let segments = if limits.splits() || outputs.len() > 1 { outputs } else { Vec::new() };
+
+        {
+            let mut tasks_guard = tasks.lock().await;
+            if let Some(task) = tasks_guard.get_mut(&task_id) {
+                task.progress.status = "completed".to_string();
+                task.progress.processing_step = None;
+                task.progress.percentage = 100.0;
+                task.progress.output_path = Some(output_path_str.clone());
+                task.progress.is_recording = Some(false);
+                task.progress.segments = segments.clone();
+                app.emit("download-progress", &task.progress).ok();
+            }
+        }
+
+        // Save to history
+        let attempts = finish_task_attempt(&tasks, &task_id, "completed", None).await;
+        save_download_history(&app, &config, &output_path_str, "completed", None, attempts, &segments).await;
+    } else {
+        // Stream interrupted - preserve recorded content, made
+        // playable by the same pipeline as a finished recording
+        let outputs = finish_recording_files(
+            &app, &tasks, &task_id, &files, &config, &post_process, reconnect.concat, None,
+        ).await;
+        if is_task_cancelled(&tasks, &task_id).await {
+            return;
+        }
+        let output_path_str = outputs.first().cloned()
+            .unwrap_or_else(|| output_path.to_str().unwrap().to_string());
+        let segments = if limits.splits() || outputs.len() > 1 { outputs } else { Vec::new() };
+
+        {
+            let mut tasks_guard = tasks.lock().await;
+            if let Some(task) = tasks_guard.get_mut(&task_id) {
+                task.progress.status = "stream_interrupted".to_string();
+                task.progress.processing_step = None;
+                task.progress.output_path = Some(output_path_str.clone());
+                task.progress.is_recording = Some(false);
+                task.progress.segments = segments.clone();
+                task.progress.error_message = Some("{\"key\":\"errors.download.streamInterrupted\"}".to_string());
+                app.emit("download-progress", &task.progress).ok();
+            }
+        }
+
+        let attempts = finish_task_attempt(&tasks, &task_id, "stream_interrupted", Some("errors.download.streamInterrupted")).await;
+        save_download_history(&app, &config, &output_path_str, "stream_interrupted", Some("errors.download.streamInterrupted"), attempts, &segments).await;
+    }
+
+    // Hand the freed slot and bandwidth to the remaining tasks.
+    rebalance_bandwidth(&app, &tasks).await;
+    process_download_queue(app, tasks);
+}
+
+/// yt-dlp arguments for one part of a live recording; each part adds its own
+/// `-o`. Only the first part asks for the stream from its start: a part after
+/// a reconnect continues from the live edge instead of recording the earlier
+/// parts again.
+fn recording_ytdlp_args(
+    config: &DownloadConfig,
+    part: u32,
+    piped: bool,
+    remux_video: bool,
+    rate_limit: Option<u64>,
+    auth_args: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "--newline".to_string(),
+        if piped { "--no-progress" } else { "--progress" }.to_string(),
+        "-f".to_string(),
+        config.format_id.clone(),
+    ];
+    if part == 1 {
+        args.push("--live-from-start".to_string());
+    }
+    if let Some(rate) = rate_limit {
+        args.extend(["--limit-rate".to_string(), rate.to_string()]);
+    }
+    args.extend(auth_args.iter().cloned());
+    if remux_video {
+        args.extend(["--remux-video".to_string(), config.container_format.clone()]);
+    }
+    args.push(config.url.clone());
+    args
+}
+
+// ── Recording Reconnect ─────────────────────────────────────────────────────
+
+/// How often a dropped stream is checked for coming back, in seconds.
+const RECONNECT_POLL_INTERVAL_SECS: u64 = 15;
+
+/// Continuing a live recording after the stream drops.
+#[derive(Debug, Clone)]
+struct RecordingReconnect {
+    /// How long to keep polling for the stream; zero disables reconnecting.
+    grace: std::time::Duration,
+    /// Join the recorded parts into one file afterwards.
+    concat: bool,
+}
+
+impl Default for RecordingReconnect {
+    fn default() -> Self {
+        Self {
+            grace: std::time::Duration::from_secs(default_recording_reconnect_grace_minutes() as u64 * 60),
+            concat: true,
+        }
+    }
+}
+
+impl RecordingReconnect {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            grace: std::time::Duration::from_secs(config.recording_reconnect_grace_minutes as u64 * 60),
+            concat: config.recording_reconnect_concat,
+        }
+    }
+}
+
+/// Recorded length and size across all parts of a recording so far.
+#[derive(Debug, Clone, Copy, Default)]
+struct RecordingTotals {
+    elapsed_secs: u64,
+    bytes: u64,
+}
+
+/// Output path of reconnect part `part`; the first part keeps the name.
+fn recording_part_path(path: &Path, part: u32) -> PathBuf {
+    if part <= 1 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => path.with_file_name(format!("{}_reconnect{}.{}", stem, part, ext)),
+        None => path.with_file_name(format!("{}_reconnect{}", stem, part)),
+    }
+}
+
+/// Whether `url` is broadcasting right now, per yt-dlp.
+async fn stream_is_live(url: &str, auth_args: &[String]) -> bool {
+    TokioCommand::new("yt-dlp")
+        .args(["--skip-download", "--no-warnings", "--print", "is_live"])
+        .args(auth_args)
+        .arg(url)
+        .output()
+        .await
+        .map(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "True")
+        .unwrap_or(false)
+}
+
+/// Poll a dropped stream until it is live again or `grace` runs out. The
+/// task shows "reconnecting" meanwhile; stopping or cancelling it ends the
+/// wait. Returns true when the stream is back and recording should go on.
+async fn wait_for_stream_return(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    url: &str,
+    auth_args: &[String],
+    grace: std::time::Duration,
+) -> bool {
+    use std::time::{Duration, Instant};
+
+    if grace.is_zero() {
+        return false;
+    }
+    if !set_reconnect_status(app, tasks, task_id, "reconnecting").await {
+        return false;
+    }
+    log::info!("[Recording] Stream dropped; waiting up to {}s for it to return", grace.as_secs());
+
+    let deadline = Instant::now() + grace;
+    let mut next_poll = Instant::now();
+    while Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let waiting = {
+            let tasks_guard = tasks.lock().await;
+            tasks_guard.get(task_id).map(|t| t.progress.status == "reconnecting").unwrap_or(false)
+        };
+        if !waiting {
+            return false;
+        }
+        if Instant::now() < next_poll {
+            continue;
+        }
+        next_poll = Instant::now() + Duration::from_secs(RECONNECT_POLL_INTERVAL_SECS);
+        if stream_is_live(url, auth_args).await {
+            log::info!("[Recording] Stream is back; recording the next part");
+            return set_reconnect_status(app, tasks, task_id, "recording").await;
+        }
+    }
+    false
+}
+
+/// Move a recording between "recording" and "reconnecting", unless it was
+/// stopped or cancelled meanwhile.
+async fn set_reconnect_status(app: &AppHandle, tasks: &DownloadTasks, task_id: &str, status: &str) -> bool {
+    let mut tasks_guard = tasks.lock().await;
+    match tasks_guard.get_mut(task_id) {
+        Some(task) if task.progress.status != "cancelled" && task.progress.status != "processing" => {
+            task.progress.status = status.to_string();
+            app.emit("download-progress", &task.progress).ok();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Join reconnect parts with the concat demuxer into the first part's path.
+/// The parts are removed once the joined file checks out.
+async fn concat_recording_parts(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    parts: &[String],
+    faststart: bool,
+) -> Result<String, String> {
+    let first = Path::new(&parts[0]);
+    let stem = first.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let extension = first.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let list_path = first.with_file_name(format!("{}.parts.txt", stem));
+    let joined_path = first.with_file_name(format!("{}.joined.{}", stem, extension));
+
+    let mut expected = probe_media(first).await?;
+    let mut total_duration = Some(0.0);
+    for part in parts {
+        let duration = probe_media(Path::new(part)).await.ok().and_then(|probe| probe.duration);
+        total_duration = total_duration.zip(duration).map(|(total, d)| total + d);
+    }
+    expected.duration = total_duration;
+
+    let list: String = parts.iter()
+        .map(|part| format!("file '{}'\n", part.replace('\'', "'\\''")))
+        .collect();
+    fs::write(&list_path, list).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let mut command = ffmpeg_command();
+    command
+        .args(["-f", "concat", "-safe", "0"])
+        .arg("-i")
+        .arg(&list_path)
+        .args(["-map", "0:v?", "-map", "0:a?", "-c", "copy"]);
+    if faststart && is_mp4_family(&joined_path) {
+        command.args(["-movflags", "+faststart"]);
+    }
+    command.arg(&joined_path);
+
+    let result = match run_ffmpeg_step(app, tasks, task_id, command, "concat", expected.duration).await {
+        Ok(()) => verify_processed_output(&expected, &joined_path).await,
+        Err(e) => Err(e),
+    };
+    fs::remove_file(&list_path).ok();
+    if let Err(e) = result {
+        fs::remove_file(&joined_path).ok();
+        return Err(e);
+    }
+
+    for part in parts {
+        fs::remove_file(part).ok();
+    }
+    fs::rename(&joined_path, first).map_err(|e| format!("Failed to replace first part: {}", e))?;
+    Ok(parts[0].clone())
+}
+
+/// Post-process every recorded file, join reconnect parts when `concat` is
+/// set and the recording is not split on purpose, then transcode. Returns
+/// the final paths in order.
+#[allow(clippy::too_many_arguments)]
+async fn finish_recording_files(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    files: &[PathBuf],
+    config: &DownloadConfig,
+    steps: &RecordingPostProcess,
+    concat: bool,
+    transcode: Option<&TranscodeSettings>,
+) -> Vec<String> {
+    let mut outputs = Vec::with_capacity(files.len());
+    for file in files {
+        if is_task_cancelled(tasks, task_id).await {
+            return outputs;
+        }
+        outputs.push(post_process_recording(app, tasks, task_id, file, &config.container_format, steps).await);
+    }
+
+    if concat && outputs.len() > 1 && !config.recording_limits.splits() {
+        match concat_recording_parts(app, tasks, task_id, &outputs, steps.faststart).await {
+            Ok(joined) => outputs = vec![joined],
+            Err(e) => log::warn!("[Recording] Cannot join reconnect parts: {}; keeping them separate", e),
+        }
+    }
+
+    if let Some(settings) = transcode {
+        let mut transcoded = Vec::with_capacity(outputs.len());
+        for output in outputs {
+            if is_task_cancelled(tasks, task_id).await {
+                return transcoded;
+            }
+            transcoded.push(transcode_output(app, tasks, task_id, Path::new(&output), settings).await);
+        }
+        outputs = transcoded;
+    }
+    outputs
+}
+
+// ── Recording Segments ──────────────────────────────────────────────────────
+
+/// Template variable replaced by the segment number of a split recording.
+const PART_VARIABLE: &str = "{part}";
+
+/// MPEG-TS packet size; yt-dlp pipes live streams as MPEG-TS.
+const TS_PACKET_BYTES: usize = 188;
+
+/// How far past a due split to wait for the start of a stream fragment
+/// before cutting at the next packet instead.
+const SPLIT_SEARCH_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How often the segment list in `DownloadProgress` is refreshed.
+const SEGMENT_SCAN_INTERVAL_SECS: u64 = 5;
+
+const RECORDING_PIPE_CHUNK_BYTES: usize = 64 * 1024;
+const BYTES_PER_GB: f64 = 1_000_000_000.0;
+
+/// Extensions stripped from the output filename before the segment suffix.
+const RECORDING_EXTENSIONS: &[&str] = &["ts", "mp4", "mkv", "flv", "webm", "mov", "m4a"];
+
+impl RecordingLimits {
+    fn splits(&self) -> bool {
+        self.split_every_minutes > 0 || self.split_every_gb > 0.0
+    }
+
+    fn is_active(&self) -> bool {
+        self.splits() || self.max_duration_minutes > 0 || self.max_size_gb > 0.0
+    }
+
+    /// Whether a recording `elapsed_secs` long with `bytes` written has hit a cap.
+    fn cap_reached(&self, elapsed_secs: u64, bytes: u64) -> bool {
+        (self.max_duration_minutes > 0 && elapsed_secs >= self.max_duration_minutes as u64 * 60)
+            || (self.max_size_gb > 0.0 && bytes as f64 >= self.max_size_gb * BYTES_PER_GB)
+    }
+
+    /// Whether a segment `elapsed_secs` long with `bytes` written is due to
+    /// be split; whichever split is reached first wins.
+    fn split_due(&self, elapsed_secs: u64, bytes: u64) -> bool {
+        (self.split_every_minutes > 0 && elapsed_secs >= self.split_every_minutes as u64 * 60)
+            || (self.split_every_gb > 0.0 && bytes as f64 >= self.split_every_gb * BYTES_PER_GB)
+    }
+}
+
+/// Where to cut `chunk`, which starts `position` bytes into the piped
+/// stream, for a split that has been due for `overdue` bytes. Cuts go at a
+/// PAT packet, where HLS fragments start on a keyframe, or at the next
+/// packet boundary once `SPLIT_SEARCH_BYTES` have gone by without one.
+fn split_point(chunk: &[u8], position: u64, overdue: u64) -> Option<usize> {
+    let misalignment = (position % TS_PACKET_BYTES as u64) as usize;
+    let first = (TS_PACKET_BYTES - misalignment) % TS_PACKET_BYTES;
+    (first..chunk.len()).step_by(TS_PACKET_BYTES).find(|&i| {
+        let is_pat = chunk.len() > i + 2 && chunk[i] == 0x47 && chunk[i + 1] & 0x1f == 0 && chunk[i + 2] == 0;
+        is_pat || overdue + i as u64 >= SPLIT_SEARCH_BYTES
+    })
+}
+
+/// Filename without a trailing media extension.
+fn media_stem(filename: &str) -> &str {
+    match filename.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && RECORDING_EXTENSIONS.contains(&ext.to_lowercase().as_str()) => stem,
+        _ => filename,
+    }
+}
+
+/// Removes `{part}` and the separator before it from a single-file output name.
+fn strip_part_variable(filename: &str) -> String {
+    let re = Regex::new(r"[ _.\-]?\{part\}").unwrap();
+    re.replace_all(filename, "").to_string()
+}
+
+/// Where a download or unsplit recording is written.
+fn output_file_path(config: &DownloadConfig) -> PathBuf {
+    PathBuf::from(&config.output_folder).join(strip_part_variable(&config.output_filename))
+}
+
+/// Segment filename pattern for a split recording: `{part}` becomes the
+/// 3-digit segment number, or `_part001` is appended to the name. Segments
+/// are MPEG-TS; post-processing remuxes each one into the target container.
+fn segment_pattern(filename: &str) -> String {
+    let stem = media_stem(filename).replace('%', "%%");
+    if stem.contains(PART_VARIABLE) {
+        format!("{}.ts", stem.replace(PART_VARIABLE, "%03d"))
+    } else {
+        format!("{}_part%03d.ts", stem)
+    }
+}
+
+/// Path of segment `number` for a `segment_pattern` result.
+fn segment_path(pattern: &str, number: u32) -> String {
+    let mut path = String::new();
+    let mut rest = pattern;
+    while let Some(index) = rest.find('%') {
+        path.push_str(&rest[..index]);
+        let tail = &rest[index..];
+        if let Some(after) = tail.strip_prefix("%%") {
+            path.push('%');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("%03d") {
+            path.push_str(&format!("{:03}", number));
+            rest = after;
+        } else {
+            path.push('%');
+            rest = &tail[1..];
+        }
+    }
+    path.push_str(rest);
+    path
+}
+
+/// Segments written so far, in order.
+fn list_segments(pattern: &str) -> Vec<PathBuf> {
+    (1..)
+        .map(|number| PathBuf::from(segment_path(pattern, number)))
+        .take_while(|path| path.exists())
+        .collect()
+}
+
+fn format_byte_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1024.0 * 1024.0 {
+        format!("{:.2}MiB/s", bytes_per_sec / (1024.0 * 1024.0))
+    } else {
+        format!("{:.2}KiB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+/// Asks a recording's yt-dlp to stop and finalize what it has written.
+fn request_recording_stop(app: &AppHandle, task: &mut DownloadTask) {
+    // Send SIGTERM for graceful shutdown (allows yt-dlp to finalize the file)
+    #[cfg(unix)]
+    if let Some(pid) = task.process_id {
+        // On Unix, we can send SIGTERM which yt-dlp handles gracefully
+        unsafe {
+            libc::kill(pid as i32, libc::SIGTERM);
+        }
+    }
+
+    // On Windows, just kill the process; the "processing" status below
+    // tells the executor it was a stop rather than a failure.
+    #[cfg(not(unix))]
+    task.interrupt_run();
+
+    task.progress.status = "processing".to_string();
+    task.progress.is_recording = Some(false);
+    app.emit("download-progress", &task.progress).ok();
+}
+
+/// Outcome of a recording process.
+struct RecordingRun {
+    /// yt-dlp exited cleanly, or was stopped by the user or a cap.
+    finished: bool,
+    stderr: String,
+    /// Recorded files, in order.
+    files: Vec<PathBuf>,
+    /// Totals including the earlier parts.
+    totals: RecordingTotals,
+}
+
+/// Records straight to `output_path`, with progress parsed from yt-dlp and
+/// counted on top of `previous`. `Ok(None)` when the task disappeared or
+/// was cancelled.
+async fn record_direct(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    args: &[&str],
+    output_path: &Path,
+    previous: RecordingTotals,
+) -> Result<Option<RecordingRun>, String> {
+    log_task_command(app, task_id, "yt-dlp", &[&["-o", output_path.to_str().unwrap_or_default()], args].concat());
+
+    // Spawn yt-dlp process
+    let mut child = TokioCommand::new("yt-dlp")
+        .arg("-o")
+        .arg(output_path)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{{\"key\":\"errors.download.cannotStartYtdlp\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string())))?;
+
+    // Read stdout for progress
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "{\"key\":\"errors.download.cannotReadOutput\"}".to_string())?;
+    let stderr_capture = StderrCapture::start(&mut child, task_log(app, task_id, "yt-dlp"));
+
+    // Store process handle
+    let Some(cancel) = attach_task_process(tasks, task_id, &child).await else {
+        kill_download_process(&mut child);
+        child.wait().await.ok();
+        return Ok(None);
+    };
+    rebalance_bandwidth(app, tasks).await;
+
+    use std::time::Instant;
+    let mut lines = AsyncBufReader::new(stdout).lines();
+    let start_time = Instant::now();
+    let mut bytes: u64 = 0;
+
+    while let Some(line) = next_output_line(&mut lines, &cancel).await {
+        // Parse recording progress
+        if let Some(recording_info) = parse_recording_progress(&line) {
+            let elapsed = start_time.elapsed();
+            let duration_str = format_duration_hhmmss(previous.elapsed_secs + elapsed.as_secs());
+            bytes = recording_info.0;
+
+            let mut tasks_guard = tasks.lock().await;
+            if let Some(task) = tasks_guard.get_mut(task_id) {
+                task.progress.downloaded_bytes = previous.bytes + bytes;
+                task.progress.bitrate = Some(recording_info.1);
+                task.progress.recorded_duration = Some(duration_str);
+                app.emit("download-progress", &task.progress).ok();
+            }
+        } else if let Some(progress_info) = parse_ytdlp_progress(&line) {
+            // Fallback to standard progress parsing
+            let elapsed = start_time.elapsed();
+            let duration_str = format_duration_hhmmss(previous.elapsed_secs + elapsed.as_secs());
+
+            let mut tasks_guard = tasks.lock().await;
+            if let Some(task) = tasks_guard.get_mut(task_id) {
+                task.progress.speed = progress_info.1.clone();
+                task.progress.recorded_duration = Some(duration_str);
+                app.emit("download-progress", &task.progress).ok();
+            }
+        }
+    }
+
+    // Wait for process to complete
+    let status = wait_task_process(tasks, task_id, &mut child, &cancel).await;
+    let stop_requested = match task_status(tasks, task_id).await.as_deref() {
+        None | Some("cancelled") => return Ok(None),
+        Some(status) => status == "processing",
+    };
+    let status = status
+        .map_err(|e| format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string())))?;
+
+    let files = vec![output_path.to_path_buf()];
+    let totals = RecordingTotals {
+        elapsed_secs: previous.elapsed_secs + start_time.elapsed().as_secs(),
+        bytes: previous.bytes + bytes,
+    };
+    if status.success() || stop_requested {
+        stderr_capture.finish().await;
+        Ok(Some(RecordingRun { finished: true, stderr: String::new(), files, totals }))
+    } else {
+        Ok(Some(RecordingRun { finished: false, stderr: stderr_capture.finish().await, files, totals }))
+    }
+}
+
+/// ffmpeg copying the piped stream from its stdin into one file as MPEG-TS.
+struct SegmentWriter {
+    ffmpeg: TokioChild,
+    sink: tokio::process::ChildStdin,
+    stderr: StderrCapture,
+}
+
+impl SegmentWriter {
+    fn spawn(app: &AppHandle, task_id: &str, output: &Path) -> Result<Self, String> {
+        let mut ffmpeg = TokioCommand::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-y", "-i", "pipe:0", "-map", "0", "-c", "copy", "-f", "mpegts"])
+            .arg(output)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => "{\"key\":\"errors.download.ffmpeg_missing\"}".to_string(),
+                _ => format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string())),
+            })?;
+        let stderr = StderrCapture::start(&mut ffmpeg, task_log(app, task_id, "ffmpeg"));
+        match ffmpeg.stdin.take() {
+            Some(sink) => Ok(Self { ffmpeg, sink, stderr }),
+            None => Err("{\"key\":\"errors.download.cannotReadOutput\"}".to_string()),
+        }
+    }
+
+    /// Close the input and wait for ffmpeg to finalize the file.
+    async fn finish(mut self) {
+        drop(self.sink);
+        self.ffmpeg.wait().await.ok();
+        let errors = self.stderr.finish().await;
+        if !errors.trim().is_empty() {
+            log::warn!("[Recording] ffmpeg segment writer: {}", errors.trim());
+        }
+    }
+}
+
+/// Records through `yt-dlp -o -` into ffmpeg. The byte pump in between
+/// tracks size, starts a new segment file when a split is due and stops the
+/// recording at its caps. yt-dlp is the task's process, so stop and cancel
+/// work as for a direct recording, and its closed output lets ffmpeg
+/// finalize. Later reconnect `part`s continue the segment numbering and the
+/// caps.
+async fn record_piped(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    args: &[&str],
+    config: &DownloadConfig,
+    part: u32,
+    previous: RecordingTotals,
+) -> Result<Option<RecordingRun>, String> {
+    use std::time::{Duration, Instant};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let limits = &config.recording_limits;
+    let output_folder = PathBuf::from(&config.output_folder);
+    // Split recordings write numbered segments; others one file per part.
+    let pattern = limits.splits().then(|| {
+        output_folder.join(segment_pattern(&config.output_filename)).to_string_lossy().into_owned()
+    });
+    let single_output = recording_part_path(
+        &output_folder.join(format!("{}.ts", media_stem(&strip_part_variable(&config.output_filename)))),
+        part,
+    );
+    let first_segment = match pattern {
+        Some(ref pattern) => list_segments(pattern).len() + 1,
+        None => 1,
+    };
+    let mut segment = first_segment;
+    let output_for = |number: usize| match pattern {
+        Some(ref pattern) => PathBuf::from(segment_path(pattern, number as u32)),
+        None => single_output.clone(),
+    };
+
+    let mut writer = SegmentWriter::spawn(app, task_id, &output_for(segment))?;
+    log_task_command(app, task_id, "yt-dlp", &[&["-o", "-"], args].concat());
+
+    let mut child = match TokioCommand::new("yt-dlp")
+        .args(["-o", "-"])
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            writer.ffmpeg.kill().await.ok();
+            return Err(format!("{{\"key\":\"errors.download.cannotStartYtdlp\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string())));
+        }
+    };
+    let Some(mut stream) = child.stdout.take() else {
+        child.kill().await.ok();
+        writer.ffmpeg.kill().await.ok();
+        return Err("{\"key\":\"errors.download.cannotReadOutput\"}".to_string());
+    };
+    let stderr_capture = StderrCapture::start(&mut child, task_log(app, task_id, "yt-dlp"));
+
+    // Store process handle
+    let Some(cancel) = attach_task_process(tasks, task_id, &child).await else {
+        child.kill().await.ok();
+        writer.finish().await;
+        return Ok(None);
+    };
+    rebalance_bandwidth(app, tasks).await;
+
+    let start_time = Instant::now();
+    let mut last_scan = start_time;
+    let mut window_start = start_time;
+    let mut window_bytes: u64 = 0;
+    let mut total_bytes: u64 = previous.bytes;
+    // Position in this yt-dlp's output, and where the current segment and
+    // a pending split began in it.
+    let mut position: u64 = 0;
+    let mut segment_start = (start_time, 0u64);
+    let mut split_due_at: Option<u64> = None;
+    let mut cap_reached = false;
+    let mut writer = Some(writer);
+    let mut buffer = vec![0u8; RECORDING_PIPE_CHUNK_BYTES];
+
+    loop {
+        let read = tokio::select! {
+            _ = cancel.cancelled() => break,
+            read = stream.read(&mut buffer) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            },
+        };
+        let mut chunk = &buffer[..read];
+
+        if pattern.is_some() && split_due_at.is_none()
+            && limits.split_due(segment_start.0.elapsed().as_secs(), position - segment_start.1)
+        {
+            split_due_at = Some(position);
+        }
+        if let Some(due_at) = split_due_at {
+            if let Some(cut) = split_point(chunk, position, position - due_at) {
+                let Some(mut current) = writer.take() else { break };
+                if current.sink.write_all(&chunk[..cut]).await.is_err() {
+                    writer = Some(current);
+                    break;
+                }
+                current.finish().await;
+                segment += 1;
+                match SegmentWriter::spawn(app, task_id, &output_for(segment)) {
+                    Ok(next) => writer = Some(next),
+                    Err(e) => {
+                        // Closing our end stops yt-dlp; the reconnect logic takes over.
+                        log::warn!("[Recording] Cannot start segment {}: {}", segment, e);
+                        break;
+                    }
+                }
+                position += cut as u64;
+                chunk = &chunk[cut..];
+                segment_start = (Instant::now(), position);
+                split_due_at = None;
+            }
+        }
+
+        let Some(current) = writer.as_mut() else { break };
+        if current.sink.write_all(chunk).await.is_err() {
+            // ffmpeg is gone; closing our end stops yt-dlp as well
+            break;
+        }
+        position += chunk.len() as u64;
+        total_bytes += read as u64;
+        window_bytes += read as u64;
+
+        let window = window_start.elapsed();
+        if window < Duration::from_secs(1) {
+            continue;
+        }
+        let elapsed = previous.elapsed_secs + start_time.elapsed().as_secs();
+        let segments = (pattern.is_some()
+            && last_scan.elapsed() >= Duration::from_secs(SEGMENT_SCAN_INTERVAL_SECS))
+        .then(|| {
+            last_scan = Instant::now();
+            pattern.as_deref().map(list_segments).unwrap_or_default()
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        });
+
+        let mut tasks_guard = tasks.lock().await;
+        if let Some(task) = tasks_guard.get_mut(task_id) {
+            task.progress.downloaded_bytes = total_bytes;
+            task.progress.bitrate = Some(format_byte_rate(window_bytes as f64 / window.as_secs_f64()));
+            task.progress.recorded_duration = Some(format_duration_hhmmss(elapsed));
+            if let Some(segments) = segments {
+                task.progress.segments = segments;
+            }
+            app.emit("download-progress", &task.progress).ok();
+
+            if !cap_reached && limits.cap_reached(elapsed, total_bytes) {
+                cap_reached = true;
+                request_recording_stop(app, task);
+            }
+        }
+        window_start = Instant::now();
+        window_bytes = 0;
+    }
+    drop(stream);
+    if let Some(writer) = writer {
+        writer.finish().await;
+    }
+
+    // Wait for process to complete
+    let status = wait_task_process(tasks, task_id, &mut child, &cancel).await;
+    let stderr = stderr_capture.finish().await;
+
+    let stop_requested = match task_status(tasks, task_id).await.as_deref() {
+        None | Some("cancelled") => return Ok(None),
+        Some(status) => status == "processing",
+    };
+    let status = status.map_err(|e| {
+        format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()))
+    })?;
+
+    // Only this part's segments; earlier parts are already collected.
+    let files: Vec<PathBuf> = match pattern {
+        Some(ref pattern) => list_segments(pattern).into_iter().skip(first_segment - 1).collect(),
+        None => vec![single_output],
+    }
+    .into_iter()
+    .filter(|path| fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false))
+    .collect();
+
+    Ok(Some(RecordingRun {
+        finished: status.success() || stop_requested || cap_reached,
+        stderr,
+        files,
+        totals: RecordingTotals {
+            elapsed_secs: previous.elapsed_secs + start_time.elapsed().as_secs(),
+            bytes: total_bytes,
+        },
+    }))
+}
+
+// ── Recording Post-Processing ───────────────────────────────────────────────
+
+/// Post-processing steps applied to a finished live recording.
+#[derive(Debug, Clone)]
+struct RecordingPostProcess {
+    /// Stream-copy the recording into the preset's container with fixed timestamps.
+    remux: bool,
+    /// Move the moov atom to the front of MP4/MOV output.
+    faststart: bool,
+    /// Check the remuxed file's duration and streams against the original.
+    verify: bool,
+}
+
+impl Default for RecordingPostProcess {
+    fn default() -> Self {
+        Self { remux: true, faststart: true, verify: true }
+    }
+}
+
+impl RecordingPostProcess {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            remux: config.recording_remux,
+            faststart: config.recording_faststart,
+            verify: config.recording_verify,
+        }
+    }
+}
+
+/// Allowed duration difference between a file and its processed output, in
+/// seconds or as a fraction of the original, whichever is larger.
+const OUTPUT_DURATION_TOLERANCE_SECS: f64 = 2.0;
+const OUTPUT_DURATION_TOLERANCE_RATIO: f64 = 0.01;
+
+#[derive(Debug, Default, Clone)]
+struct MediaProbe {
+    duration: Option<f64>,
+    /// Container start time; negative when the first packets precede the
+    /// point the file was cut at.
+    start_time: Option<f64>,
+    video_streams: usize,
+    audio_streams: usize,
+    /// Codec and size of the first video stream, e.g. "h264 1920x1080".
+    video_format: Option<String>,
+}
+
+async fn probe_media(path: &Path) -> Result<MediaProbe, String> {
+    let output = TokioCommand::new("ffprobe")
+        .args([
+            "-v", "error",
+            "-show_entries", "format=duration,start_time:stream=codec_type,codec_name,width,height",
+            "-of", "json",
+        ])
+        .arg(path)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
 
-    {
-        let mut tasks_guard = tasks.lock().unwrap();
-        tasks_guard.insert(task_id.clone(), task);
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
     }
 
-    // Emit initial progress
-    app.emit("download-progress", &progress).ok();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
 
-    // Start download in background
-    let app_clone = app.clone();
-    let tasks_clone = tasks.inner().clone();
-    let task_id_clone = task_id.clone();
+    let duration = json["format"]["duration"]
+        .as_str()
+        .and_then(|d| d.parse::<f64>().ok());
+    let start_time = json["format"]["start_time"]
+        .as_str()
+        .and_then(|d| d.parse::<f64>().ok());
+    let streams = json["streams"].as_array().cloned().unwrap_or_default();
+    let count = |kind: &str| streams.iter().filter(|s| s["codec_type"] == kind).count();
 
-    tokio::spawn(async move {
-        execute_download(app_clone, tasks_clone, task_id_clone).await;
+    let video_format = streams.iter().find(|s| s["codec_type"] == "video").map(|s| {
+        format!("{} {}x{}", s["codec_name"].as_str().unwrap_or("?"), s["width"], s["height"])
     });
 
-    Ok(task_id)
+    Ok(MediaProbe {
+        duration,
+        start_time,
+        video_streams: count("video"),
+        audio_streams: count("audio"),
+        video_format,
+    })
 }
 
-#[tauri::command]
-async fn start_recording(
-    app: AppHandle,
-    config: DownloadConfig,
-    tasks: tauri::State<'_, DownloadTasks>,
-) -> Result<String, String> {
-    if !config.video_info.is_live {
-        return Err("errors.download.not_livestream".to_string());
+/// Container to remux into: the preset's format, or MP4 for raw MPEG-TS/FLV
+/// output when the preset leaves it on "auto".
+fn remux_target_extension(input_path: &Path, container_format: &str) -> String {
+    if container_format != "auto" {
+        return container_format.to_lowercase();
     }
+    match input_path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()) {
+        Some(ext) if ext == "ts" || ext == "flv" => "mp4".to_string(),
+        Some(ext) => ext,
+        None => "mp4".to_string(),
+    }
+}
 
-    let task_id = Uuid::new_v4().to_string();
+/// Parse the output position from an ffmpeg `-progress` line, in seconds.
+/// `out_time_ms` is also in microseconds despite its name.
+fn parse_ffmpeg_progress_time(line: &str) -> Option<f64> {
+    let value = line
+        .strip_prefix("out_time_us=")
+        .or_else(|| line.strip_prefix("out_time_ms="))?;
+    value.trim().parse::<i64>().ok().map(|us| us.max(0) as f64 / 1_000_000.0)
+}
 
-    let progress = DownloadProgress {
-        task_id: task_id.clone(),
-        status: "recording".to_string(),
-        title: config.video_info.title.clone(),
-        percentage: 0.0,
-        speed: "0 B/s".to_string(),
-        eta: "直播錄製中...".to_string(),
-        downloaded_bytes: 0,
-        total_bytes: 0,
-        output_path: None,
-        error_message: None,
-        is_recording: Some(true),
-        recorded_duration: Some("00:00:00".to_string()),
-        bitrate: Some("N/A".to_string()),
-    };
+async fn set_processing_step(app: &AppHandle, tasks: &DownloadTasks, task_id: &str, step: &str, percentage: f64) {
+    let mut tasks_guard = tasks.lock().await;
+    if let Some(task) = tasks_guard.get_mut(task_id) {
+        task.progress.status = "processing".to_string();
+        task.progress.processing_step = Some(step.to_string());
+        task.progress.percentage = percentage;
+        app.emit("download-progress", &task.progress).ok();
+    }
+}
 
-    let task = DownloadTask {
-        config: config.clone(),
-        progress: progress.clone(),
-        process: None,
-        paused: false,
-    };
+async fn is_task_cancelled(tasks: &DownloadTasks, task_id: &str) -> bool {
+    let tasks_guard = tasks.lock().await;
+    tasks_guard
+        .get(task_id)
+        .map(|t| t.progress.status == "cancelled")
+        .unwrap_or(true)
+}
 
+/// An ffmpeg command with the options shared by post-processing steps:
+/// quiet logging, machine-readable `-progress` on stdout, and (on Unix) its
+/// own process group so pause/cancel signals reach it.
+fn ffmpeg_command() -> Command {
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-nostats", "-progress", "pipe:1", "-y"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
     {
-        let mut tasks_guard = tasks.lock().unwrap();
-        tasks_guard.insert(task_id.clone(), task);
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
     }
+    command
+}
 
-    // Emit initial progress
-    app.emit("download-progress", &progress).ok();
+/// Run an `ffmpeg_command` for a task, reporting its position as the
+/// percentage of `step`. The child is the task's process while it runs, so
+/// cancelling the task stops it.
+async fn run_ffmpeg_step(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    command: Command,
+    step: &str,
+    total_duration: Option<f64>,
+) -> Result<(), String> {
+    let mut child = TokioCommand::from(command)
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Failed to read ffmpeg output")?;
+    let stderr_capture = StderrCapture::start(&mut child, task_log(app, task_id, "ffmpeg"));
+
+    let Some(cancel) = attach_task_process(tasks, task_id, &child).await else {
+        kill_download_process(&mut child);
+        child.wait().await.ok();
+        return Err("Task cancelled".to_string());
+    };
 
-    // Start recording in background
-    let app_clone = app.clone();
-    let tasks_clone = tasks.inner().clone();
-    let task_id_clone = task_id.clone();
+    let mut lines = AsyncBufReader::new(stdout).lines();
+    while let Some(line) = next_output_line(&mut lines, &cancel).await {
+        if let (Some(position), Some(total)) = (parse_ffmpeg_progress_time(&line), total_duration) {
+            if total > 0.0 {
+                let percentage = (position / total * 100.0).clamp(0.0, 99.0);
+                set_processing_step(app, tasks, task_id, step, percentage).await;
+            }
+        }
+    }
 
-    tokio::spawn(async move {
-        execute_recording(app_clone, tasks_clone, task_id_clone).await;
-    });
+    let status = wait_task_process(tasks, task_id, &mut child, &cancel)
+        .await
+        .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+    if cancel.is_cancelled() {
+        return Err("Task cancelled".to_string());
+    }
 
-    Ok(task_id)
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg {} failed: {}", step, stderr_capture.finish().await.trim()))
+    }
 }
 
-async fn execute_download(app: AppHandle, tasks: DownloadTasks, task_id: String) {
-    let config = {
-        let tasks_guard = tasks.lock().unwrap();
-        match tasks_guard.get(&task_id) {
-            Some(task) => task.config.clone(),
-            None => return,
-        }
-    };
+/// Stream-copy `input` into `output`, regenerating timestamps.
+async fn remux_recording(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input: &Path,
+    output: &Path,
+    faststart: bool,
+    total_duration: Option<f64>,
+) -> Result<(), String> {
+    let mut command = ffmpeg_command();
+    command
+        .args(["-fflags", "+genpts"])
+        .arg("-i")
+        .arg(input)
+        .args(remux_output_args(output, faststart))
+        .arg(output);
 
-    // Load auth config
-    let auth_config = get_auth_config(app.clone()).await.ok();
+    run_ffmpeg_step(app, tasks, task_id, command, "remux", total_duration).await
+}
 
-    // Update status to downloading
-    {
-        let mut tasks_guard = tasks.lock().unwrap();
-        if let Some(task) = tasks_guard.get_mut(&task_id) {
-            task.progress.status = "downloading".to_string();
-            app.emit("download-progress", &task.progress).ok();
-        }
+/// Output options for `remux_recording`: copy every video and audio stream,
+/// moving the index to the front of MP4-family files when asked.
+fn remux_output_args(output: &Path, faststart: bool) -> Vec<String> {
+    let mut args: Vec<String> = ["-map", "0:v?", "-map", "0:a?", "-c", "copy", "-avoid_negative_ts", "make_zero"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if faststart && is_mp4_family(output) {
+        args.extend(["-movflags".to_string(), "+faststart".to_string()]);
     }
+    args
+}
 
-    // Build yt-dlp command
-    let output_path = PathBuf::from(&config.output_folder).join(&config.output_filename);
-    let output_template = output_path.to_str().unwrap();
+/// Final and temporary paths of a remuxed recording, next to the original.
+fn remux_output_paths(input_path: &Path, container_format: &str) -> (PathBuf, PathBuf) {
+    let extension = remux_target_extension(input_path, container_format);
+    let stem = input_path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    (
+        input_path.with_file_name(format!("{}.{}", stem, extension)),
+        input_path.with_file_name(format!("{}.remux.{}", stem, extension)),
+    )
+}
 
-    let mut args = vec![
-        "--newline",
-        "--progress",
-        "-f", &config.format_id,
-        "-o", output_template,
-    ];
+fn is_mp4_family(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("mp4" | "mov" | "m4a")
+    )
+}
 
-    // Add authentication arguments
-    let cookies_path_storage;
-    if let Some(ref auth) = auth_config {
-        // YouTube cookies
-        if config.video_info.platform == "youtube" {
-            if let Some(ref cookies_path) = auth.youtube_cookies_path {
-                cookies_path_storage = cookies_path.clone();
-                args.push("--cookies");
-                args.push(&cookies_path_storage);
-            }
-        }
+/// Check that a processed file kept every stream and the length of the original.
+async fn verify_processed_output(original: &MediaProbe, output_path: &Path) -> Result<(), String> {
+    compare_processed_output(original, &probe_media(output_path).await?)
+}
 
-        // Twitch token (would be used if yt-dlp supports it via config)
-        // For now, yt-dlp handles Twitch auth differently
-        // We may need to set environment variables or use streamlink instead
+fn compare_processed_output(original: &MediaProbe, processed: &MediaProbe) -> Result<(), String> {
+    if processed.video_streams + processed.audio_streams == 0 {
+        return Err("Output file has no streams".to_string());
+    }
+    if processed.video_streams != original.video_streams || processed.audio_streams != original.audio_streams {
+        return Err(format!(
+            "Stream mismatch: {} video / {} audio, expected {} / {}",
+            processed.video_streams, processed.audio_streams, original.video_streams, original.audio_streams
+        ));
     }
 
-    // Add time range if specified
-    let download_sections = config.time_range.as_ref().and_then(|time_range| {
-        match (&time_range.start, &time_range.end) {
-            (Some(start), Some(end)) => {
-                let start_seconds = parse_time_to_seconds(start).unwrap_or(0);
-                let end_seconds = parse_time_to_seconds(end).unwrap_or(0);
-                let start_normalized = normalize_time_to_hhmmss(start_seconds);
-                let end_normalized = normalize_time_to_hhmmss(end_seconds);
-                Some(format!("*{}-{}", start_normalized, end_normalized))
-            }
-            _ => None,
+    let duration = processed.duration.filter(|d| *d > 0.0).ok_or("Output file has no duration")?;
+    if let Some(expected) = original.duration {
+        let tolerance = OUTPUT_DURATION_TOLERANCE_SECS.max(expected * OUTPUT_DURATION_TOLERANCE_RATIO);
+        if (duration - expected).abs() > tolerance {
+            return Err(format!("Duration mismatch: {:.1}s, expected {:.1}s", duration, expected));
         }
-    });
-    if let Some(ref sections) = download_sections {
-        args.push("--download-sections");
-        args.push(sections);
     }
 
-    // Add container format
-    if config.container_format != "auto" {
-        args.push("--remux-video");
-        args.push(&config.container_format);
+    Ok(())
+}
+
+/// Remux a finished recording into its target container and verify the
+/// result, reporting progress as "processing". Returns the path of the file
+/// to keep; the original recording is kept whenever a step fails.
+async fn post_process_recording(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input_path: &Path,
+    container_format: &str,
+    steps: &RecordingPostProcess,
+) -> String {
+    let original = input_path.to_str().unwrap_or_default().to_string();
+    if !steps.remux || !input_path.exists() {
+        return original;
     }
 
-    args.push(&config.url);
+    set_processing_step(app, tasks, task_id, "remux", 0.0).await;
 
-    // Spawn yt-dlp process
-    let mut child = match Command::new("yt-dlp")
-        .args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        Ok(child) => child,
+    let input_probe = match probe_media(input_path).await {
+        Ok(probe) => probe,
         Err(e) => {
-            let msg = format!("{{\"key\":\"errors.download.cannotStartYtdlp\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
-            update_download_error(&app, &tasks, &task_id, &msg).await;
-            return;
+            log::warn!("[PostProcess] Cannot probe {}: {}; keeping original", original, e);
+            return original;
         }
     };
 
-    // Read stdout for progress (before storing process)
-    let stdout = match child.stdout.take() {
-        Some(stdout) => stdout,
-        None => {
-            update_download_error(&app, &tasks, &task_id, "{\"key\":\"errors.download.cannotReadOutput\"}").await;
-            return;
-        }
+    let (target_path, temp_path) = remux_output_paths(input_path, container_format);
+
+    let result = remux_recording(
+        app, tasks, task_id, input_path, &temp_path, steps.faststart, input_probe.duration,
+    )
+    .await;
+    if result.is_ok() && steps.verify {
+        set_processing_step(app, tasks, task_id, "verify", 99.0).await;
+    }
+    let result = match result {
+        Ok(()) if steps.verify => verify_processed_output(&input_probe, &temp_path).await,
+        Ok(()) => match fs::metadata(&temp_path) {
+            Ok(m) if m.len() > 0 => Ok(()),
+            _ => Err("Remuxed file is empty".to_string()),
+        },
+        Err(e) => Err(e),
     };
 
-    // Store process handle
-    {
-        let mut tasks_guard = tasks.lock().unwrap();
-        if let Some(task) = tasks_guard.get_mut(&task_id) {
-            task.process = Some(child);
+    settle_remuxed_output(input_path, &temp_path, &target_path, result)
+}
+
+/// Move a checked remux into place and drop the original, or drop the remux
+/// and keep the original when any step failed. Returns the path to keep.
+fn settle_remuxed_output(input_path: &Path, temp_path: &Path, target_path: &Path, result: Result<(), String>) -> String {
+    let original = input_path.to_str().unwrap_or_default().to_string();
+    let result = result.and_then(|_| {
+        fs::rename(temp_path, target_path).map_err(|e| format!("Failed to move remuxed file: {}", e))
+    });
+
+    match result {
+        Ok(()) => {
+            if target_path != input_path {
+                if let Err(e) = fs::remove_file(input_path) {
+                    log::warn!("[PostProcess] Failed to remove original {}: {}", original, e);
+                }
+            }
+            log::info!("[PostProcess] Remuxed {} -> {}", original, target_path.display());
+            target_path.to_str().unwrap_or_default().to_string()
+        }
+        Err(e) => {
+            log::warn!("[PostProcess] {}; keeping original {}", e, original);
+            fs::remove_file(temp_path).ok();
+            original
         }
     }
+}
 
-    use std::io::{BufRead, BufReader};
-    let reader = BufReader::new(stdout);
+// ── Transcoding ─────────────────────────────────────────────────────────────
 
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(_) => break,
-        };
+/// Optional re-encode of finished downloads and recordings (`enable_transcoder`).
+#[derive(Debug, Clone)]
+struct TranscodeSettings {
+    /// Target video codec: "h264" or "hevc".
+    codec: String,
+    /// Constant quality, used when `bitrate_kbps` is 0.
+    crf: u32,
+    bitrate_kbps: u32,
+    /// Maximum output height such as "1080p", or "original".
+    resolution: String,
+    hardware_encoding: bool,
+    /// "auto" or a specific encoder from `get_available_hardware_encoders`.
+    hardware_encoder: String,
+}
 
-        // Parse progress line
-        if let Some(progress_info) = parse_ytdlp_progress(&line) {
-            let mut tasks_guard = tasks.lock().unwrap();
-            if let Some(task) = tasks_guard.get_mut(&task_id) {
-                task.progress.percentage = progress_info.0;
-                task.progress.speed = progress_info.1;
-                task.progress.eta = progress_info.2;
-                app.emit("download-progress", &task.progress).ok();
-            }
+impl TranscodeSettings {
+    fn from_config(config: &AppConfig) -> Option<Self> {
+        if !config.enable_transcoder {
+            return None;
         }
+        Some(Self {
+            codec: config.transcode_codec.clone(),
+            crf: config.transcode_crf,
+            bitrate_kbps: config.transcode_bitrate_kbps,
+            resolution: config.transcode_resolution.clone(),
+            hardware_encoding: config.enable_hardware_encoding,
+            hardware_encoder: config.hardware_encoder.clone(),
+        })
+    }
+}
+
+/// Hardware backends tried by "auto", most preferred first.
+const HARDWARE_ENCODER_BACKENDS: [&str; 4] = ["nvenc", "qsv", "amf", "videotoolbox"];
+
+fn encoder_codec(encoder: &str) -> &str {
+    if encoder == "libx265" || encoder.starts_with("hevc_") {
+        "hevc"
+    } else {
+        "h264"
     }
+}
 
-    // Wait for process to complete
-    let mut child = {
-        let mut tasks_guard = tasks.lock().unwrap();
-        match tasks_guard.get_mut(&task_id) {
-            Some(task) => task.process.take(),
-            None => return,
+fn software_encoder(codec: &str) -> &'static str {
+    if codec == "hevc" {
+        "libx265"
+    } else {
+        "libx264"
+    }
+}
+
+/// Encoders to try in order: the configured (or first detected) hardware
+/// encoder, then the software encoder for the same codec as a fallback.
+fn transcode_encoder_candidates(settings: &TranscodeSettings, available: &[String]) -> Vec<String> {
+    let mut candidates = Vec::new();
+
+    if settings.hardware_encoding {
+        if settings.hardware_encoder == "auto" {
+            if let Some(encoder) = HARDWARE_ENCODER_BACKENDS
+                .iter()
+                .map(|backend| format!("{}_{}", settings.codec, backend))
+                .find(|name| available.contains(name))
+            {
+                candidates.push(encoder);
+            }
+        } else {
+            candidates.push(settings.hardware_encoder.clone());
         }
+    }
+
+    let codec = candidates.first().map(|e| encoder_codec(e)).unwrap_or(&settings.codec);
+    candidates.push(software_encoder(codec).to_string());
+    candidates
+}
+
+/// Rate-control arguments for `encoder`: a target bitrate when one is set,
+/// otherwise the encoder's constant-quality mode at `crf`.
+fn encoder_quality_args(encoder: &str, crf: u32, bitrate_kbps: u32) -> Vec<String> {
+    if bitrate_kbps > 0 {
+        return vec!["-b:v".to_string(), format!("{}k", bitrate_kbps)];
+    }
+
+    let crf = crf.min(51);
+    let q = crf.to_string();
+    let args: Vec<&str> = if encoder.ends_with("_nvenc") {
+        vec!["-rc", "vbr", "-cq", &q, "-b:v", "0"]
+    } else if encoder.ends_with("_qsv") {
+        vec!["-global_quality", &q]
+    } else if encoder.ends_with("_amf") {
+        vec!["-rc", "cqp", "-qp_i", &q, "-qp_p", &q]
+    } else if encoder.ends_with("_videotoolbox") {
+        // VideoToolbox has no CRF; -q:v is 1-100 with higher meaning better.
+        return vec!["-q:v".to_string(), 100u32.saturating_sub(crf * 2).max(1).to_string()];
+    } else {
+        vec!["-crf", &q, "-preset", "medium"]
     };
+    args.into_iter().map(String::from).collect()
+}
 
-    if let Some(ref mut child) = child {
-        match child.wait() {
-            Ok(status) => {
-                if status.success() {
-                    // Download completed successfully
-                    let output_path_str = output_path.to_str().unwrap().to_string();
+fn transcode_max_height(resolution: &str) -> Option<u32> {
+    resolution.strip_suffix('p').and_then(|h| h.parse().ok())
+}
 
-                    {
-                        let mut tasks_guard = tasks.lock().unwrap();
-                        if let Some(task) = tasks_guard.get_mut(&task_id) {
-                            task.progress.status = "completed".to_string();
-                            task.progress.percentage = 100.0;
-                            task.progress.output_path = Some(output_path_str.clone());
-                            app.emit("download-progress", &task.progress).ok();
-                        }
-                    }
+/// Re-encode `input` into `output` with `encoder`.
+#[allow(clippy::too_many_arguments)]
+async fn transcode_with_encoder(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input: &Path,
+    output: &Path,
+    encoder: &str,
+    settings: &TranscodeSettings,
+    total_duration: Option<f64>,
+) -> Result<(), String> {
+    let mut command = ffmpeg_command();
+    command
+        .arg("-i")
+        .arg(input)
+        .args(["-map", "0:v?", "-map", "0:a?", "-c:v", encoder])
+        .args(encoder_quality_args(encoder, settings.crf, settings.bitrate_kbps));
 
-                    // Save to history
-                    save_download_history(&app, &config, &output_path_str, "completed", None).await;
-                } else {
-                    update_download_error(&app, &tasks, &task_id, "{\"key\":\"errors.download.failed\"}").await;
-                }
-            }
-            Err(e) => {
-                let msg = format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
-                update_download_error(&app, &tasks, &task_id, &msg).await;
-            }
+    if let Some(height) = transcode_max_height(&settings.resolution) {
+        // Downscale only; never upscale smaller sources.
+        command.args(["-vf", &format!("scale=-2:min({}\\,ih)", height)]);
+    }
+
+    let same_container = input.extension() == output.extension();
+    if same_container {
+        command.args(["-c:a", "copy"]);
+    } else {
+        command.args(["-c:a", "aac", "-b:a", "192k"]);
+    }
+
+    if is_mp4_family(output) {
+        command.args(["-movflags", "+faststart"]);
+        if encoder_codec(encoder) == "hevc" {
+            // Needed for HEVC playback in QuickTime and Apple devices.
+            command.args(["-tag:v", "hvc1"]);
         }
     }
+    command.arg(output);
+
+    run_ffmpeg_step(app, tasks, task_id, command, "transcode", total_duration).await
 }
 
-async fn execute_recording(app: AppHandle, tasks: DownloadTasks, task_id: String) {
-    let config = {
-        let tasks_guard = tasks.lock().unwrap();
-        match tasks_guard.get(&task_id) {
-            Some(task) => task.config.clone(),
-            None => return,
+/// Transcode a finished download, trying each candidate encoder until one
+/// succeeds. Returns the path of the file to keep; the original is kept
+/// when every encoder fails or the input has no video.
+async fn transcode_output(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input_path: &Path,
+    settings: &TranscodeSettings,
+) -> String {
+    let original = input_path.to_str().unwrap_or_default().to_string();
+
+    let input_probe = match probe_media(input_path).await {
+        Ok(probe) if probe.video_streams > 0 => probe,
+        Ok(_) => return original,
+        Err(e) => {
+            log::warn!("[Transcode] Cannot probe {}: {}; keeping original", original, e);
+            return original;
         }
     };
 
-    // Load auth config
-    let auth_config = get_auth_config(app.clone()).await.ok();
+    let extension = match input_path.extension().and_then(|e| e.to_str()) {
+        Some(ext @ ("mp4" | "mkv" | "mov")) => ext.to_string(),
+        _ => "mp4".to_string(),
+    };
+    let stem = input_path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let target_path = input_path.with_file_name(format!("{}.{}", stem, extension));
+    let temp_path = input_path.with_file_name(format!("{}.transcode.{}", stem, extension));
 
-    // Build output path
-    let output_path = PathBuf::from(&config.output_folder).join(&config.output_filename);
-    let output_template = output_path.to_str().unwrap();
+    let available = if settings.hardware_encoding && settings.hardware_encoder == "auto" {
+        get_available_hardware_encoders().await.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
 
-    // Build yt-dlp command for live recording
-    let mut args = vec![
-        "--newline",
-        "--progress",
-        "-f", &config.format_id,
-        "-o", output_template,
-        "--live-from-start",  // Try to record from the start of the stream
-    ];
+    for encoder in transcode_encoder_candidates(settings, &available) {
+        set_processing_step(app, tasks, task_id, "transcode", 0.0).await;
 
-    // Add authentication arguments
-    let cookies_path_storage;
-    if let Some(ref auth) = auth_config {
-        // YouTube cookies
-        if config.video_info.platform == "youtube" {
-            if let Some(ref cookies_path) = auth.youtube_cookies_path {
-                cookies_path_storage = cookies_path.clone();
-                args.push("--cookies");
-                args.push(&cookies_path_storage);
+        let result = match transcode_with_encoder(
+            app, tasks, task_id, input_path, &temp_path, &encoder, settings, input_probe.duration,
+        )
+        .await
+        {
+            Ok(()) => verify_processed_output(&input_probe, &temp_path).await,
+            Err(e) => Err(e),
+        }
+        .and_then(|_| {
+            fs::rename(&temp_path, &target_path).map_err(|e| format!("Failed to move transcoded file: {}", e))
+        });
+
+        match result {
+            Ok(()) => {
+                if target_path != input_path {
+                    if let Err(e) = fs::remove_file(input_path) {
+                        log::warn!("[Transcode] Failed to remove original {}: {}", original, e);
+                    }
+                }
+                log::info!("[Transcode] Encoded {} with {}", target_path.display(), encoder);
+                return target_path.to_str().unwrap_or_default().to_string();
+            }
+            Err(e) => {
+                fs::remove_file(&temp_path).ok();
+                if is_task_cancelled(tasks, task_id).await {
+                    return original;
+                }
+                log::warn!("[Transcode] {} failed: {}", encoder, e);
             }
         }
     }
 
-    // Add container format
-    if config.container_format != "auto" {
-        args.push("--remux-video");
-        args.push(&config.container_format);
+    log::warn!("[Transcode] All encoders failed; keeping original {}", original);
+    original
+}
+
+// ── Precise Cutting ─────────────────────────────────────────────────────────
+
+/// Seconds downloaded on each side of a precise cut, enough to contain the
+/// keyframe before the cut on YouTube and Twitch VODs.
+const PRECISE_CUT_PADDING_SECS: i64 = 10;
+/// Quality for re-encoded cut segments; high so boundaries match the copied middle.
+const PRECISE_CUT_CRF: u32 = 18;
+/// Re-encoded boundary segments shorter than this are skipped.
+const PRECISE_CUT_MIN_SEGMENT_SECS: f64 = 0.01;
+
+/// Trim of a padded download back to the requested range.
+#[derive(Debug, Clone)]
+struct PreciseCut {
+    /// Start of the requested range relative to the start of the padded
+    /// section that was requested from yt-dlp.
+    offset: f64,
+    duration: f64,
+    /// "boundaries" or "full".
+    mode: String,
+}
+
+/// Padded download range for a precise cut of `start..end`, clamped to the
+/// start of the video and, when known, to its duration.
+fn precise_cut_padding(start: i64, end: i64, duration: Option<i64>) -> (i64, i64) {
+    let padded_end = end + PRECISE_CUT_PADDING_SECS;
+    (
+        (start - PRECISE_CUT_PADDING_SECS).max(0),
+        duration.map_or(padded_end, |d| padded_end.min(d.max(end))),
+    )
+}
+
+/// Where the requested range starts in a downloaded section. A stream-copied
+/// section begins at the keyframe before its requested start, which stays
+/// at timestamp 0, so a negative `start_time` is how much earlier the file
+/// really starts. A section never starts after its requested start, so
+/// positive values (muxer delays) are ignored.
+fn section_cut_offset(requested_offset: f64, start_time: Option<f64>) -> f64 {
+    requested_offset - start_time.unwrap_or(0.0).min(0.0)
+}
+
+/// Keyframe times of the first video stream, relative to the start of the
+/// file (the timeline `-ss` uses).
+async fn probe_keyframes(path: &Path) -> Result<Vec<f64>, String> {
+    let output = TokioCommand::new("ffprobe")
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-skip_frame", "nokey",
+            "-show_entries", "format=start_time:frame=pts_time",
+            "-of", "json",
+        ])
+        .arg(path)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
     }
 
-    args.push(&config.url);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+    let parse = |v: &serde_json::Value| v.as_str().and_then(|s| s.parse::<f64>().ok());
+    let start_time = parse(&json["format"]["start_time"]).unwrap_or(0.0);
 
-    // Spawn yt-dlp process
-    let mut child = match Command::new("yt-dlp")
-        .args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        Ok(child) => child,
-        Err(e) => {
-            let msg = format!("{{\"key\":\"errors.download.cannotStartYtdlp\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
-            update_download_error(&app, &tasks, &task_id, &msg).await;
-            return;
-        }
-    };
+    Ok(json["frames"]
+        .as_array()
+        .map(|frames| frames.iter().filter_map(|f| parse(&f["pts_time"])).map(|t| t - start_time).collect())
+        .unwrap_or_default())
+}
 
-    // Read stdout for progress
-    let stdout = match child.stdout.take() {
-        Some(stdout) => stdout,
-        None => {
-            update_download_error(&app, &tasks, &task_id, "{\"key\":\"errors.download.cannotReadOutput\"}").await;
-            return;
-        }
-    };
+/// The stream-copyable middle of a cut from `start` to `end`: from the first
+/// keyframe at or after `start` to the last keyframe at or before `end`.
+/// `None` when no whole GOP fits inside the range.
+fn smart_cut_plan(keyframes: &[f64], start: f64, end: f64) -> Option<(f64, f64)> {
+    let first = keyframes.iter().copied().find(|&k| k >= start - PRECISE_CUT_MIN_SEGMENT_SECS)?;
+    let last = keyframes.iter().copied().rev().find(|&k| k <= end + PRECISE_CUT_MIN_SEGMENT_SECS)?;
+    (first < last).then_some((first.max(start), last.min(end)))
+}
 
-    // Store process handle
-    {
-        let mut tasks_guard = tasks.lock().unwrap();
-        if let Some(task) = tasks_guard.get_mut(&task_id) {
-            task.process = Some(child);
+/// Codec of an ffprobe `video_format` ("h264 1920x1080" -> "h264").
+fn probe_codec(probe: &MediaProbe) -> Option<&str> {
+    probe.video_format.as_deref().and_then(|f| f.split(' ').next())
+}
+
+/// Re-encode the whole requested range. Input seeking while decoding is
+/// frame-accurate in ffmpeg.
+async fn full_precise_cut(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input: &Path,
+    output: &Path,
+    probe: &MediaProbe,
+    cut: &PreciseCut,
+) -> Result<(), String> {
+    let mut command = ffmpeg_command();
+    command
+        .args(["-ss", &format!("{:.3}", cut.offset)])
+        .arg("-i")
+        .arg(input)
+        .args(["-t", &format!("{:.3}", cut.duration), "-map", "0:v?", "-map", "0:a?"]);
+
+    if probe.video_streams > 0 {
+        let encoder = software_encoder(probe_codec(probe).unwrap_or("h264"));
+        command
+            .args(["-c:v", encoder])
+            .args(encoder_quality_args(encoder, PRECISE_CUT_CRF, 0))
+            .args(["-c:a", "aac", "-b:a", "192k"]);
+        if is_mp4_family(output) {
+            command.args(["-movflags", "+faststart"]);
         }
+    } else {
+        // Audio packets are short enough that a copy cut is accurate.
+        command.args(["-c", "copy"]);
     }
+    command.arg(output);
 
-    use std::io::{BufRead, BufReader};
-    use std::time::Instant;
-    let reader = BufReader::new(stdout);
-    let start_time = Instant::now();
-
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(_) => break,
-        };
+    run_ffmpeg_step(app, tasks, task_id, command, "cut", Some(cut.duration)).await
+}
 
-        // Parse recording progress
-        if let Some(recording_info) = parse_recording_progress(&line) {
-            let elapsed = start_time.elapsed();
-            let duration_str = format_duration_hhmmss(elapsed.as_secs());
+/// Re-encode only the partial GOPs at each end and stream-copy the rest.
+/// The pieces are joined as MPEG-TS so each keeps its own parameter sets.
+async fn smart_precise_cut(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input: &Path,
+    output: &Path,
+    probe: &MediaProbe,
+    cut: &PreciseCut,
+) -> Result<(), String> {
+    let codec = probe_codec(probe).filter(|c| matches!(*c, "h264" | "hevc")).ok_or("Codec not supported by smart cut")?;
+    let encoder = software_encoder(codec);
+    let (start, end) = (cut.offset, cut.offset + cut.duration);
+    let (copy_start, copy_end) = smart_cut_plan(&probe_keyframes(input).await?, start, end).ok_or("No whole GOP inside the cut")?;
+
+    let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("cut");
+    let part = |name: &str| output.with_file_name(format!("{}.{}", stem, name));
+    let segments = [
+        (part("head.ts"), start, copy_start, true),
+        (part("middle.ts"), copy_start, copy_end, false),
+        (part("tail.ts"), copy_end, end, true),
+    ];
+    let audio_path = part("audio.mka");
+    let list_path = part("concat.txt");
 
-            let mut tasks_guard = tasks.lock().unwrap();
-            if let Some(task) = tasks_guard.get_mut(&task_id) {
-                task.progress.downloaded_bytes = recording_info.0;
-                task.progress.bitrate = Some(recording_info.1);
-                task.progress.recorded_duration = Some(duration_str);
-                app.emit("download-progress", &task.progress).ok();
+    let result = async {
+        let mut list = String::new();
+        for (path, from, to, reencode) in &segments {
+            if to - from < PRECISE_CUT_MIN_SEGMENT_SECS {
+                continue;
             }
-        } else if let Some(progress_info) = parse_ytdlp_progress(&line) {
-            // Fallback to standard progress parsing
-            let elapsed = start_time.elapsed();
-            let duration_str = format_duration_hhmmss(elapsed.as_secs());
-
-            let mut tasks_guard = tasks.lock().unwrap();
-            if let Some(task) = tasks_guard.get_mut(&task_id) {
-                task.progress.speed = progress_info.1.clone();
-                task.progress.recorded_duration = Some(duration_str);
-                app.emit("download-progress", &task.progress).ok();
+            // A copy seek lands on the keyframe at or before the target, so
+            // nudge it past rounding to be sure that is the planned keyframe.
+            let seek = if *reencode { *from } else { from + 0.001 };
+            let mut command = ffmpeg_command();
+            command
+                .args(["-ss", &format!("{:.3}", seek)])
+                .arg("-i")
+                .arg(input)
+                .args(["-t", &format!("{:.3}", to - from), "-map", "0:v:0", "-an"]);
+            if *reencode {
+                command.args(["-c:v", encoder]).args(encoder_quality_args(encoder, PRECISE_CUT_CRF, 0));
+            } else {
+                command.args(["-c:v", "copy"]);
+            }
+            command.args(["-f", "mpegts"]).arg(path);
+            run_ffmpeg_step(app, tasks, task_id, command, "cut", None).await?;
+            list.push_str(&format!("file '{}'\n", path.to_string_lossy().replace('\'', "'\\''")));
+        }
+        fs::write(&list_path, list).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+        if probe.audio_streams > 0 {
+            let mut command = ffmpeg_command();
+            command
+                .args(["-ss", &format!("{:.3}", start)])
+                .arg("-i")
+                .arg(input)
+                .args(["-t", &format!("{:.3}", cut.duration), "-map", "0:a", "-vn", "-c:a", "aac", "-b:a", "192k"])
+                .arg(&audio_path);
+            run_ffmpeg_step(app, tasks, task_id, command, "cut", None).await?;
+        }
+
+        let mut command = ffmpeg_command();
+        command.args(["-f", "concat", "-safe", "0"]).arg("-i").arg(&list_path);
+        if probe.audio_streams > 0 {
+            command.arg("-i").arg(&audio_path).args(["-map", "0:v", "-map", "1:a"]);
+        }
+        command.args(["-c", "copy"]);
+        if is_mp4_family(output) {
+            command.args(["-movflags", "+faststart"]);
+            if codec == "hevc" {
+                command.args(["-tag:v", "hvc1"]);
             }
         }
+        command.arg(output);
+        run_ffmpeg_step(app, tasks, task_id, command, "cut", Some(cut.duration)).await
     }
+    .await;
 
-    // Wait for process to complete
-    let mut child = {
-        let mut tasks_guard = tasks.lock().unwrap();
-        match tasks_guard.get_mut(&task_id) {
-            Some(task) => task.process.take(),
-            None => return,
+    for path in segments.iter().map(|(p, ..)| p).chain([&audio_path, &list_path]) {
+        fs::remove_file(path).ok();
+    }
+    result
+}
+
+/// Trim a padded download to the exact requested range. "boundaries" falls
+/// back to a full re-encode when the smart cut cannot be used. Returns the
+/// path of the file to keep; the padded download is kept if every attempt
+/// fails.
+async fn precise_cut_output(
+    app: &AppHandle,
+    tasks: &DownloadTasks,
+    task_id: &str,
+    input_path: &Path,
+    cut: &PreciseCut,
+) -> String {
+    let original = input_path.to_str().unwrap_or_default().to_string();
+
+    let input_probe = match probe_media(input_path).await {
+        Ok(probe) => probe,
+        Err(e) => {
+            log::warn!("[PreciseCut] Cannot probe {}: {}; keeping padded download", original, e);
+            return original;
         }
     };
 
-    if let Some(ref mut child) = child {
-        match child.wait() {
-            Ok(status) => {
-                if status.success() {
-                    // Recording completed - run post-processing
-                    {
-                        let mut tasks_guard = tasks.lock().unwrap();
-                        if let Some(task) = tasks_guard.get_mut(&task_id) {
-                            task.progress.status = "processing".to_string();
-                            app.emit("download-progress", &task.progress).ok();
-                        }
-                    }
-
-                    // Run FFmpeg remux if needed
-                    let final_path = post_process_recording(&output_path).await;
-                    let output_path_str = final_path.unwrap_or_else(|| output_path.to_str().unwrap().to_string());
-
-                    {
-                        let mut tasks_guard = tasks.lock().unwrap();
-                        if let Some(task) = tasks_guard.get_mut(&task_id) {
-                            task.progress.status = "completed".to_string();
-                            task.progress.percentage = 100.0;
-                            task.progress.output_path = Some(output_path_str.clone());
-                            task.progress.is_recording = Some(false);
-                            app.emit("download-progress", &task.progress).ok();
-                        }
-                    }
-
-                    // Save to history
-                    save_download_history(&app, &config, &output_path_str, "completed", None).await;
-                } else {
-                    // Check if stream was interrupted (exit code may vary)
-                    let stderr_output = child.stderr.as_mut()
-                        .and_then(|stderr| {
-                            use std::io::Read;
-                            let mut buf = String::new();
-                            stderr.read_to_string(&mut buf).ok()?;
-                            Some(buf)
-                        });
+    let extension = input_path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let stem = input_path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let temp_path = input_path.with_file_name(format!("{}.cut.{}", stem, extension));
+    let expected = MediaProbe { duration: Some(cut.duration), ..input_probe.clone() };
+    let cut = &PreciseCut { offset: section_cut_offset(cut.offset, input_probe.start_time), ..cut.clone() };
 
-                    let is_interrupted = stderr_output
-                        .as_ref()
-                        .map(|s| s.contains("Stream ended") || s.contains("connection") || s.contains("interrupt"))
-                        .unwrap_or(false);
+    let mut modes = Vec::new();
+    if cut.mode == "boundaries" && input_probe.video_streams > 0 {
+        modes.push("boundaries");
+    }
+    modes.push("full");
 
-                    if is_interrupted {
-                        // Stream interrupted - preserve recorded content
-                        let output_path_str = output_path.to_str().unwrap().to_string();
+    for mode in modes {
+        set_processing_step(app, tasks, task_id, "cut", 0.0).await;
 
-                        {
-                            let mut tasks_guard = tasks.lock().unwrap();
-                            if let Some(task) = tasks_guard.get_mut(&task_id) {
-                                task.progress.status = "stream_interrupted".to_string();
-                                task.progress.output_path = Some(output_path_str.clone());
-                                task.progress.is_recording = Some(false);
-                                task.progress.error_message = Some("{\"key\":\"errors.download.streamInterrupted\"}".to_string());
-                                app.emit("download-progress", &task.progress).ok();
-                            }
-                        }
+        let cut_result = if mode == "boundaries" {
+            smart_precise_cut(app, tasks, task_id, input_path, &temp_path, &input_probe, cut).await
+        } else {
+            full_precise_cut(app, tasks, task_id, input_path, &temp_path, &input_probe, cut).await
+        };
+        let result = match cut_result {
+            Ok(()) => verify_processed_output(&expected, &temp_path).await,
+            Err(e) => Err(e),
+        }
+        .and_then(|_| fs::rename(&temp_path, input_path).map_err(|e| format!("Failed to move cut file: {}", e)));
 
-                        save_download_history(&app, &config, &output_path_str, "stream_interrupted", Some("errors.download.streamInterrupted")).await;
-                    } else {
-                        update_download_error(&app, &tasks, &task_id, "{\"key\":\"errors.download.recordingFailed\"}").await;
-                    }
-                }
+        match result {
+            Ok(()) => {
+                log::info!("[PreciseCut] Cut {} ({})", original, mode);
+                return original;
             }
             Err(e) => {
-                let msg = format!("{{\"key\":\"errors.download.processError\",\"params\":{{\"detail\":{}}}}}", serde_json::json!(e.to_string()));
-                update_download_error(&app, &tasks, &task_id, &msg).await;
+                fs::remove_file(&temp_path).ok();
+                if is_task_cancelled(tasks, task_id).await {
+                    return original;
+                }
+                log::warn!("[PreciseCut] {} cut failed: {}", mode, e);
             }
         }
     }
-}
 
-async fn post_process_recording(input_path: &Path) -> Option<String> {
-    // Run FFmpeg remux to ensure the file is properly formatted
-    // For MVP, we'll just verify the file exists
-    if input_path.exists() {
-        Some(input_path.to_str().unwrap().to_string())
-    } else {
-        None
-    }
+    log::warn!("[PreciseCut] Keeping padded download {}", original);
+    original
 }
 
 fn parse_recording_progress(line: &str) -> Option<(u64, String)> {
@@ -4105,20 +7477,32 @@ fn parse_recording_progress(line: &str) -> Option<(u64, String)> {
     let bitrate_value = caps.get(3)?.as_str();
     let bitrate_unit = caps.get(4)?.as_str();
 
-    // Convert to bytes
-    let multiplier = match size_unit {
-        "KiB" => 1024,
-        "MiB" => 1024 * 1024,
-        "GiB" => 1024 * 1024 * 1024,
-        _ => 1,
-    };
-
-    let bytes = (size_value * multiplier as f64) as u64;
+    let bytes = size_to_bytes(size_value, size_unit);
     let bitrate = format!("{}{}", bitrate_value, bitrate_unit);
 
     Some((bytes, bitrate))
 }
 
+/// Convert a yt-dlp size such as `123.45` + `MiB` to bytes.
+fn size_to_bytes(value: f64, unit: &str) -> u64 {
+    let multiplier = match unit {
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+    (value * multiplier) as u64
+}
+
+/// Parse the total size from a yt-dlp progress line.
+/// Example: [download]  45.2% of ~123.45MiB at 2.34MiB/s ETA 00:23
+fn parse_ytdlp_total_bytes(line: &str) -> Option<u64> {
+    let re = Regex::new(r"\[download\]\s+\d+\.?\d*%\s+of\s+~?\s*([0-9.]+)([KMG]iB|B)").ok()?;
+    let caps = re.captures(line)?;
+    let value: f64 = caps.get(1)?.as_str().parse().ok()?;
+    Some(size_to_bytes(value, caps.get(2)?.as_str()))
+}
+
 fn format_duration_hhmmss(seconds: u64) -> String {
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
@@ -4137,27 +7521,616 @@ fn parse_ytdlp_progress(line: &str) -> Option<(f64, String, String)> {
     let speed = caps.get(2)?.as_str().to_string();
     let eta = caps.get(3)?.as_str().to_string();
 
-    Some((percentage, speed, eta))
+    Some((percentage, speed, eta))
+}
+
+/// Keep at most this much of a process's stderr for failure classification.
+const STDERR_TAIL_BYTES: usize = 16 * 1024;
+
+/// A task log is moved to `<task>.log.1` once it grows past this size.
+const TASK_LOG_MAX_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Task logs not written to for this long are removed when a new task starts logging.
+const TASK_LOG_RETENTION_DAYS: u64 = 14;
+
+/// `tidemark/logs/tasks` in the app data directory.
+fn task_log_dir(app: &AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_data_dir().ok()?.join("tidemark").join("logs").join("tasks");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+fn rotated_log_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Remove task logs (and their rotated halves) older than the retention period.
+fn prune_task_logs(dir: &Path) {
+    let max_age = std::time::Duration::from_secs(TASK_LOG_RETENTION_DAYS * 24 * 60 * 60);
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if expired {
+            fs::remove_file(entry.path()).ok();
+        }
+    }
+}
+
+/// Appends lines from one source (yt-dlp, ffmpeg, ...) to a task's log.
+/// The file is opened per line so every writer sees a rotation.
+#[derive(Debug, Clone)]
+struct TaskLogWriter {
+    path: PathBuf,
+    source: &'static str,
+}
+
+impl TaskLogWriter {
+    fn append(&self, line: &str) {
+        use std::io::Write;
+        if fs::metadata(&self.path).map(|m| m.len() >= TASK_LOG_MAX_BYTES).unwrap_or(false) {
+            fs::rename(&self.path, rotated_log_path(&self.path)).ok();
+        }
+        if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&self.path) {
+            writeln!(file, "[{}] [{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), self.source, line).ok();
+        }
+    }
+}
+
+/// Log writer for `task_id`; the first writer of a task prunes old logs.
+fn task_log(app: &AppHandle, task_id: &str, source: &'static str) -> Option<TaskLogWriter> {
+    let dir = task_log_dir(app)?;
+    let path = dir.join(format!("{}.log", task_id));
+    if !path.exists() {
+        prune_task_logs(&dir);
+    }
+    Some(TaskLogWriter { path, source })
+}
+
+/// Record the command a task is about to run in its log. Credentials are
+/// only ever passed as cookie file paths, so the arguments are safe to show.
+fn log_task_command(app: &AppHandle, task_id: &str, program: &str, args: &[&str]) {
+    if let Some(log) = task_log(app, task_id, "tidemark") {
+        log.append(&format!("$ {} {}", program, args.join(" ")));
+    }
+}
+
+/// Drains a child's stderr on a background task so the pipe never fills up
+/// and blocks yt-dlp, keeping the tail for classifying failures.
+struct StderrCapture {
+    handle: Option<tokio::task::JoinHandle<String>>,
+}
+
+impl StderrCapture {
+    /// Start draining; every line also goes to `log` as it arrives.
+    fn start(child: &mut TokioChild, log: Option<TaskLogWriter>) -> Self {
+        let handle = child.stderr.take().map(|stderr| {
+            tokio::spawn(async move {
+                let mut buf = String::new();
+                let mut lines = AsyncBufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Some(ref log) = log {
+                        log.append(&line);
+                    }
+                    buf.push_str(&line);
+                    buf.push('\n');
+                    if buf.len() > STDERR_TAIL_BYTES {
+                        let mut cut = buf.len() - STDERR_TAIL_BYTES;
+                        while !buf.is_char_boundary(cut) {
+                            cut += 1;
+                        }
+                        buf.drain(..cut);
+                    }
+                }
+                buf
+            })
+        });
+        Self { handle }
+    }
+
+    /// Wait for stderr to close and return what was captured.
+    async fn finish(mut self) -> String {
+        match self.handle.take() {
+            Some(handle) => handle.await.unwrap_or_default(),
+            None => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FailureKind {
+    /// Network or server hiccup; the same command may well succeed later.
+    Transient,
+    /// The content itself cannot be fetched (private, removed, geo-blocked...).
+    Permanent,
+}
+
+/// Known yt-dlp failure signatures (lowercase stderr substrings) and the
+/// `errors.download.*` key shown for them. Checked in order.
+const YTDLP_FAILURE_SIGNATURES: &[(&str, &[&str])] = &[
+    ("ffmpeg_missing", &[
+        "ffmpeg not found",
+        "ffmpeg is not installed",
+        "ffprobe and ffmpeg not found",
+        "ffmpeg could not be found",
+    ]),
+    ("cookies_expired", &[
+        "cookies are no longer valid",
+        "cookies have expired",
+        "confirm you're not a bot",
+        "confirm you\u{2019}re not a bot",
+    ]),
+    ("members_only", &[
+        "members-only",
+        "members only",
+        "join this channel",
+        "subscriber-only",
+        "subscribers-only",
+    ]),
+    ("age_restricted", &[
+        "sign in to confirm your age",
+        "age-restricted",
+        "age restricted",
+        "inappropriate for some users",
+    ]),
+    ("geo_blocked", &[
+        "available in your country",
+        "blocked it in your country",
+        "geo restrict",
+        "geo-restrict",
+        "not available from your location",
+    ]),
+    ("format_unavailable", &[
+        "requested format is not available",
+        "requested format not available",
+        "no video formats found",
+    ]),
+];
+
+/// Netscape cookies file holding the Twitch token as the `auth-token`
+/// cookie, which yt-dlp's Twitch extractor sends to the GQL API the same way
+/// the web player does. Scoped to `.twitch.tv`, so the token never reaches
+/// the CDN or any other host, as a request header would.
+fn twitch_cookie_file(token: &str) -> String {
+    format!("# Netscape HTTP Cookie File\n.twitch.tv\tTRUE\t/\tTRUE\t0\tauth-token\t{}\n", token)
+}
+
+/// yt-dlp arguments that authenticate as the user on `platform`: the YouTube
+/// cookies file, or the Twitch token written to `twitch_cookies_path` (owner
+/// read/write only).
+fn ytdlp_auth_args(auth: Option<&AuthConfig>, platform: &str, twitch_cookies_path: &Path) -> Vec<String> {
+    let Some(auth) = auth else { return Vec::new() };
+    match platform {
+        "youtube" => match auth.youtube_cookies_path {
+            Some(ref cookies_path) => vec!["--cookies".to_string(), cookies_path.clone()],
+            None => Vec::new(),
+        },
+        "twitch" => match auth.twitch_token.as_deref().and_then(twitch_oauth_token) {
+            Some(token) => match write_private_file(twitch_cookies_path, twitch_cookie_file(token).as_bytes()) {
+                Ok(()) => vec!["--cookies".to_string(), twitch_cookies_path.to_string_lossy().into_owned()],
+                Err(e) => {
+                    log::warn!("[Auth] Cannot write Twitch cookies file: {}", e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// The Twitch cookies file of one yt-dlp run. Dropping it deletes the file,
+/// so the token does not outlive the run however it ends.
+struct TwitchCookiesFile(PathBuf);
+
+impl Drop for TwitchCookiesFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.0).ok();
+    }
+}
+
+/// Where the Twitch token is written for yt-dlp, one file per run, in a
+/// directory only the owner can enter.
+fn get_twitch_cookies_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get app cache dir: {}", e))?;
+    let dir = cache_dir.join("twitch_cookies");
+
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(&dir)
+        .map_err(|e| format!("Failed to create Twitch cookies dir: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict Twitch cookies dir: {}", e))?;
+    }
+
+    Ok(dir)
+}
+
+/// Delete Twitch cookies files left behind by runs the app did not see end,
+/// and the shared file earlier versions kept.
+fn clear_twitch_cookies(app: &AppHandle) {
+    if let Ok(cache_dir) = app.path().app_cache_dir() {
+        fs::remove_dir_all(cache_dir.join("twitch_cookies")).ok();
+        fs::remove_file(cache_dir.join("twitch_cookies.txt")).ok();
+    }
+}
+
+/// yt-dlp arguments from the auth settings for one run on `platform`; a
+/// Twitch token goes to a cookies file named by `run_id`, returned to be
+/// dropped once yt-dlp has exited.
+async fn settings_auth_args(app: &AppHandle, platform: &str, run_id: &str) -> (Vec<String>, Option<TwitchCookiesFile>) {
+    let auth_config = get_auth_config(app.clone()).await.ok();
+    match get_twitch_cookies_dir(app) {
+        Ok(dir) => {
+            let cookies_path = dir.join(format!("{}.txt", run_id));
+            let args = ytdlp_auth_args(auth_config.as_ref(), platform, &cookies_path);
+            (args, Some(TwitchCookiesFile(cookies_path)))
+        }
+        Err(e) => {
+            log::warn!("[Auth] {}", e);
+            (Vec::new(), None)
+        }
+    }
+}
+
+/// yt-dlp arguments for a cookie profile: the exported cookies file, or the
+/// browser yt-dlp should read the cookies from.
+fn cookie_profile_args(profile: &CookieProfile) -> Vec<String> {
+    match profile.source.as_str() {
+        "browser" => match profile.browser.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+            Some(browser) => vec!["--cookies-from-browser".to_string(), browser.to_string()],
+            None => Vec::new(),
+        },
+        _ => match profile.cookies_path {
+            Some(ref cookies_path) => vec!["--cookies".to_string(), cookies_path.clone()],
+            None => Vec::new(),
+        },
+    }
+}
+
+/// The cookie profile `profile_id` if it still exists and is for the
+/// download's `platform`; another platform's cookies would not sign in.
+fn download_cookie_profile(profiles: Vec<CookieProfile>, profile_id: &str, platform: &str) -> Option<CookieProfile> {
+    match profiles.into_iter().find(|p| p.id == profile_id) {
+        Some(profile) if profile.platform == platform => Some(profile),
+        Some(profile) => {
+            log::warn!(
+                "[Cookies] Profile {} is for {}, not {}; using auth settings",
+                profile_id, profile.platform, platform
+            );
+            None
+        }
+        None => {
+            log::warn!("[Cookies] Profile {} not found, using auth settings", profile_id);
+            None
+        }
+    }
+}
+
+/// Authentication for a download: its cookie profile if it names one that
+/// still exists for the download's platform, otherwise the credentials from
+/// the auth settings (see `settings_auth_args`, keyed by `task_id`).
+async fn download_auth_args(app: &AppHandle, config: &DownloadConfig, task_id: &str) -> (Vec<String>, Option<TwitchCookiesFile>) {
+    if let Some(ref profile_id) = config.cookie_profile_id {
+        let profiles = get_cookie_profiles(app.clone()).unwrap_or_default();
+        if let Some(profile) = download_cookie_profile(profiles, profile_id, &config.video_info.platform) {
+            return (cookie_profile_args(&profile), None);
+        }
+    }
+    settings_auth_args(app, &config.video_info.platform, task_id).await
+}
+
+/// Cookies that carry the signed-in session on a platform; the rest of an
+/// export (consent, preferences, analytics) does not matter for access.
+struct PlatformAuthCookies {
+    platform: &'static str,
+    domains: &'static [&'static str],
+    /// Cookies yt-dlp cannot sign in without.
+    session: &'static [&'static str],
+    /// Other login cookies, listed but not needed.
+    other: &'static [&'static str],
+}
+
+const AUTH_COOKIE_NAMES: &[PlatformAuthCookies] = &[
+    PlatformAuthCookies {
+        platform: "youtube",
+        domains: &["youtube.com", "google.com"],
+        session: &["SAPISID", "__Secure-3PSID"],
+        other: &["SID", "HSID", "SSID", "APISID", "__Secure-1PSID", "LOGIN_INFO"],
+    },
+    PlatformAuthCookies {
+        platform: "twitch",
+        domains: &["twitch.tv"],
+        session: &["auth-token"],
+        other: &["persistent", "login"],
+    },
+];
+
+/// Auth cookies expiring within this window are reported as "expiring".
+const COOKIE_EXPIRY_WARNING_SECS: i64 = 3 * 24 * 60 * 60;
+
+/// Whether `content` looks like a Netscape cookies.txt export.
+fn is_netscape_cookie_file(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.starts_with("# Netscape HTTP Cookie File") || line.starts_with("# HTTP Cookie File")
+    }) || netscape_cookies(content).next().is_some()
+}
+
+/// `(domain, name, expires)` of each cookie line. `#HttpOnly_` lines are
+/// cookies, not comments; an expiry of 0 marks a session cookie.
+fn netscape_cookies(content: &str) -> impl Iterator<Item = (&str, &str, i64)> {
+    content.lines().filter_map(|line| {
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') || line.trim().is_empty() {
+            return None;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 6 {
+            return None;
+        }
+        let expires = fields[4].trim().parse::<f64>().ok()? as i64;
+        Some((fields[0], fields[5], expires))
+    })
+}
+
+/// Status of a cookies file's session cookies for `platform` at unix time
+/// `now`, with the auth cookies found: session cookies first, each group
+/// soonest-expiring first. Other login cookies are listed but do not affect
+/// the status.
+fn cookie_file_status(content: &str, platform: &str, now: i64) -> (String, Vec<AuthCookieExpiry>) {
+    let Some(PlatformAuthCookies { domains, session, other, .. }) =
+        AUTH_COOKIE_NAMES.iter().find(|cookies| cookies.platform == platform)
+    else {
+        return ("no_auth_cookies".to_string(), Vec::new());
+    };
+    let mut found: Vec<(&str, &str, i64)> = netscape_cookies(content)
+        .filter(|(domain, name, _)| {
+            let domain = domain.trim_start_matches('.');
+            (session.contains(name) || other.contains(name))
+                && domains.iter().any(|d| domain == *d || domain.ends_with(&format!(".{}", d)))
+        })
+        .collect();
+    // Cookies without an expiry last as long as the file does, so they sort
+    // last within their group.
+    found.sort_by_key(|(_, name, expires)| (!session.contains(name), if *expires > 0 { *expires } else { i64::MAX }));
+
+    let has_session = session.iter().all(|name| found.iter().any(|(_, n, _)| n == name));
+    let status = match found.first().filter(|_| has_session) {
+        None => "no_auth_cookies",
+        Some((_, _, expires)) if *expires > 0 && *expires <= now => "expired",
+        Some((_, _, expires)) if *expires > 0 && *expires <= now + COOKIE_EXPIRY_WARNING_SECS => "expiring",
+        Some(_) => "valid",
+    };
+    let auth_cookies = found
+        .into_iter()
+        .map(|(domain, name, expires)| AuthCookieExpiry {
+            name: name.to_string(),
+            domain: domain.to_string(),
+            expires_at: (expires > 0)
+                .then(|| DateTime::from_timestamp(expires, 0))
+                .flatten()
+                .map(|dt| dt.to_rfc3339()),
+        })
+        .collect();
+    (status.to_string(), auth_cookies)
+}
+
+/// Checks a profile's cookies. Browser cookie stores are encrypted and read by
+/// yt-dlp itself, so only file sources can be checked.
+fn check_cookie_profile(profile: &CookieProfile) -> CookieProfileStatus {
+    let (status, auth_cookies) = match (profile.source.as_str(), profile.cookies_path.as_deref()) {
+        ("browser", _) => ("unchecked".to_string(), Vec::new()),
+        (_, Some(path)) => match fs::read_to_string(path) {
+            Ok(content) => cookie_file_status(&content, &profile.platform, Utc::now().timestamp()),
+            Err(_) => ("unreadable".to_string(), Vec::new()),
+        },
+        (_, None) => ("unreadable".to_string(), Vec::new()),
+    };
+    CookieProfileStatus {
+        profile_id: profile.id.clone(),
+        status,
+        auth_cookies,
+    }
+}
+
+/// The bare token from what the user pasted, which may be the whole
+/// `OAuth <token>` header value or an `oauth:` chat token.
+fn twitch_oauth_token(raw: &str) -> Option<&str> {
+    let token = raw.trim();
+    let token = token
+        .strip_prefix("OAuth ")
+        .or_else(|| token.strip_prefix("oauth:"))
+        .unwrap_or(token)
+        .trim();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Errors of the Twitch extractor that mean the OAuth token is missing,
+/// expired, or belongs to an account without access (subscriber-only VODs).
+const TWITCH_AUTH_SIGNATURES: &[&str] = &[
+    "http error 401",
+    "http error 403",
+    "unauthorized",
+    "token is invalid",
+    "vod_manifest_restricted",
+    "subscriber",
+];
+
+/// The `errors.download.*` key for a recognised failure signature in `stderr`.
+fn ytdlp_failure_key(stderr: &str) -> Option<&'static str> {
+    let lower = stderr.to_lowercase();
+    // Only lines from the Twitch extractor ("[twitch:vod] ...") count as auth failures
+    let twitch_auth = lower
+        .lines()
+        .any(|line| line.contains("[twitch") && TWITCH_AUTH_SIGNATURES.iter().any(|p| line.contains(p)));
+    if twitch_auth {
+        return Some("twitch_auth_failed");
+    }
+    YTDLP_FAILURE_SIGNATURES
+        .iter()
+        .find(|(_, patterns)| patterns.iter().any(|p| lower.contains(p)))
+        .map(|(key, _)| *key)
+}
+
+/// The error shown for a failed yt-dlp run: a specific key when the failure
+/// is recognised, `fallback` (a localized message) otherwise.
+fn ytdlp_error_message(stderr: &str, fallback: &str) -> String {
+    match ytdlp_failure_key(stderr) {
+        Some(key) => serde_json::json!({"key": format!("errors.download.{}", key)}).to_string(),
+        None => fallback.to_string(),
+    }
+}
+
+/// Classify a failed yt-dlp run from its stderr. Anything not recognised as
+/// transient is treated as permanent, so unknown errors are not retried.
+fn classify_ytdlp_failure(stderr: &str) -> FailureKind {
+    const PERMANENT: &[&str] = &[
+        "private video",
+        "this video is private",
+        "video unavailable",
+        "this video has been removed",
+        "http error 404",
+        "http error 410",
+        "unsupported url",
+        "does not exist",
+    ];
+    if ytdlp_failure_key(stderr).is_some() {
+        return FailureKind::Permanent;
+    }
+    const TRANSIENT: &[&str] = &[
+        "http error 5",
+        "http error 429",
+        "connection reset",
+        "connection refused",
+        "connection aborted",
+        "remote end closed",
+        "timed out",
+        "temporary failure",
+        "incompleteread",
+        "fragment",
+        "unable to download",
+        "network is unreachable",
+    ];
+
+    let lower = stderr.to_lowercase();
+    if PERMANENT.iter().any(|p| lower.contains(p)) {
+        FailureKind::Permanent
+    } else if TRANSIENT.iter().any(|p| lower.contains(p)) {
+        FailureKind::Transient
+    } else {
+        FailureKind::Permanent
+    }
+}
+
+/// Backoff before retry number `retry` (1-based): 5s, 10s, 20s, ... capped at 5 minutes.
+fn retry_backoff(retry: u32) -> std::time::Duration {
+    let secs = 5u64.saturating_mul(1u64 << retry.saturating_sub(1).min(16));
+    std::time::Duration::from_secs(secs.min(300))
+}
+
+/// Schedule another attempt of a failed task when `auto_retry` is enabled, the
+/// failure looks transient and `max_retry_count` is not exhausted.
+/// Returns `true` when a retry was scheduled (the caller must not mark the task failed).
+async fn schedule_download_retry(app: &AppHandle, tasks: &DownloadTasks, task_id: &str, stderr: &str, error: &str) -> bool {
+    let config = load_config(app.clone()).unwrap_or_default();
+    if !config.auto_retry || classify_ytdlp_failure(stderr) != FailureKind::Transient {
+        return false;
+    }
+
+    let retry = {
+        let mut tasks_guard = tasks.lock().await;
+        let task = match tasks_guard.get_mut(task_id) {
+            Some(t) if t.progress.status != "cancelled" => t,
+            _ => return false,
+        };
+        let retry = task.attempt;
+        if retry > config.max_retry_count {
+            return false;
+        }
+        task.finish_attempt("failed", Some(error));
+        task.attempt += 1;
+        task.progress.status = "retrying".to_string();
+        task.progress.speed = "0 B/s".to_string();
+        task.progress.retry_attempt = Some(retry);
+        task.progress.max_retries = Some(config.max_retry_count);
+        app.emit("download-progress", &task.progress).ok();
+        retry
+    };
+
+    let delay = retry_backoff(retry);
+    log::info!(
+        "[Retry] Task {} failed transiently; retry {}/{} in {:?}",
+        task_id,
+        retry,
+        config.max_retry_count,
+        delay
+    );
+
+    // After the backoff the task goes back into the download queue, so a
+    // retry does not exceed max_concurrent_downloads.
+    let app = app.clone();
+    let tasks = tasks.clone();
+    let task_id = task_id.to_string();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        // Cancelled while waiting?
+        {
+            let mut tasks_guard = tasks.lock().await;
+            match tasks_guard.get_mut(&task_id) {
+                Some(task) if task.progress.status == "retrying" => {
+                    task.attempt_started_at = Utc::now().to_rfc3339();
+                    task.queue_seq = next_queue_seq();
+                    task.progress.status = "queued".to_string();
+                    app.emit("download-progress", &task.progress).ok();
+                }
+                _ => return,
+            }
+        }
+
+        process_download_queue(app, tasks);
+    });
+
+    true
 }
 
 async fn update_download_error(app: &AppHandle, tasks: &DownloadTasks, task_id: &str, error: &str) {
     let config = {
-        let mut tasks_guard = tasks.lock().unwrap();
+        let mut tasks_guard = tasks.lock().await;
         if let Some(task) = tasks_guard.get_mut(task_id) {
             task.progress.status = "failed".to_string();
             task.progress.error_message = Some(error.to_string());
+            task.progress.retry_attempt = None;
             app.emit("download-progress", &task.progress).ok();
 
             // Clone config before dropping guard
-            Some(task.config.clone())
+            Some((task.config.clone(), task.finish_attempt("failed", Some(error))))
         } else {
             None
         }
     };
 
     // Save to history after dropping mutex
-    if let Some(cfg) = config {
-        save_download_history(app, &cfg, "", "failed", Some(error)).await;
+    if let Some((cfg, attempts)) = config {
+        save_download_history(app, &cfg, "", "failed", Some(error), attempts, &[]).await;
     }
 }
 
@@ -4167,6 +8140,8 @@ async fn save_download_history(
     output_path: &str,
     status: &str,
     error: Option<&str>,
+    attempts: Vec<DownloadAttempt>,
+    segments: &[String],
 ) {
     let history_path = match app.path().app_data_dir() {
         Ok(dir) => {
@@ -4182,8 +8157,10 @@ async fn save_download_history(
     let mut history = read_versioned_array::<DownloadHistoryEntry>(&history_path)
         .unwrap_or_default();
 
-    // Get file size if completed
-    let file_size = if status == "completed" {
+    // Get file size if completed; a segmented recording counts every segment
+    let file_size = if status == "completed" && !segments.is_empty() {
+        Some(segments.iter().filter_map(|path| fs::metadata(path).ok()).map(|m| m.len()).sum())
+    } else if status == "completed" {
         fs::metadata(output_path).ok().map(|m| m.len())
     } else {
         None
@@ -4200,9 +8177,13 @@ async fn save_download_history(
         file_path: if status == "completed" { Some(output_path.to_string()) } else { None },
         file_size,
         resolution: Some(config.format_id.clone()),
-        started_at: Utc::now().to_rfc3339(),
+        started_at: attempts.first()
+            .map(|a| a.started_at.clone())
+            .unwrap_or_else(|| Utc::now().to_rfc3339()),
         completed_at: if status == "completed" { Some(Utc::now().to_rfc3339()) } else { None },
         error_message: error.map(String::from),
+        attempts,
+        segments: segments.to_vec(),
     };
 
     history.push(entry);
@@ -4211,15 +8192,270 @@ async fn save_download_history(
     write_versioned_array(&history_path, &history).ok();
 }
 
+// ── Bandwidth Budget ─────────────────────────────────────────────────────────
+
+/// Weight of a live recording when splitting the budget. A throttled recording
+/// loses stream content, a throttled VOD download only takes longer.
+const LIVE_BANDWIDTH_WEIGHT: u64 = 3;
+
+/// Lower bound for a single task's share (bytes/s), so a crowded budget still
+/// lets every task make progress. It is dropped when the budget cannot give
+/// it to every task.
+const MIN_TASK_RATE: u64 = 64 * 1024;
+
+/// Relative change of a VOD download's share that justifies restarting it.
+const RATE_REBALANCE_THRESHOLD: f64 = 0.25;
+
+/// Split `budget` (bytes/s) across `tasks` (task_id, is_live) by weight.
+/// Tasks whose share falls under `MIN_TASK_RATE` get the minimum and the
+/// others share the rest, so the rates never add up to more than `budget`.
+fn split_bandwidth_budget(budget: u64, tasks: &[(String, bool)], prioritize_live: bool) -> HashMap<String, u64> {
+    let weight = |is_live: bool| if is_live && prioritize_live { LIVE_BANDWIDTH_WEIGHT } else { 1 };
+    let floor = if MIN_TASK_RATE.saturating_mul(tasks.len() as u64) <= budget { MIN_TASK_RATE } else { 0 };
+
+    let mut rates = HashMap::new();
+    let mut remaining = budget;
+    let mut pool: Vec<&(String, bool)> = tasks.iter().collect();
+    loop {
+        let total_weight: u64 = pool.iter().map(|(_, is_live)| weight(*is_live)).sum();
+        let share = |is_live: bool| remaining * weight(is_live) / total_weight.max(1);
+        let (starved, rest): (Vec<_>, Vec<_>) = pool.into_iter().partition(|(_, is_live)| share(*is_live) < floor);
+        if starved.is_empty() {
+            for (id, is_live) in rest {
+                rates.insert(id.clone(), share(*is_live));
+            }
+            return rates;
+        }
+        for (id, _) in starved {
+            rates.insert(id.clone(), floor);
+            remaining -= floor;
+        }
+        pool = rest;
+    }
+}
+
+/// Per-task rate for every task currently using the network.
+/// Values are `None` when no budget is configured.
+fn plan_bandwidth(config: &AppConfig, tasks: &HashMap<String, DownloadTask>) -> HashMap<String, Option<u64>> {
+    let active: Vec<(String, bool)> = tasks
+        .iter()
+        .filter(|(_, t)| {
+            !t.paused && (t.progress.status == "downloading" || t.progress.status == "recording")
+        })
+        .map(|(id, t)| (id.clone(), t.config.video_info.is_live || t.progress.is_recording == Some(true)))
+        .collect();
+
+    if config.download_speed_limit == 0 {
+        return active.into_iter().map(|(id, _)| (id, None)).collect();
+    }
+
+    let budget = config.download_speed_limit as u64 * 1024 * 1024;
+    split_bandwidth_budget(budget, &active, config.prioritize_live_recordings)
+        .into_iter()
+        .map(|(id, rate)| (id, Some(rate)))
+        .collect()
+}
+
+/// Compute and record the rate a task is about to start with.
+async fn assign_task_rate(app: &AppHandle, tasks: &DownloadTasks, task_id: &str) -> Option<u64> {
+    let config = load_config(app.clone()).unwrap_or_default();
+    let mut tasks_guard = tasks.lock().await;
+    let rate = plan_bandwidth(&config, &tasks_guard).get(task_id).copied().flatten();
+    if let Some(task) = tasks_guard.get_mut(task_id) {
+        task.rate_limit = rate;
+    }
+    rate
+}
+
+fn rate_changed(current: Option<u64>, target: Option<u64>) -> bool {
+    match (current, target) {
+        (Some(cur), Some(new)) => {
+            (new as f64 - cur as f64).abs() / cur.max(1) as f64 > RATE_REBALANCE_THRESHOLD
+        }
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+/// Re-split the budget after a task started or finished. yt-dlp cannot change
+/// `--limit-rate` at runtime, so VOD downloads whose share moved noticeably
+/// are stopped; their executor restarts them with the new rate, continuing
+/// from the .part file. Live recordings keep the rate they started with.
+async fn rebalance_bandwidth(app: &AppHandle, tasks: &DownloadTasks) {
+    let config = load_config(app.clone()).unwrap_or_default();
+    let mut tasks_guard = tasks.lock().await;
+    let plan = plan_bandwidth(&config, &tasks_guard);
+    for (id, rate) in plan {
+        if let Some(task) = tasks_guard.get_mut(&id) {
+            if task.config.video_info.is_live || task.progress.is_recording == Some(true) {
+                continue;
+            }
+            if !rate_changed(task.rate_limit, rate) {
+                continue;
+            }
+            if task.process_id.is_some() {
+                task.rate_limit = rate;
+                task.interrupt_run();
+            }
+        }
+    }
+}
+
+/// Start a download again after a bandwidth rebalance stopped it.
+/// Plain function (not async) so execute_download can call it without a type cycle.
+fn restart_download(app: AppHandle, tasks: DownloadTasks, task_id: String) {
+    log::info!("[Bandwidth] Restarting download {} with a new rate limit", task_id);
+    tokio::spawn(execute_download(app, tasks, task_id));
+}
+
+/// Suspend a running download in place (Unix: SIGSTOP to its process group).
+/// Returns `false` when the platform cannot suspend processes.
+#[cfg(unix)]
+fn suspend_download_process(pid: u32) -> Result<bool, String> {
+    let ret = unsafe { libc::killpg(pid as i32, libc::SIGSTOP) };
+    if ret == 0 {
+        Ok(true)
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+#[cfg(not(unix))]
+fn suspend_download_process(_pid: u32) -> Result<bool, String> {
+    Ok(false)
+}
+
+/// Continue a download previously suspended by `suspend_download_process`.
+#[cfg(unix)]
+fn continue_download_process(pid: u32) -> Result<(), String> {
+    let ret = unsafe { libc::killpg(pid as i32, libc::SIGCONT) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+#[cfg(not(unix))]
+fn continue_download_process(_pid: u32) -> Result<(), String> {
+    Ok(())
+}
+
+/// Kill a download process together with its children where possible.
+fn kill_download_process(child: &mut TokioChild) {
+    #[cfg(unix)]
+    {
+        // Downloads run in their own process group; SIGKILL also reaches
+        // suspended ffmpeg children. Falls through for recordings, which
+        // share our process group.
+        if child.id().is_some_and(|pid| unsafe { libc::killpg(pid as i32, libc::SIGKILL) } == 0) {
+            return;
+        }
+    }
+    child.start_kill().ok();
+}
+
+/// Make `child` the task's running process. Returns the token that cancels
+/// it, or `None` when the task is gone or was cancelled meanwhile.
+async fn attach_task_process(tasks: &DownloadTasks, task_id: &str, child: &TokioChild) -> Option<CancellationToken> {
+    let mut tasks_guard = tasks.lock().await;
+    match tasks_guard.get_mut(task_id) {
+        Some(task) if task.progress.status != "cancelled" => {
+            task.process_id = child.id();
+            Some(task.cancel.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Next line of a task process's output; `None` at the end of the output or
+/// once the task is cancelled.
+async fn next_output_line<R: tokio::io::AsyncBufRead + Unpin>(
+    lines: &mut tokio::io::Lines<R>,
+    cancel: &CancellationToken,
+) -> Option<String> {
+    tokio::select! {
+        _ = cancel.cancelled() => None,
+        line = lines.next_line() => line.ok().flatten(),
+    }
+}
+
+/// Wait for a task's process to exit, killing it if the task is cancelled
+/// meanwhile, and clear it from the task.
+async fn wait_task_process(
+    tasks: &DownloadTasks,
+    task_id: &str,
+    child: &mut TokioChild,
+    cancel: &CancellationToken,
+) -> std::io::Result<std::process::ExitStatus> {
+    let pid = child.id();
+    let status = tokio::select! {
+        status = child.wait() => status,
+        _ = cancel.cancelled() => {
+            kill_download_process(child);
+            child.wait().await
+        }
+    };
+    let mut tasks_guard = tasks.lock().await;
+    if let Some(task) = tasks_guard.get_mut(task_id) {
+        if pid.is_some() && task.process_id == pid {
+            task.process_id = None;
+        }
+    }
+    status
+}
+
+async fn task_status(tasks: &DownloadTasks, task_id: &str) -> Option<String> {
+    let tasks_guard = tasks.lock().await;
+    tasks_guard.get(task_id).map(|t| t.progress.status.clone())
+}
+
 #[tauri::command]
-async fn pause_download(task_id: String, tasks: tauri::State<'_, DownloadTasks>) -> Result<(), String> {
-    let mut tasks_guard = tasks.lock().unwrap();
+async fn pause_download(
+    app: AppHandle,
+    task_id: String,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<(), String> {
+    let mut tasks_guard = tasks.lock().await;
     if let Some(task) = tasks_guard.get_mut(&task_id) {
-        if let Some(ref mut child) = task.process {
-            // Kill the process
-            child.kill().map_err(|e| serde_json::json!({"key": "errors.download.pause_failed", "params": {"error": e.to_string()}}).to_string())?;
+        // A live recording cannot be continued later, so it cannot be paused either.
+        if task.config.video_info.is_live || task.progress.is_recording == Some(true) {
+            return Err("errors.download.pause_live_unsupported".to_string());
+        }
+        if let Some(pid) = task.process_id {
+            let suspended = suspend_download_process(pid).map_err(|e| {
+                serde_json::json!({"key": "errors.download.pause_failed", "params": {"error": e}}).to_string()
+            })?;
+            if !suspended {
+                // No SIGSTOP available: stop the process and let resume_download
+                // continue from the .part file.
+                task.interrupt_run();
+            }
+            task.suspended = suspended;
             task.paused = true;
             task.progress.status = "paused".to_string();
+            task.progress.speed = "0 B/s".to_string();
+            app.emit("download-progress", &task.progress).ok();
+
+            // A paused task does not hold a download slot.
+            drop(tasks_guard);
+            process_download_queue(app, tasks.inner().clone());
+        } else if task.progress.status == "queued" || task.progress.status == "retrying" {
+            // Nothing is running yet: leaving "queued" keeps the dispatcher
+            // from starting it, and a pending retry sees the status change and
+            // drops out. resume_download queues it again.
+            task.paused = true;
+            task.progress.status = "paused".to_string();
+            task.progress.queue_position = None;
+            task.progress.retry_attempt = None;
+            task.progress.speed = "0 B/s".to_string();
+            app.emit("download-progress", &task.progress).ok();
+
+            drop(tasks_guard);
+            process_download_queue(app, tasks.inner().clone());
+        } else if !task.paused {
+            // Starting up or finishing: there is no process to stop yet.
+            return Err("errors.download.pause_unavailable".to_string());
         }
         Ok(())
     } else {
@@ -4233,24 +8469,28 @@ async fn resume_download(
     task_id: String,
     tasks: tauri::State<'_, DownloadTasks>,
 ) -> Result<(), String> {
-    // For MVP, we'll restart the download with --continue flag
-    // In production, this would need proper resume support
-
-    let mut tasks_guard = tasks.lock().unwrap();
+    let mut tasks_guard = tasks.lock().await;
     if let Some(task) = tasks_guard.get_mut(&task_id) {
+        if task.config.video_info.is_live || task.progress.is_recording == Some(true) {
+            return Err("errors.download.resume_live_unsupported".to_string());
+        }
+        if !task.paused {
+            return Ok(());
+        }
+
         task.paused = false;
-        task.progress.status = "downloading".to_string();
 
-        // Re-spawn download in background
-        let app_clone = app.clone();
-        let tasks_clone = tasks.inner().clone();
-        let task_id_clone = task_id.clone();
+        // Queue the task again until a download slot is free. A suspended
+        // process is then continued in place, keeping the progress reader of
+        // the original execute_download; a stopped one is restarted and
+        // yt-dlp continues from the .part file (--continue). Percentage and
+        // byte counts are kept until yt-dlp reports fresh progress.
+        task.progress.status = "queued".to_string();
+        app.emit("download-progress", &task.progress).ok();
 
         drop(tasks_guard);
 
-        tokio::spawn(async move {
-            execute_download(app_clone, tasks_clone, task_id_clone).await;
-        });
+        process_download_queue(app, tasks.inner().clone());
 
         Ok(())
     } else {
@@ -4265,25 +8505,42 @@ async fn cancel_download(
     tasks: tauri::State<'_, DownloadTasks>,
 ) -> Result<(), String> {
     let config = {
-        let mut tasks_guard = tasks.lock().unwrap();
+        let mut tasks_guard = tasks.lock().await;
         if let Some(task) = tasks_guard.get_mut(&task_id) {
-            if let Some(ref mut child) = task.process {
-                child.kill().ok();
-            }
+            // The executor kills the process once the token is cancelled.
+            task.cancel.cancel();
+            let was_queued = task.progress.status == "queued";
+            task.paused = false;
             task.progress.status = "cancelled".to_string();
             app.emit("download-progress", &task.progress).ok();
 
+            // A scheduled recording that never started has no monitor that
+            // would report the cancellation to the scheduled queue.
+            let unstarted_sched_id = if was_queued { task.scheduled_task_id.clone() } else { None };
+
             // Clone config before dropping guard
-            Some(task.config.clone())
+            Some((task.config.clone(), task.finish_attempt("cancelled", None), unstarted_sched_id))
         } else {
             None
         }
     };
 
     match config {
-        Some(cfg) => {
+        Some((cfg, attempts, unstarted_sched_id)) => {
+            if let Some(sched_id) = unstarted_sched_id {
+                {
+                    let mut state = scheduled_download_state().lock().await;
+                    if let Some(t) = state.queue.iter_mut().find(|t| t.id == sched_id) {
+                        t.status = "cancelled".to_string();
+                        t.completed_at = Some(Utc::now().to_rfc3339());
+                    }
+                }
+                emit_queue_update(&app).await;
+            }
+
             // Save to history after dropping mutex
-            save_download_history(&app, &cfg, "", "cancelled", None).await;
+            save_download_history(&app, &cfg, "", "cancelled", None, attempts, &[]).await;
+            process_download_queue(app, tasks.inner().clone());
             Ok(())
         }
         None => Err("找不到下載任務".to_string()),
@@ -4297,28 +8554,9 @@ async fn stop_recording(
     tasks: tauri::State<'_, DownloadTasks>,
 ) -> Result<(), String> {
     {
-        let mut tasks_guard = tasks.lock().unwrap();
+        let mut tasks_guard = tasks.lock().await;
         if let Some(task) = tasks_guard.get_mut(&task_id) {
-            if let Some(ref mut child) = task.process {
-                // Send SIGTERM for graceful shutdown (allows yt-dlp to finalize the file)
-                #[cfg(unix)]
-                {
-                    // On Unix, we can send SIGTERM which yt-dlp handles gracefully
-                    unsafe {
-                        libc::kill(child.id() as i32, libc::SIGTERM);
-                    }
-                }
-
-                #[cfg(not(unix))]
-                {
-                    // On Windows, just kill the process
-                    child.kill().ok();
-                }
-            }
-
-            task.progress.status = "processing".to_string();
-            task.progress.is_recording = Some(false);
-            app.emit("download-progress", &task.progress).ok();
+            request_recording_stop(&app, task);
         }
     }
 
@@ -4398,60 +8636,414 @@ fn get_history_path(app: &AppHandle) -> Result<PathBuf, String> {
     fs::create_dir_all(&history_dir)
         .map_err(|e| serde_json::json!({"key": "errors.file.create_history_dir_failed", "params": {"error": e.to_string()}}).to_string())?;
 
-    Ok(history_dir.join("history.json"))
+    Ok(history_dir.join("history.json"))
+}
+
+#[tauri::command]
+async fn get_download_history(app: AppHandle) -> Result<Vec<DownloadHistoryEntry>, String> {
+    let history_path = get_history_path(&app)?;
+    read_versioned_array::<DownloadHistoryEntry>(&history_path)
+}
+
+#[tauri::command]
+async fn delete_history_entry(app: AppHandle, id: String) -> Result<(), String> {
+    let history_path = get_history_path(&app)?;
+
+    if !history_path.exists() {
+        return Ok(());
+    }
+
+    let mut history = read_versioned_array::<DownloadHistoryEntry>(&history_path)?;
+
+    // Remove entry with matching id
+    history.retain(|entry| entry.id != id);
+
+    // Save updated history using versioned wrapper
+    write_versioned_array(&history_path, &history)
+        .map_err(|e| serde_json::json!({"key": "errors.file.save_history_failed", "params": {"error": e.to_string()}}).to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn clear_all_history(app: AppHandle) -> Result<(), String> {
+    let history_path = get_history_path(&app)?;
+
+    // Write empty versioned wrapper
+    let empty: Vec<DownloadHistoryEntry> = Vec::new();
+    write_versioned_array(&history_path, &empty)
+        .map_err(|e| serde_json::json!({"key": "errors.file.clear_history_failed", "params": {"error": e.to_string()}}).to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn check_file_exists(path: String) -> Result<bool, String> {
+    Ok(PathBuf::from(&path).exists())
+}
+
+/// Change a task's queue priority (higher starts first). Running tasks keep
+/// running; the value only matters while the task is queued.
+#[tauri::command]
+async fn set_download_priority(
+    app: AppHandle,
+    task_id: String,
+    priority: i32,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<(), String> {
+    {
+        let mut tasks_guard = tasks.lock().await;
+        let task = tasks_guard.get_mut(&task_id).ok_or_else(|| "找不到下載任務".to_string())?;
+        task.progress.priority = priority;
+        app.emit("download-progress", &task.progress).ok();
+    }
+    process_download_queue(app, tasks.inner().clone());
+    Ok(())
+}
+
+/// Reorder queued tasks. `task_ids` lists queued tasks in the desired order;
+/// they swap queue positions among themselves, other tasks keep theirs.
+/// Priority still takes precedence over position.
+#[tauri::command]
+async fn reorder_download_queue(
+    app: AppHandle,
+    task_ids: Vec<String>,
+    tasks: tauri::State<'_, DownloadTasks>,
+) -> Result<(), String> {
+    reorder_queued_tasks(&mut *tasks.lock().await, &task_ids);
+    process_download_queue(app, tasks.inner().clone());
+    Ok(())
+}
+
+/// Gives the queued tasks among `task_ids` their own sequence numbers in the
+/// order listed, so they swap places only among themselves.
+fn reorder_queued_tasks(tasks: &mut HashMap<String, DownloadTask>, task_ids: &[String]) {
+    let mut seqs: Vec<u64> = task_ids.iter()
+        .filter_map(|id| tasks.get(id))
+        .filter(|t| t.progress.status == "queued")
+        .map(|t| t.queue_seq)
+        .collect();
+    seqs.sort_unstable();
+
+    let mut seqs = seqs.into_iter();
+    for id in task_ids {
+        if let Some(task) = tasks.get_mut(id) {
+            if task.progress.status == "queued" {
+                if let Some(seq) = seqs.next() {
+                    task.queue_seq = seq;
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_download_tasks(tasks: tauri::State<'_, DownloadTasks>) -> Result<Vec<DownloadProgress>, String> {
+    let tasks_guard = tasks.lock().await;
+    let progress_list: Vec<DownloadProgress> = tasks_guard
+        .values()
+        .map(|task| task.progress.clone())
+        .collect();
+    Ok(progress_list)
+}
+
+/// A task's captured yt-dlp/ffmpeg output, rotated part first.
+#[tauri::command]
+fn get_task_log(app: AppHandle, task_id: String) -> Result<String, String> {
+    // Task ids are generated by us; anything else must not reach the filesystem.
+    if task_id.is_empty() || !task_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("errors.download.log_not_found".to_string());
+    }
+    let path = task_log_dir(&app)
+        .ok_or("errors.download.log_not_found")?
+        .join(format!("{}.log", task_id));
+    let log: String = [rotated_log_path(&path), path]
+        .iter()
+        .filter_map(|part| fs::read_to_string(part).ok())
+        .collect();
+    if log.is_empty() {
+        return Err("errors.download.log_not_found".to_string());
+    }
+    Ok(log)
+}
+
+// ── Download Task Persistence ────────────────────────────────────────────────
+
+/// Snapshot of an unfinished download task, saved so it survives a restart.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PersistedDownloadTask {
+    config: DownloadConfig,
+    progress: DownloadProgress,
+    attempt: u32,
+    attempt_started_at: String,
+    #[serde(default)]
+    attempts: Vec<DownloadAttempt>,
+    #[serde(default)]
+    queue_seq: u64,
+    #[serde(default)]
+    scheduled_task_id: Option<String>,
+}
+
+impl From<&DownloadTask> for PersistedDownloadTask {
+    fn from(task: &DownloadTask) -> Self {
+        Self {
+            config: task.config.clone(),
+            progress: task.progress.clone(),
+            attempt: task.attempt,
+            attempt_started_at: task.attempt_started_at.clone(),
+            attempts: task.attempts.clone(),
+            queue_seq: task.queue_seq,
+            scheduled_task_id: task.scheduled_task_id.clone(),
+        }
+    }
+}
+
+/// A restored task that was closed out on launch and goes straight to history:
+/// (config, output path, status, error, attempts, segments).
+type RestoredHistoryEntry = (DownloadConfig, String, String, Option<String>, Vec<DownloadAttempt>, Vec<String>);
+
+/// How often in-flight tasks are written to disk.
+const TASK_PERSIST_INTERVAL_SECS: u64 = 5;
+
+fn get_download_tasks_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+
+    let tidemark_dir = app_data_dir.join("tidemark");
+    fs::create_dir_all(&tidemark_dir)
+        .map_err(|e| format!("Failed to create tidemark dir: {}", e))?;
+
+    Ok(tidemark_dir.join("download_tasks.json"))
+}
+
+fn get_scheduled_queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+
+    let tidemark_dir = app_data_dir.join("tidemark");
+    fs::create_dir_all(&tidemark_dir)
+        .map_err(|e| format!("Failed to create tidemark dir: {}", e))?;
+
+    Ok(tidemark_dir.join("scheduled_queue.json"))
+}
+
+fn is_unfinished_status(status: &str) -> bool {
+    matches!(status, "queued" | "downloading" | "recording" | "reconnecting" | "processing" | "paused" | "retrying")
+}
+
+/// Serialize unfinished download tasks and scheduled queue entries.
+async fn snapshot_download_state(tasks: &DownloadTasks) -> (Vec<PersistedDownloadTask>, Vec<ScheduledDownloadTask>) {
+    let mut persisted: Vec<PersistedDownloadTask> = {
+        let tasks_guard = tasks.lock().await;
+        tasks_guard
+            .values()
+            .filter(|t| is_unfinished_status(&t.progress.status))
+            .map(PersistedDownloadTask::from)
+            .collect()
+    };
+    persisted.sort_by_key(|t| t.queue_seq);
+
+    let scheduled = {
+        let state = scheduled_download_state().lock().await;
+        state
+            .queue
+            .iter()
+            .filter(|t| t.status == "queued" || t.status == "downloading")
+            .cloned()
+            .collect()
+    };
+
+    (persisted, scheduled)
 }
 
-#[tauri::command]
-async fn get_download_history(app: AppHandle) -> Result<Vec<DownloadHistoryEntry>, String> {
-    let history_path = get_history_path(&app)?;
-    read_versioned_array::<DownloadHistoryEntry>(&history_path)
-}
+/// Periodically write unfinished tasks to disk, skipping unchanged snapshots.
+async fn run_task_persistence(app: AppHandle, tasks: DownloadTasks) {
+    let mut last_written = String::new();
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(TASK_PERSIST_INTERVAL_SECS)).await;
 
-#[tauri::command]
-async fn delete_history_entry(app: AppHandle, id: String) -> Result<(), String> {
-    let history_path = get_history_path(&app)?;
+        let (persisted, scheduled) = snapshot_download_state(&tasks).await;
+        let fingerprint = serde_json::to_string(&(&persisted, &scheduled)).unwrap_or_default();
+        if fingerprint == last_written {
+            continue;
+        }
 
-    if !history_path.exists() {
-        return Ok(());
+        let tasks_result = get_download_tasks_path(&app)
+            .and_then(|path| write_versioned_array(&path, &persisted));
+        let queue_result = get_scheduled_queue_path(&app)
+            .and_then(|path| write_versioned_array(&path, &scheduled));
+
+        match tasks_result.and(queue_result) {
+            Ok(()) => last_written = fingerprint,
+            Err(e) => log::warn!("[Persist] Failed to save download tasks: {}", e),
+        }
     }
+}
 
-    let mut history = read_versioned_array::<DownloadHistoryEntry>(&history_path)?;
+/// Whether `name` is a `.part` file of the output with this stem. The stem
+/// must be followed by a `.`, so `Stream` does not match `Stream 2.mp4.part`.
+fn is_part_file_of(name: &str, stem: &str) -> bool {
+    name.ends_with(".part")
+        && name.strip_prefix(stem).is_some_and(|rest| rest.starts_with('.'))
+}
 
-    // Remove entry with matching id
-    history.retain(|entry| entry.id != id);
+/// `.part` files yt-dlp may have left for an output file: `<name>.part` and
+/// the per-format `<stem>.f<id>.<ext>.part` files of merged downloads.
+fn find_part_files(output_path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (
+        output_path.parent(),
+        output_path.file_stem().and_then(|s| s.to_str()),
+    ) else {
+        return Vec::new();
+    };
 
-    // Save updated history using versioned wrapper
-    write_versioned_array(&history_path, &history)
-        .map_err(|e| serde_json::json!({"key": "errors.file.save_history_failed", "params": {"error": e.to_string()}}).to_string())?;
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| is_part_file_of(n, stem))
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Turns a saved task back into a task. VOD downloads come back as "paused"
+/// (or "queued" with `auto_resume`); leftover `.part` files are matched to
+/// their task. A download that finished after the last snapshot, and a live
+/// recording that was running, since the stream cannot be continued, are
+/// closed and returned for history.
+fn restore_task(saved: PersistedDownloadTask, auto_resume: bool) -> (DownloadTask, Option<RestoredHistoryEntry>) {
+    let interrupted_error = "{\"key\":\"errors.download.interrupted_by_restart\"}";
+    let mut closed = None;
+    let mut task = DownloadTask::new(saved.config, saved.progress);
+    task.attempt = saved.attempt.max(1);
+    task.attempt_started_at = saved.attempt_started_at;
+    task.attempts = saved.attempts;
+    task.scheduled_task_id = saved.scheduled_task_id;
+    task.progress.speed = "0 B/s".to_string();
+    task.progress.queue_position = None;
+
+    let output_path = output_file_path(&task.config);
+    let is_live = task.config.video_info.is_live || task.progress.is_recording == Some(true);
+
+    if is_live {
+        // A live stream that had not started yet cannot wait paused.
+        if task.progress.status != "queued" {
+            let segments = task.progress.segments.clone();
+            let recorded = output_path.exists() || !find_part_files(&output_path).is_empty() || !segments.is_empty();
+            let output = segments.first().cloned()
+                .unwrap_or_else(|| output_path.to_str().unwrap_or_default().to_string());
+            task.progress.is_recording = Some(false);
+            task.progress.error_message = Some(interrupted_error.to_string());
+            if recorded {
+                task.progress.status = "stream_interrupted".to_string();
+                task.progress.output_path = Some(output.clone());
+            } else {
+                task.progress.status = "failed".to_string();
+            }
+            let status = task.progress.status.clone();
+            let attempts = task.finish_attempt(&status, Some(interrupted_error));
+            closed = Some((task.config.clone(), output, status, Some(interrupted_error.to_string()), attempts, segments));
+        }
+    } else {
+        let part_files = find_part_files(&output_path);
+        if part_files.is_empty() && output_path.exists() {
+            // yt-dlp finished after the last snapshot.
+            let output = output_path.to_str().unwrap_or_default().to_string();
+            task.progress.status = "completed".to_string();
+            task.progress.percentage = 100.0;
+            task.progress.output_path = Some(output.clone());
+            let attempts = task.finish_attempt("completed", None);
+            closed = Some((task.config.clone(), output, "completed".to_string(), None, attempts, Vec::new()));
+        } else {
+            if part_files.is_empty() {
+                // Nothing on disk to continue from.
+                task.progress.percentage = 0.0;
+                task.progress.downloaded_bytes = 0;
+            } else {
+                task.progress.downloaded_bytes = part_files
+                    .iter()
+                    .filter_map(|p| fs::metadata(p).ok())
+                    .map(|m| m.len())
+                    .sum();
+            }
 
-    Ok(())
+            if auto_resume {
+                task.progress.status = "queued".to_string();
+            } else {
+                task.progress.status = "paused".to_string();
+                task.paused = true;
+            }
+        }
+    }
+    (task, closed)
 }
 
-#[tauri::command]
-async fn clear_all_history(app: AppHandle) -> Result<(), String> {
-    let history_path = get_history_path(&app)?;
+/// Restore tasks saved by `run_task_persistence` (see `restore_task`) and the
+/// scheduled queue, writing closed tasks to history.
+async fn restore_download_tasks(app: AppHandle, tasks: DownloadTasks) {
+    let config = load_config(app.clone()).unwrap_or_default();
 
-    // Write empty versioned wrapper
-    let empty: Vec<DownloadHistoryEntry> = Vec::new();
-    write_versioned_array(&history_path, &empty)
-        .map_err(|e| serde_json::json!({"key": "errors.file.clear_history_failed", "params": {"error": e.to_string()}}).to_string())?;
+    let mut persisted: Vec<PersistedDownloadTask> = get_download_tasks_path(&app)
+        .and_then(|path| read_versioned_array(&path))
+        .unwrap_or_default();
+    let scheduled: Vec<ScheduledDownloadTask> = get_scheduled_queue_path(&app)
+        .and_then(|path| read_versioned_array(&path))
+        .unwrap_or_default();
 
-    Ok(())
-}
+    if persisted.is_empty() && scheduled.is_empty() {
+        return;
+    }
+    persisted.sort_by_key(|t| t.queue_seq);
 
-#[tauri::command]
-async fn check_file_exists(path: String) -> Result<bool, String> {
-    Ok(PathBuf::from(&path).exists())
-}
+    let mut finished: Vec<RestoredHistoryEntry> = Vec::new();
+    let mut dispatch = false;
 
-#[tauri::command]
-async fn get_download_tasks(tasks: tauri::State<'_, DownloadTasks>) -> Result<Vec<DownloadProgress>, String> {
-    let tasks_guard = tasks.lock().unwrap();
-    let progress_list: Vec<DownloadProgress> = tasks_guard
-        .values()
-        .map(|task| task.progress.clone())
-        .collect();
-    Ok(progress_list)
+    {
+        let mut tasks_guard = tasks.lock().await;
+        for saved in persisted {
+            let task_id = saved.progress.task_id.clone();
+            let (task, closed) = restore_task(saved, config.auto_resume_downloads);
+            dispatch |= task.progress.status == "queued";
+            finished.extend(closed);
+            app.emit("download-progress", &task.progress).ok();
+            tasks_guard.insert(task_id, task);
+        }
+    }
+
+    {
+        let mut state = scheduled_download_state().lock().await;
+        for mut sched in scheduled {
+            if sched.status == "downloading" {
+                sched.status = "failed".to_string();
+                sched.error_message = Some("errors.download.interrupted_by_restart".to_string());
+                sched.completed_at = Some(Utc::now().to_rfc3339());
+            }
+            if !state.queue.iter().any(|t| t.id == sched.id) {
+                state.queue.push(sched);
+            }
+        }
+    }
+    emit_queue_update(&app).await;
+
+    for (cfg, output, status, error, attempts, segments) in finished {
+        save_download_history(&app, &cfg, &output, &status, error.as_deref(), attempts, &segments).await;
+    }
+
+    log::info!("[Persist] Restored unfinished download tasks");
+    if dispatch {
+        process_download_queue(app, tasks);
+    }
 }
 
 // Authentication commands
@@ -4486,34 +9078,72 @@ async fn import_youtube_cookies(path: String) -> Result<bool, String> {
     let content = fs::read_to_string(&path)
         .map_err(|e| serde_json::json!({"key": "errors.file.read_failed", "params": {"error": e.to_string()}}).to_string())?;
 
-    // Check for Netscape cookies.txt format
-    // Should start with "# Netscape HTTP Cookie File" or have tab-separated cookie lines
-    let lines: Vec<&str> = content.lines().collect();
-
-    if lines.is_empty() {
-        return Ok(false);
-    }
+    Ok(is_netscape_cookie_file(&content))
+}
 
-    // Look for Netscape header or valid cookie lines
-    let has_netscape_header = lines.iter().any(|line|
-        line.starts_with("# Netscape HTTP Cookie File") ||
-        line.starts_with("# HTTP Cookie File")
-    );
+#[tauri::command]
+fn get_cookie_profiles(app: AppHandle) -> Result<Vec<CookieProfile>, String> {
+    let profiles_path = get_cookie_profiles_path(&app)?;
+    read_versioned_array::<CookieProfile>(&profiles_path)
+}
 
-    // Check if at least one line looks like a cookie (7 tab-separated fields)
-    let has_cookie_lines = lines.iter().any(|line| {
-        if line.starts_with('#') || line.trim().is_empty() {
-            return false;
+#[tauri::command]
+fn save_cookie_profile(app: AppHandle, profile: CookieProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("errors.cookies.name_required".to_string());
+    }
+    match profile.source.as_str() {
+        "browser" => {
+            if !profile.browser.as_deref().is_some_and(|b| !b.trim().is_empty()) {
+                return Err("errors.cookies.browser_required".to_string());
+            }
         }
-        let parts: Vec<&str> = line.split('\t').collect();
-        parts.len() >= 6 // Netscape format has at least 6-7 fields
-    });
+        _ => {
+            let content = profile.cookies_path.as_deref()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .ok_or_else(|| "errors.cookies.invalid_file".to_string())?;
+            if !is_netscape_cookie_file(&content) {
+                return Err("errors.cookies.invalid_file".to_string());
+            }
+        }
+    }
 
-    if has_netscape_header || has_cookie_lines {
-        Ok(true)
+    let mut profiles = get_cookie_profiles(app.clone())?;
+    if let Some(pos) = profiles.iter().position(|p| p.id == profile.id) {
+        profiles[pos] = profile;
     } else {
-        Ok(false)
+        profiles.push(profile);
+    }
+
+    let profiles_path = get_cookie_profiles_path(&app)?;
+    write_versioned_array(&profiles_path, &profiles)
+}
+
+#[tauri::command]
+fn delete_cookie_profile(app: AppHandle, id: String) -> Result<(), String> {
+    let mut profiles = get_cookie_profiles(app.clone())?;
+    profiles.retain(|p| p.id != id);
+    let profiles_path = get_cookie_profiles_path(&app)?;
+    write_versioned_array(&profiles_path, &profiles)?;
+
+    // Presets using the profile fall back to the auth settings.
+    let mut presets = get_scheduled_presets(app.clone())?;
+    let mut changed = false;
+    for preset in presets.iter_mut().filter(|p| p.cookie_profile_id.as_deref() == Some(id.as_str())) {
+        preset.cookie_profile_id = None;
+        changed = true;
+    }
+    if changed {
+        let presets_path = get_scheduled_presets_path(&app)?;
+        write_versioned_array(&presets_path, &presets)?;
     }
+
+    Ok(())
+}
+
+#[tauri::command]
+fn check_cookie_profiles(app: AppHandle) -> Result<Vec<CookieProfileStatus>, String> {
+    Ok(get_cookie_profiles(app)?.iter().map(check_cookie_profile).collect())
 }
 
 #[tauri::command]
@@ -4522,39 +9152,14 @@ async fn save_auth_config(
     twitch_token: Option<String>,
     youtube_cookies_path: Option<String>,
 ) -> Result<(), String> {
-    let auth_config_path = get_auth_config_path(&app)?;
-
     // Load existing config to preserve API keys
-    let mut config = if auth_config_path.exists() {
-        let content = fs::read_to_string(&auth_config_path)
-            .map_err(|e| format!("Failed to read auth config: {}", e))?;
-        serde_json::from_str::<AuthConfig>(&content)
-            .unwrap_or(AuthConfig {
-                twitch_token: None,
-                youtube_cookies_path: None,
-                openai_api_key: None,
-                groq_api_key: None,
-                elevenlabs_api_key: None,
-            })
-    } else {
-        AuthConfig {
-            twitch_token: None,
-            youtube_cookies_path: None,
-            openai_api_key: None,
-            groq_api_key: None,
-            elevenlabs_api_key: None,
-        }
-    };
+    let mut config = read_auth_config(&app).unwrap_or_default();
 
     // Update fields
     config.twitch_token = twitch_token;
     config.youtube_cookies_path = youtube_cookies_path;
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize auth config: {}", e))?;
-
-    fs::write(&auth_config_path, content)
-        .map_err(|e| format!("Failed to write auth config: {}", e))?;
+    write_auth_config(&app, &config)?;
 
     Ok(())
 }
@@ -4566,63 +9171,93 @@ async fn save_api_keys(
     groq_api_key: Option<String>,
     elevenlabs_api_key: Option<String>,
 ) -> Result<(), String> {
-    let auth_config_path = get_auth_config_path(&app)?;
-
     // Load existing config to preserve Twitch/YouTube auth
-    let mut config = if auth_config_path.exists() {
-        let content = fs::read_to_string(&auth_config_path)
-            .map_err(|e| format!("Failed to read auth config: {}", e))?;
-        serde_json::from_str::<AuthConfig>(&content)
-            .unwrap_or(AuthConfig {
-                twitch_token: None,
-                youtube_cookies_path: None,
-                openai_api_key: None,
-                groq_api_key: None,
-                elevenlabs_api_key: None,
-            })
-    } else {
-        AuthConfig {
-            twitch_token: None,
-            youtube_cookies_path: None,
-            openai_api_key: None,
-            groq_api_key: None,
-            elevenlabs_api_key: None,
-        }
-    };
+    let mut config = read_auth_config(&app).unwrap_or_default();
 
     // Update API keys
     config.openai_api_key = openai_api_key;
     config.groq_api_key = groq_api_key;
     config.elevenlabs_api_key = elevenlabs_api_key;
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize auth config: {}", e))?;
-
-    fs::write(&auth_config_path, content)
-        .map_err(|e| format!("Failed to write auth config: {}", e))?;
+    write_auth_config(&app, &config)?;
 
     Ok(())
 }
 
 #[tauri::command]
 async fn get_auth_config(app: AppHandle) -> Result<AuthConfig, String> {
-    let auth_config_path = get_auth_config_path(&app)?;
+    read_auth_config(&app)
+}
 
-    if !auth_config_path.exists() {
-        return Ok(AuthConfig {
-            twitch_token: None,
-            youtube_cookies_path: None,
-            openai_api_key: None,
-            groq_api_key: None,
-            elevenlabs_api_key: None,
-        });
-    }
+#[tauri::command]
+fn get_secret_store_status(app: AppHandle) -> Result<SecretStoreStatus, String> {
+    let store = read_secret_store_file(&app)?;
+    let key_source = store.as_ref()
+        .map(|s| s.key_source.clone())
+        .unwrap_or_else(|| "keyfile".to_string());
+    let locked = key_source == "passphrase" && SECRET_KEY.lock().unwrap().is_none();
+    Ok(SecretStoreStatus { key_source, locked })
+}
 
-    let content = fs::read_to_string(&auth_config_path)
-        .map_err(|e| format!("Failed to read auth config: {}", e))?;
+#[tauri::command]
+async fn unlock_secret_store(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let store = read_secret_store_file(&app)?
+        .filter(|s| s.key_source == "passphrase")
+        .ok_or_else(|| "errors.secrets.no_passphrase".to_string())?;
+    let salt = store.salt.as_deref()
+        .and_then(|salt| BASE64.decode(salt).ok())
+        .ok_or_else(|| "errors.secrets.corrupted".to_string())?;
+    let key = derive_secret_key_blocking(passphrase, salt, store.iterations.unwrap_or(PASSPHRASE_ITERATIONS)).await?;
+    open_secrets(&key, &store.nonce, &store.ciphertext)
+        .map_err(|_| "errors.secrets.wrong_passphrase".to_string())?;
+    *SECRET_KEY.lock().unwrap() = Some(key);
+    Ok(())
+}
 
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse auth config: {}", e))
+/// Re-encrypts the secret store with a key derived from `passphrase`, or with
+/// the machine-local key file when `passphrase` is `None`. The store must be
+/// unlocked.
+#[tauri::command]
+async fn set_secret_passphrase(app: AppHandle, passphrase: Option<String>) -> Result<(), String> {
+    let secrets = load_secrets(&app)?;
+    let key_path = get_secret_key_path(&app)?;
+
+    let store = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => {
+            let salt = random_bytes::<16>()?;
+            let key = derive_secret_key_blocking(passphrase, salt.to_vec(), PASSPHRASE_ITERATIONS).await?;
+            let (nonce, ciphertext) = seal_secrets(&key, &secrets)?;
+            *SECRET_KEY.lock().unwrap() = Some(key);
+            SecretStoreFile {
+                version: SECRET_STORE_VERSION,
+                key_source: "passphrase".to_string(),
+                salt: Some(BASE64.encode(salt)),
+                iterations: Some(PASSPHRASE_ITERATIONS),
+                nonce,
+                ciphertext,
+            }
+        }
+        None => {
+            let key = read_or_create_key_file(&key_path)?;
+            let (nonce, ciphertext) = seal_secrets(&key, &secrets)?;
+            *SECRET_KEY.lock().unwrap() = Some(key);
+            SecretStoreFile {
+                version: SECRET_STORE_VERSION,
+                key_source: "keyfile".to_string(),
+                salt: None,
+                iterations: None,
+                nonce,
+                ciphertext,
+            }
+        }
+    };
+    write_secret_store_file(&app, &store)?;
+
+    // The key file would decrypt nothing now; don't leave it lying around.
+    if store.key_source == "passphrase" && key_path.exists() {
+        let _ = fs::remove_file(&key_path);
+    }
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -4756,29 +9391,8 @@ async fn save_api_key(
     provider: String,
     api_key: String,
 ) -> Result<(), String> {
-    let auth_config_path = get_auth_config_path(&app)?;
-
     // Load existing config
-    let mut config = if auth_config_path.exists() {
-        let content = fs::read_to_string(&auth_config_path)
-            .map_err(|e| format!("Failed to read auth config: {}", e))?;
-        serde_json::from_str::<AuthConfig>(&content)
-            .unwrap_or(AuthConfig {
-                twitch_token: None,
-                youtube_cookies_path: None,
-                openai_api_key: None,
-                groq_api_key: None,
-                elevenlabs_api_key: None,
-            })
-    } else {
-        AuthConfig {
-            twitch_token: None,
-            youtube_cookies_path: None,
-            openai_api_key: None,
-            groq_api_key: None,
-            elevenlabs_api_key: None,
-        }
-    };
+    let mut config = read_auth_config(&app).unwrap_or_default();
 
     // Update the specific API key
     let key_value = if api_key.is_empty() { None } else { Some(api_key) };
@@ -4789,11 +9403,7 @@ async fn save_api_key(
         _ => return Err(format!("Unknown provider: {}", provider)),
     }
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize auth config: {}", e))?;
-
-    fs::write(&auth_config_path, content)
-        .map_err(|e| format!("Failed to write auth config: {}", e))?;
+    write_auth_config(&app, &config)?;
 
     Ok(())
 }
@@ -4860,6 +9470,9 @@ pub struct TranscriptionConfig {
     pub max_chars: i32,
     pub traditional_chinese: bool,
     pub auto_segment: bool,
+    /// Script conversion for Chinese transcripts: `s2t`, `s2tw`, `s2hk` or `t2s`.
+    #[serde(default)]
+    pub chinese_conversion: Option<String>,
 }
 
 fn check_gpu_availability() -> (bool, Option<String>) {
@@ -5185,81 +9798,247 @@ pub struct CloudSegmentProgress {
     pub percentage: f64,
 }
 
-async fn split_audio_for_cloud(input_path: &str, max_size_mb: u64) -> Result<Vec<String>, String> {
-    // Get file size
-    let metadata = fs::metadata(input_path)
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-    let file_size_mb = metadata.len() / (1024 * 1024);
-
-    if file_size_mb <= max_size_mb {
-        // File is small enough, no need to split
-        return Ok(vec![input_path.to_string()]);
-    }
-
-    // Split file using FFmpeg
-    let input_pathbuf = PathBuf::from(input_path);
-    let parent_dir = input_pathbuf.parent()
-        .ok_or("Invalid input path")?;
-    let file_stem = input_pathbuf.file_stem()
-        .ok_or("Invalid file name")?
-        .to_string_lossy();
-
-    // Calculate segment duration (aim for max_size_mb - 1 MB buffer)
-    let target_size_mb = max_size_mb - 1;
-    let total_segments = ((file_size_mb as f64) / (target_size_mb as f64)).ceil() as usize;
+/// FFmpeg output arguments of the audio sent to cloud engines: mono 16 kHz,
+/// which is all speech recognition uses, at a bitrate small enough that
+/// hours of audio fit in one upload.
+const CLOUD_AUDIO_ARGS: &[&str] = &["-vn", "-ac", "1", "-ar", "16000", "-c:a", "libmp3lame", "-b:a", "32k"];
+/// Each segment runs this far past the next one's start, so a word cut at a
+/// hard split is heard whole by the earlier segment.
+const CLOUD_SEGMENT_OVERLAP_SECS: f64 = 2.0;
+/// `silencedetect` settings for finding split points between words.
+const CLOUD_SILENCE_FILTER: &str = "silencedetect=noise=-35dB:d=0.4";
 
-    // Get file duration
-    let duration_output = Command::new("ffprobe")
+async fn probe_duration(path: &str) -> Result<f64, String> {
+    let duration_output = TokioCommand::new("ffprobe")
         .args([
             "-v", "error",
             "-show_entries", "format=duration",
             "-of", "default=noprint_wrappers=1:nokey=1",
-            input_path
+            path
         ])
         .output()
+        .await
         .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
 
     let duration_str = String::from_utf8_lossy(&duration_output.stdout);
-    let total_duration: f64 = duration_str.trim().parse()
-        .map_err(|e| format!("Failed to parse duration: {}", e))?;
-
-    let segment_duration = total_duration / (total_segments as f64);
-
-    // Split file into segments
-    let mut segment_paths = Vec::new();
-    for i in 0..total_segments {
-        let start_time = i as f64 * segment_duration;
-        let segment_path = parent_dir.join(format!("{}_segment_{:03}.mp3", file_stem, i));
-
-        let output = Command::new("ffmpeg")
-            .args([
-                "-i", input_path,
-                "-ss", &format!("{:.2}", start_time),
-                "-t", &format!("{:.2}", segment_duration),
-                "-c:a", "libmp3lame",
-                "-b:a", "128k",
-                "-y",
-                segment_path.to_str().ok_or("Invalid segment path")?
-            ])
-            .output()
-            .map_err(|e| format!("Failed to split audio: {}", e))?;
+    duration_str.trim().parse()
+        .map_err(|e| format!("Failed to parse duration: {}", e))
+}
+
+/// Re-encodes `input_path` to the compact cloud format at `output_path`.
+async fn compact_audio_for_cloud(input_path: &str, output_path: &Path) -> Result<(), String> {
+    let tmp_path = output_path.with_extension("tmp.mp3");
+    let output = TokioCommand::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i", input_path])
+        .args(CLOUD_AUDIO_ARGS)
+        .arg("-y")
+        .arg(&tmp_path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("FFmpeg split failed: {}", stderr));
+    if !output.status.success() {
+        let _ = fs::remove_file(&tmp_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg re-encode failed: {}", stderr));
+    }
+    fs::rename(&tmp_path, output_path)
+        .map_err(|e| format!("Failed to move re-encoded audio: {}", e))
+}
+
+/// `(start, end)` of each silence in FFmpeg `silencedetect` output.
+fn parse_silences(stderr: &str) -> Vec<(f64, f64)> {
+    let mut silences = Vec::new();
+    let mut start: Option<f64> = None;
+    for line in stderr.lines() {
+        if let Some(rest) = line.split("silence_start:").nth(1) {
+            start = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+        } else if let Some(rest) = line.split("silence_end:").nth(1) {
+            let end = rest.split('|').next().and_then(|v| v.trim().parse::<f64>().ok());
+            if let (Some(s), Some(e)) = (start.take(), end) {
+                silences.push((s.max(0.0), e));
+            }
         }
+    }
+    silences
+}
+
+async fn detect_silences(audio_path: &str) -> Vec<(f64, f64)> {
+    let output = TokioCommand::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i", audio_path, "-af", CLOUD_SILENCE_FILTER, "-f", "null", "-"])
+        .output()
+        .await;
+    match output {
+        Ok(output) => parse_silences(&String::from_utf8_lossy(&output.stderr)),
+        Err(e) => {
+            log::warn!("[CloudASR] Silence detection failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Where to cut `total` seconds into pieces of at most `max_len` seconds,
+/// overlap included: the latest silence that keeps the piece under the cap,
+/// or a hard cut at the cap when the second half of the piece has none.
+fn choose_split_points(total: f64, max_len: f64, silences: &[(f64, f64)]) -> Vec<f64> {
+    let reach = (max_len - CLOUD_SEGMENT_OVERLAP_SECS).max(1.0);
+    let mut cuts = Vec::new();
+    let mut pos = 0.0;
+    while total - pos > max_len {
+        let limit = pos + reach;
+        let cut = silences.iter()
+            .filter(|(start, _)| *start < limit)
+            .map(|(start, end)| ((start + end) / 2.0).min(limit))
+            .filter(|point| *point > pos + reach / 2.0)
+            .fold(None, |best: Option<f64>, point| Some(best.map_or(point, |b| b.max(point))))
+            .unwrap_or(limit);
+        cuts.push(cut);
+        pos = cut;
+    }
+    cuts
+}
+
+/// `(start, duration)` of the segments between `cuts`, each running
+/// `CLOUD_SEGMENT_OVERLAP_SECS` into the next.
+fn segment_windows(total: f64, cuts: &[f64]) -> Vec<(f64, f64)> {
+    let starts = std::iter::once(0.0).chain(cuts.iter().copied());
+    let ends = cuts.iter().map(|cut| cut + CLOUD_SEGMENT_OVERLAP_SECS).chain(std::iter::once(total));
+    starts.zip(ends).map(|(start, end)| (start, end.min(total) - start)).collect()
+}
+
+/// Where an audio file too large for one upload is cut: `(start, duration)`
+/// of each segment, or `None` when it fits in one upload.
+async fn plan_cloud_segments(audio_path: &str, max_size_mb: u64) -> Result<Option<Vec<(f64, f64)>>, String> {
+    let file_size = fs::metadata(audio_path)
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?
+        .len();
+    if file_size / (1024 * 1024) <= max_size_mb {
+        return Ok(None);
+    }
+
+    // Longest segment that stays 1 MB under the cap at this file's bitrate
+    let total_duration = probe_duration(audio_path).await?;
+    let bytes_per_sec = file_size as f64 / total_duration.max(1.0);
+    let max_len = ((max_size_mb - 1) * 1024 * 1024) as f64 / bytes_per_sec;
+
+    let silences = detect_silences(audio_path).await;
+    let cuts = choose_split_points(total_duration, max_len, &silences);
+    Ok(Some(segment_windows(total_duration, &cuts)))
+}
+
+/// Cuts `[start, start + duration)` out of `source_path` into `segment_path`.
+async fn extract_cloud_segment(
+    source_path: &str,
+    segment_path: &Path,
+    start: f64,
+    duration: f64,
+) -> Result<(), String> {
+    let output = TokioCommand::new("ffmpeg")
+        .args([
+            "-hide_banner", "-nostats",
+            "-ss", &format!("{:.3}", start),
+            "-t", &format!("{:.3}", duration),
+            "-i", source_path,
+        ])
+        .args(CLOUD_AUDIO_ARGS)
+        .arg("-y")
+        .arg(segment_path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to split audio: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(segment_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg split failed: {}", stderr));
+    }
+    Ok(())
+}
+
+/// Model each cloud engine transcribes with; part of the segment cache key.
+fn cloud_asr_model(provider: &str) -> &'static str {
+    match provider {
+        "openai" => "whisper-1",
+        "groq" => "whisper-large-v3",
+        _ => "scribe_v2",
+    }
+}
+
+/// A failed transcription request, and whether sending it again may succeed.
+#[derive(Debug)]
+struct UploadError {
+    message: String,
+    retryable: bool,
+    /// Server-requested delay (`Retry-After`) before the next attempt.
+    retry_after: Option<std::time::Duration>,
+}
+
+impl From<String> for UploadError {
+    fn from(message: String) -> Self {
+        UploadError { message, retryable: false, retry_after: None }
+    }
+}
+
+impl From<&str> for UploadError {
+    fn from(message: &str) -> Self {
+        UploadError::from(message.to_string())
+    }
+}
+
+/// Rate limits and server errors are worth retrying; a 429 that reports an
+/// exhausted quota is not.
+fn is_retryable_upload_status(status: u16, body: &str) -> bool {
+    match status {
+        429 => !body.contains("insufficient_quota"),
+        500..=599 => true,
+        _ => false,
+    }
+}
+
+/// Turns the response of a transcription API into its JSON body or an
+/// `UploadError`. Connection failures and timeouts are retryable.
+async fn read_transcription_response(
+    sent: Result<reqwest::Response, reqwest::Error>,
+) -> Result<serde_json::Value, UploadError> {
+    let response = sent.map_err(|e| UploadError {
+        message: format!("API request failed: {}", e),
+        retryable: true,
+        retry_after: None,
+    })?;
 
-        segment_paths.push(segment_path.to_string_lossy().to_string());
+    let status = response.status();
+    let retry_after = response.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(std::time::Duration::from_secs);
+    let response_text = response.text().await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    if !status.is_success() {
+        let message = if status.as_u16() == 401 {
+            "errors.asr.invalid_api_key".to_string()
+        } else if status.as_u16() == 429 {
+            "errors.asr.quota_exhausted".to_string()
+        } else {
+            serde_json::json!({"key": "errors.asr.api_request_failed", "params": {"status": status.to_string(), "body": response_text}}).to_string()
+        };
+        return Err(UploadError {
+            message,
+            retryable: is_retryable_upload_status(status.as_u16(), &response_text),
+            retry_after,
+        });
     }
 
-    Ok(segment_paths)
+    serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse response: {}", e).into())
 }
 
 async fn upload_to_openai(
     api_key: &str,
     file_path: &str,
     language: &str,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, UploadError> {
     let client = reqwest::Client::new();
 
     let file_bytes = fs::read(file_path)
@@ -5278,8 +10057,10 @@ async fn upload_to_openai(
 
     let mut form = reqwest::multipart::Form::new()
         .part("file", file_part)
-        .text("model", "whisper-1")
-        .text("response_format", "verbose_json");
+        .text("model", cloud_asr_model("openai"))
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment")
+        .text("timestamp_granularities[]", "word");
 
     if language != "auto" {
         form = form.text("language", language.to_string());
@@ -5290,32 +10071,16 @@ async fn upload_to_openai(
         .header("Authorization", format!("Bearer {}", api_key))
         .multipart(form)
         .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-
-    let status = response.status();
-    let response_text = response.text().await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-
-    if !status.is_success() {
-        if status.as_u16() == 401 {
-            return Err("errors.asr.invalid_api_key".to_string());
-        } else if status.as_u16() == 429 {
-            return Err("errors.asr.quota_exhausted".to_string());
-        } else {
-            return Err(serde_json::json!({"key": "errors.asr.api_request_failed", "params": {"status": status.to_string(), "body": response_text}}).to_string());
-        }
-    }
+        .await;
 
-    serde_json::from_str(&response_text)
-        .map_err(|e| format!("Failed to parse response: {}", e))
+    read_transcription_response(response).await
 }
 
 async fn upload_to_groq(
     api_key: &str,
     file_path: &str,
     language: &str,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, UploadError> {
     let client = reqwest::Client::new();
 
     let file_bytes = fs::read(file_path)
@@ -5334,8 +10099,10 @@ async fn upload_to_groq(
 
     let mut form = reqwest::multipart::Form::new()
         .part("file", file_part)
-        .text("model", "whisper-large-v3")
-        .text("response_format", "verbose_json");
+        .text("model", cloud_asr_model("groq"))
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment")
+        .text("timestamp_granularities[]", "word");
 
     if language != "auto" {
         form = form.text("language", language.to_string());
@@ -5346,32 +10113,16 @@ async fn upload_to_groq(
         .header("Authorization", format!("Bearer {}", api_key))
         .multipart(form)
         .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-
-    let status = response.status();
-    let response_text = response.text().await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-
-    if !status.is_success() {
-        if status.as_u16() == 401 {
-            return Err("errors.asr.invalid_api_key".to_string());
-        } else if status.as_u16() == 429 {
-            return Err("errors.asr.quota_exhausted".to_string());
-        } else {
-            return Err(serde_json::json!({"key": "errors.asr.api_request_failed", "params": {"status": status.to_string(), "body": response_text}}).to_string());
-        }
-    }
+        .await;
 
-    serde_json::from_str(&response_text)
-        .map_err(|e| format!("Failed to parse response: {}", e))
+    read_transcription_response(response).await
 }
 
 async fn upload_to_elevenlabs(
     api_key: &str,
     file_path: &str,
     language: &str,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, UploadError> {
     let client = reqwest::Client::new();
 
     let file_bytes = fs::read(file_path)
@@ -5390,7 +10141,7 @@ async fn upload_to_elevenlabs(
 
     let mut form = reqwest::multipart::Form::new()
         .part("audio", file_part)
-        .text("model_id", "scribe_v2");
+        .text("model_id", cloud_asr_model("elevenlabs"));
 
     if language != "auto" {
         form = form.text("language", language.to_string());
@@ -5401,160 +10152,1031 @@ async fn upload_to_elevenlabs(
         .header("xi-api-key", api_key)
         .multipart(form)
         .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
+        .await;
 
-    let status = response.status();
-    let response_text = response.text().await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+    read_transcription_response(response).await
+}
 
-    if !status.is_success() {
-        if status.as_u16() == 401 {
-            return Err("errors.asr.invalid_api_key".to_string());
-        } else if status.as_u16() == 429 {
-            return Err("errors.asr.quota_exhausted".to_string());
+/// A transcript in the form every engine's result is converted to, so all
+/// engines write the same set of output formats from it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Transcript {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TranscriptSegment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    /// Probability from 0 to 1, when the engine reports one.
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TranscriptWord {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+/// Output formats a transcript can be written as, in the order they are written.
+const TRANSCRIPT_FORMATS: [&str; 5] = ["srt", "vtt", "ass", "txt", "json"];
+
+fn logprob_to_confidence(logprob: Option<f64>) -> Option<f64> {
+    logprob.map(|p| p.exp().clamp(0.0, 1.0))
+}
+
+/// Converts an OpenAI or Groq `verbose_json` response. Words, when the
+/// response has them, are attached to the segment they start in.
+fn transcript_from_openai(response: &serde_json::Value) -> Result<Transcript, String> {
+    let raw_segments = response.get("segments")
+        .and_then(|v| v.as_array())
+        .ok_or("No segments in response")?;
+
+    let mut segments = Vec::new();
+    for segment in raw_segments {
+        let start = segment.get("start")
+            .and_then(|v| v.as_f64())
+            .ok_or("Missing start time")?;
+        let end = segment.get("end")
+            .and_then(|v| v.as_f64())
+            .ok_or("Missing end time")?;
+        let text = segment.get("text")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing text")?;
+
+        segments.push(TranscriptSegment {
+            start,
+            end,
+            text: text.trim().to_string(),
+            speaker: None,
+            confidence: logprob_to_confidence(segment.get("avg_logprob").and_then(|v| v.as_f64())),
+            words: Vec::new(),
+        });
+    }
+
+    let raw_words = response.get("words").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or(&[]);
+    for word in raw_words {
+        let (Some(start), Some(end)) = (
+            word.get("start").and_then(|v| v.as_f64()),
+            word.get("end").and_then(|v| v.as_f64()),
+        ) else {
+            continue;
+        };
+        let text = word.get("word").or_else(|| word.get("text"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let index = segments.partition_point(|s| s.start <= start).saturating_sub(1);
+        if let Some(segment) = segments.get_mut(index) {
+            segment.words.push(TranscriptWord {
+                start,
+                end,
+                text: text.trim().to_string(),
+                speaker: None,
+                confidence: word.get("probability").and_then(|v| v.as_f64()),
+            });
+        }
+    }
+
+    Ok(Transcript {
+        language: response.get("language").and_then(|v| v.as_str()).map(String::from),
+        segments,
+    })
+}
+
+/// Words in an ElevenLabs segment at most. Its segments are written as they
+/// are when no segmentation rules are set, so long unpunctuated speech
+/// still comes out as readable subtitles.
+const ELEVENLABS_SEGMENT_MAX_WORDS: usize = 10;
+
+/// Converts an ElevenLabs response, grouping its words into segments at
+/// sentence punctuation, a change of speaker, or every
+/// `ELEVENLABS_SEGMENT_MAX_WORDS` words.
+fn transcript_from_elevenlabs(response: &serde_json::Value) -> Result<Transcript, String> {
+    let raw_words = response.get("words")
+        .and_then(|v| v.as_array())
+        .ok_or("No words in response")?;
+    // Spacing between words comes as separate items; without them words are
+    // joined with a space.
+    let has_spacing = raw_words.iter()
+        .any(|w| w.get("type").and_then(|v| v.as_str()) == Some("spacing"));
+
+    fn finish(words: &mut Vec<TranscriptWord>, text: &mut String, segments: &mut Vec<TranscriptSegment>) {
+        if words.is_empty() {
+            text.clear();
+            return;
+        }
+        let scores: Vec<f64> = words.iter().filter_map(|w| w.confidence).collect();
+        segments.push(TranscriptSegment {
+            start: words[0].start,
+            end: words[words.len() - 1].end,
+            text: text.trim().to_string(),
+            speaker: words[0].speaker.clone(),
+            confidence: (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+            words: std::mem::take(words),
+        });
+        text.clear();
+    }
+
+    let mut segments = Vec::new();
+    let mut current: Vec<TranscriptWord> = Vec::new();
+    let mut text = String::new();
+
+    for word_obj in raw_words {
+        let word = word_obj.get("text")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing word text")?;
+        if word_obj.get("type").and_then(|v| v.as_str()) == Some("spacing") {
+            if !current.is_empty() {
+                text.push_str(word);
+            }
+            continue;
+        }
+        let start = word_obj.get("start")
+            .and_then(|v| v.as_f64())
+            .ok_or("Missing start time")?;
+        let end = word_obj.get("end")
+            .and_then(|v| v.as_f64())
+            .ok_or("Missing end time")?;
+        let speaker = word_obj.get("speaker_id").and_then(|v| v.as_str()).map(String::from);
+
+        if current.last().is_some_and(|last| last.speaker != speaker) {
+            finish(&mut current, &mut text, &mut segments);
+        }
+        if !has_spacing && !current.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(word);
+        current.push(TranscriptWord {
+            start,
+            end,
+            text: word.to_string(),
+            speaker,
+            confidence: logprob_to_confidence(word_obj.get("logprob").and_then(|v| v.as_f64())),
+        });
+
+        if ends_sentence(word) || current.len() >= ELEVENLABS_SEGMENT_MAX_WORDS {
+            finish(&mut current, &mut text, &mut segments);
+        }
+    }
+    finish(&mut current, &mut text, &mut segments);
+
+    Ok(Transcript {
+        language: response.get("language_code").and_then(|v| v.as_str()).map(String::from),
+        segments,
+    })
+}
+
+fn transcript_from_cloud_response(provider: &str, response: &serde_json::Value) -> Result<Transcript, String> {
+    match provider {
+        "openai" | "groq" => transcript_from_openai(response),
+        "elevenlabs" => transcript_from_elevenlabs(response),
+        _ => Err(format!("Unsupported cloud engine: {}", provider)),
+    }
+}
+
+/// A pause at least this long always starts a new subtitle.
+const CUE_PAUSE_BREAK_SECS: f64 = 1.5;
+/// A pause at least this long is a good place to split an overlong subtitle.
+const CUE_SOFT_PAUSE_SECS: f64 = 0.3;
+
+/// How a transcript is cut into subtitles, from the `max_seconds`,
+/// `max_chars` and `enable_punctuation` fields of `TranscriptionConfig`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SegmentationRules {
+    max_seconds: Option<f64>,
+    /// Characters per line; a subtitle holds up to two lines.
+    max_chars: Option<usize>,
+    /// End subtitles at sentence punctuation and prefer splitting at clause
+    /// punctuation.
+    punctuation: bool,
+}
+
+impl SegmentationRules {
+    fn from_config(config: &TranscriptionConfig) -> Self {
+        Self {
+            max_seconds: (config.max_seconds > 0).then_some(config.max_seconds as f64),
+            max_chars: (config.max_chars > 0).then_some(config.max_chars as usize),
+            punctuation: config.enable_punctuation,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.max_seconds.is_some() || self.max_chars.is_some() || self.punctuation
+    }
+}
+
+/// Scripts written without spaces between words. Hangul is left out since
+/// Korean separates words with spaces.
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x9FFF | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFFEF | 0x20000..=0x2FFFF)
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end().ends_with(['.', '?', '!', '…', '。', '？', '！'])
+}
+
+fn ends_clause(text: &str) -> bool {
+    text.trim_end().ends_with([',', ';', ':', '，', '、', '；', '：'])
+}
+
+/// Joins words with a space, except next to CJK text.
+fn join_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for word in words {
+        let word = word.trim();
+        if word.is_empty() {
+            continue;
+        }
+        let spaced = match (joined.chars().last(), word.chars().next()) {
+            (Some(prev), Some(next)) => !is_cjk(prev) && !is_cjk(next),
+            _ => false,
+        };
+        if spaced {
+            joined.push(' ');
+        }
+        joined.push_str(word);
+    }
+    joined
+}
+
+/// A word on the way into a subtitle. Segments without word timings are
+/// split into tokens with times spread over the segment by length;
+/// those are `synthetic` and not kept as words.
+#[derive(Debug, Clone)]
+struct CueToken {
+    start: f64,
+    end: f64,
+    text: String,
+    speaker: Option<String>,
+    confidence: Option<f64>,
+    synthetic: bool,
+}
+
+fn split_segment_text(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() || is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+            // Punctuation stays with the character before it
+            if c.is_alphanumeric() || tokens.is_empty() {
+                tokens.push(c.to_string());
+            } else if let Some(last) = tokens.last_mut() {
+                last.push(c);
+            }
         } else {
-            return Err(serde_json::json!({"key": "errors.asr.api_request_failed", "params": {"status": status.to_string(), "body": response_text}}).to_string());
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+fn transcript_tokens(transcript: &Transcript) -> Vec<CueToken> {
+    let mut tokens = Vec::new();
+    for segment in &transcript.segments {
+        if !segment.words.is_empty() {
+            tokens.extend(segment.words.iter().map(|w| CueToken {
+                start: w.start,
+                end: w.end,
+                text: w.text.clone(),
+                speaker: w.speaker.clone().or_else(|| segment.speaker.clone()),
+                confidence: w.confidence,
+                synthetic: false,
+            }));
+            continue;
+        }
+        let parts = split_segment_text(&segment.text);
+        let total_chars = parts.iter().map(|p| p.chars().count()).sum::<usize>().max(1) as f64;
+        let span = (segment.end - segment.start).max(0.0);
+        let mut chars = 0;
+        let mut time = segment.start;
+        for part in parts {
+            chars += part.chars().count();
+            let end = segment.start + span * chars as f64 / total_chars;
+            tokens.push(CueToken {
+                start: time,
+                end,
+                text: part,
+                speaker: segment.speaker.clone(),
+                confidence: segment.confidence,
+                synthetic: true,
+            });
+            time = end;
+        }
+    }
+    tokens
+}
+
+/// Breaks words into at most two lines of `max_chars`, after punctuation
+/// where that fits and otherwise as even in length as possible. Returns
+/// `None` when they do not fit.
+fn balance_lines(words: &[&str], max_chars: usize) -> Option<Vec<String>> {
+    let single = join_words(words.iter().copied());
+    if single.chars().count() <= max_chars {
+        return Some(vec![single]);
+    }
+    (1..words.len())
+        .map(|i| (join_words(words[..i].iter().copied()), join_words(words[i..].iter().copied())))
+        .filter(|(first, second)| first.chars().count() <= max_chars && second.chars().count() <= max_chars)
+        .min_by_key(|(first, second)| (
+            !(ends_clause(first) || ends_sentence(first)),
+            first.chars().count().abs_diff(second.chars().count()),
+        ))
+        .map(|(first, second)| vec![first, second])
+}
+
+/// Whether `next` can join the subtitle made of `tokens`.
+fn cue_fits(tokens: &[CueToken], next: &CueToken, rules: &SegmentationRules) -> bool {
+    let Some(first) = tokens.first() else { return true };
+    if rules.max_seconds.is_some_and(|max| next.end - first.start > max) {
+        return false;
+    }
+    let words: Vec<&str> = tokens.iter().chain([next]).map(|t| t.text.as_str()).collect();
+    rules.max_chars.map_or(true, |max| balance_lines(&words, max).is_some())
+}
+
+/// Where to split an overlong subtitle: after the last clause punctuation or
+/// pause in its second half, or else before the word that did not fit.
+fn soft_break_index(tokens: &[CueToken], rules: &SegmentationRules) -> usize {
+    (tokens.len() / 2..tokens.len())
+        .rev()
+        .filter(|&i| i > 0)
+        .find(|&i| {
+            (rules.punctuation && ends_clause(&tokens[i - 1].text))
+                || tokens[i].start - tokens[i - 1].end >= CUE_SOFT_PAUSE_SECS
+        })
+        .unwrap_or(tokens.len())
+}
+
+fn cue_segment(tokens: Vec<CueToken>, rules: &SegmentationRules) -> TranscriptSegment {
+    let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+    let text = rules.max_chars
+        .and_then(|max| balance_lines(&words, max))
+        .map(|lines| lines.join("\n"))
+        .unwrap_or_else(|| join_words(words.iter().copied()));
+    let scores: Vec<f64> = tokens.iter().filter_map(|t| t.confidence).collect();
+    TranscriptSegment {
+        start: tokens[0].start,
+        end: tokens[tokens.len() - 1].end,
+        text,
+        speaker: tokens[0].speaker.clone(),
+        confidence: (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+        words: tokens.into_iter()
+            .filter(|t| !t.synthetic)
+            .map(|t| TranscriptWord {
+                start: t.start,
+                end: t.end,
+                text: t.text,
+                speaker: t.speaker,
+                confidence: t.confidence,
+            })
+            .collect(),
+    }
+}
+
+/// Re-cuts a transcript into subtitles from its word timings so each stays
+/// within the duration and line-length limits, breaking at speaker changes,
+/// long pauses and (with punctuation rules on) sentence ends. Line lengths
+/// count characters, so CJK text needs no word boundaries.
+fn resegment_transcript(transcript: &Transcript, rules: &SegmentationRules) -> Transcript {
+    if !rules.is_active() {
+        return transcript.clone();
+    }
+
+    let mut cues: Vec<Vec<CueToken>> = Vec::new();
+    let mut current: Vec<CueToken> = Vec::new();
+    for token in transcript_tokens(transcript) {
+        if let Some(last) = current.last() {
+            let hard_break = last.speaker != token.speaker
+                || token.start - last.end >= CUE_PAUSE_BREAK_SECS
+                || (rules.punctuation && ends_sentence(&last.text));
+            if hard_break {
+                cues.push(std::mem::take(&mut current));
+            } else if !cue_fits(&current, &token, rules) {
+                let rest = current.split_off(soft_break_index(&current, rules));
+                cues.push(std::mem::replace(&mut current, rest));
+                if !cue_fits(&current, &token, rules) {
+                    cues.push(std::mem::take(&mut current));
+                }
+            }
+        }
+        current.push(token);
+    }
+    cues.push(current);
+
+    Transcript {
+        language: transcript.language.clone(),
+        segments: cues.into_iter()
+            .filter(|cue| !cue.is_empty())
+            .map(|cue| cue_segment(cue, rules))
+            .collect(),
+    }
+}
+
+/// Chinese script conversions, named as in OpenCC.
+const CHINESE_CONVERSIONS: [&str; 4] = ["s2t", "s2tw", "s2hk", "t2s"];
+
+// A subset of OpenCC's dictionaries covering the characters of everyday
+// text; `CHINESE_COMMON_CHARACTERS` in the tests lists what they must cover.
+// Taken from data/dictionary of OpenCC 1.1 (https://github.com/BYVoid/OpenCC,
+// Apache-2.0); see opencc/LICENSE and opencc/NOTICE.
+const OPENCC_ST_CHARACTERS: &str = include_str!("opencc/STCharacters.txt");
+const OPENCC_ST_PHRASES: &str = include_str!("opencc/STPhrases.txt");
+const OPENCC_TS_CHARACTERS: &str = include_str!("opencc/TSCharacters.txt");
+const OPENCC_TS_PHRASES: &str = include_str!("opencc/TSPhrases.txt");
+const OPENCC_TW_VARIANTS: &str = include_str!("opencc/TWVariants.txt");
+const OPENCC_HK_VARIANTS: &str = include_str!("opencc/HKVariants.txt");
+
+/// One conversion step, replacing the longest entry that matches at each
+/// position.
+struct ChineseDictionary {
+    entries: HashMap<String, String>,
+    max_key_chars: usize,
+}
+
+impl ChineseDictionary {
+    /// Builds a dictionary; the first value given for a key wins.
+    fn from_pairs(pairs: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut entries = HashMap::new();
+        for (key, value) in pairs {
+            entries.entry(key).or_insert(value);
+        }
+        let max_key_chars = entries.keys().map(|k| k.chars().count()).max().unwrap_or(0);
+        Self { entries, max_key_chars }
+    }
+
+    fn convert(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut converted = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let longest = self.max_key_chars.min(chars.len() - i);
+            let matched = (1..=longest).rev().find_map(|len| {
+                let key: String = chars[i..i + len].iter().collect();
+                self.entries.get(&key).map(|value| (len, value))
+            });
+            match matched {
+                Some((len, value)) => {
+                    converted.push_str(value);
+                    i += len;
+                }
+                None => {
+                    converted.push(chars[i]);
+                    i += 1;
+                }
+            }
+        }
+        converted
+    }
+}
+
+/// Reads an OpenCC text dictionary: one `key<TAB>value [alternatives]` per
+/// line, of which the first value is used.
+fn opencc_pairs(source: &'static str) -> impl Iterator<Item = (String, String)> {
+    source.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, values) = line.split_once('\t')?;
+            let value = values.split_whitespace().next()?;
+            Some((key.to_string(), value.to_string()))
+        })
+}
+
+struct ChineseConverters {
+    s2t: ChineseDictionary,
+    tw_variants: ChineseDictionary,
+    hk_variants: ChineseDictionary,
+    /// Taiwan and Hong Kong forms back to the standard Traditional form.
+    variants_rev: ChineseDictionary,
+    t2s: ChineseDictionary,
+}
+
+static CHINESE_CONVERTERS: std::sync::OnceLock<ChineseConverters> = std::sync::OnceLock::new();
+
+fn chinese_converters() -> &'static ChineseConverters {
+    CHINESE_CONVERTERS.get_or_init(|| {
+        let t2s = ChineseDictionary::from_pairs(
+            opencc_pairs(OPENCC_TS_PHRASES).chain(opencc_pairs(OPENCC_TS_CHARACTERS)),
+        );
+        // Regional forms are undone only where the standard form converts
+        // further, so e.g. 吃 is not turned into 喫.
+        let variants_rev = ChineseDictionary::from_pairs(
+            opencc_pairs(OPENCC_TW_VARIANTS)
+                .chain(opencc_pairs(OPENCC_HK_VARIANTS))
+                .map(|(standard, regional)| (regional, standard))
+                .filter(|(_, standard)| t2s.entries.contains_key(standard)),
+        );
+        ChineseConverters {
+            s2t: ChineseDictionary::from_pairs(
+                opencc_pairs(OPENCC_ST_PHRASES).chain(opencc_pairs(OPENCC_ST_CHARACTERS)),
+            ),
+            tw_variants: ChineseDictionary::from_pairs(opencc_pairs(OPENCC_TW_VARIANTS)),
+            hk_variants: ChineseDictionary::from_pairs(opencc_pairs(OPENCC_HK_VARIANTS)),
+            variants_rev,
+            t2s,
+        }
+    })
+}
+
+/// Converts text between Simplified and Traditional Chinese: `s2t` to
+/// standard Traditional, `s2tw` and `s2hk` to the Taiwan and Hong Kong forms,
+/// and `t2s` from any of those to Simplified.
+fn convert_chinese(text: &str, conversion: &str) -> String {
+    let converters = chinese_converters();
+    match conversion {
+        "s2t" => converters.s2t.convert(text),
+        "s2tw" => converters.tw_variants.convert(&converters.s2t.convert(text)),
+        "s2hk" => converters.hk_variants.convert(&converters.s2t.convert(text)),
+        "t2s" => converters.t2s.convert(&converters.variants_rev.convert(text)),
+        _ => text.to_string(),
+    }
+}
+
+/// The script conversion a job asks for. `traditional_chinese` is the older
+/// switch for `s2t`.
+fn chinese_conversion(config: &TranscriptionConfig) -> Result<Option<&'static str>, String> {
+    match config.chinese_conversion.as_deref().filter(|c| !c.is_empty()) {
+        Some(requested) => CHINESE_CONVERSIONS.iter()
+            .find(|c| **c == requested)
+            .map(|c| Some(*c))
+            .ok_or_else(|| "errors.asr.unsupported_chinese_conversion".to_string()),
+        None => Ok(config.traditional_chinese.then_some("s2t")),
+    }
+}
+
+/// Whether a language setting or detected language is Chinese. Engines
+/// report either codes (`zh`, `cmn`, `yue`) or names (`chinese`).
+fn is_chinese_language(language: &str) -> bool {
+    let language = language.to_lowercase();
+    language.starts_with("zh")
+        || ["chinese", "mandarin", "cantonese", "cmn", "yue", "zho"].contains(&language.as_str())
+}
+
+/// Applies the job's script conversion to a Chinese transcript. Other
+/// languages are left alone, since Japanese kanji would be changed too.
+fn convert_transcript_script(transcript: &mut Transcript, config: &TranscriptionConfig) -> Result<(), String> {
+    let Some(conversion) = chinese_conversion(config)? else { return Ok(()) };
+    let language = if config.language == "auto" {
+        transcript.language.as_deref().unwrap_or("")
+    } else {
+        config.language.as_str()
+    };
+    if !is_chinese_language(language) {
+        return Ok(());
+    }
+    for segment in &mut transcript.segments {
+        segment.text = convert_chinese(&segment.text, conversion);
+        for word in &mut segment.words {
+            word.text = convert_chinese(&word.text, conversion);
+        }
+    }
+    Ok(())
+}
+
+/// Splits a time in seconds into hours, minutes, seconds and milliseconds.
+fn timestamp_parts(seconds: f64) -> (u64, u64, u64, u64) {
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let total_seconds = total_millis / 1000;
+    (total_seconds / 3600, (total_seconds % 3600) / 60, total_seconds % 60, total_millis % 1000)
+}
+
+fn format_srt_time(seconds: f64) -> String {
+    let (hours, minutes, secs, millis) = timestamp_parts(seconds);
+    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, secs, millis)
+}
+
+fn format_vtt_time(seconds: f64) -> String {
+    let (hours, minutes, secs, millis) = timestamp_parts(seconds);
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+}
+
+/// ASS times use a single hour digit and centiseconds.
+fn format_ass_time(seconds: f64) -> String {
+    let (hours, minutes, secs, millis) = timestamp_parts(seconds);
+    format!("{}:{:02}:{:02}.{:02}", hours, minutes, secs, millis / 10)
+}
+
+/// Resolves a `TranscriptionConfig.output_format` to the formats to write:
+/// one format, a comma-separated list, `both` (SRT and TXT) or `all`.
+fn transcript_output_formats(output_format: &str) -> Result<Vec<&'static str>, String> {
+    match output_format {
+        "both" => return Ok(vec!["srt", "txt"]),
+        "all" => return Ok(TRANSCRIPT_FORMATS.to_vec()),
+        _ => {}
+    }
+    let requested: Vec<&str> = output_format.split(',').map(str::trim).collect();
+    if requested.iter().any(|f| !TRANSCRIPT_FORMATS.contains(f)) {
+        return Err("errors.asr.unsupported_output_format".to_string());
+    }
+    Ok(TRANSCRIPT_FORMATS.iter().copied().filter(|f| requested.contains(f)).collect())
+}
+
+fn transcript_to_srt(transcript: &Transcript) -> String {
+    let mut srt = String::new();
+    for (i, segment) in transcript.segments.iter().enumerate() {
+        srt.push_str(&format!("{}\n", i + 1));
+        srt.push_str(&format!("{} --> {}\n", format_srt_time(segment.start), format_srt_time(segment.end)));
+        srt.push_str(&format!("{}\n\n", segment.text.trim()));
+    }
+    srt
+}
+
+fn transcript_to_vtt(transcript: &Transcript) -> String {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut vtt = String::from("WEBVTT\n\n");
+    for segment in &transcript.segments {
+        vtt.push_str(&format!("{} --> {}\n", format_vtt_time(segment.start), format_vtt_time(segment.end)));
+        let text = escape(segment.text.trim());
+        match &segment.speaker {
+            Some(speaker) => vtt.push_str(&format!("<v {}>{}\n\n", escape(speaker), text)),
+            None => vtt.push_str(&format!("{}\n\n", text)),
+        }
+    }
+    vtt
+}
+
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,64,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+fn transcript_to_ass(transcript: &Transcript) -> String {
+    let mut ass = String::from(ASS_HEADER);
+    for segment in &transcript.segments {
+        // ASS has no escape for override braces, and the name field ends at a comma.
+        let text = segment.text.trim()
+            .replace('{', "(")
+            .replace('}', ")")
+            .replace('\n', "\\N");
+        let name = segment.speaker.as_deref().unwrap_or("").replace(',', " ");
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},Default,{},0,0,0,,{}\n",
+            format_ass_time(segment.start),
+            format_ass_time(segment.end),
+            name,
+            text
+        ));
+    }
+    ass
+}
+
+fn transcript_to_txt(transcript: &Transcript) -> String {
+    let mut txt = String::new();
+    for segment in &transcript.segments {
+        // Subtitle line breaks are undone in plain text
+        let text = join_words(segment.text.lines());
+        if !text.is_empty() {
+            txt.push_str(&text);
+            txt.push('\n');
+        }
+    }
+    txt
+}
+
+fn render_transcript(transcript: &Transcript, format: &str) -> Result<String, String> {
+    match format {
+        "srt" => Ok(transcript_to_srt(transcript)),
+        "vtt" => Ok(transcript_to_vtt(transcript)),
+        "ass" => Ok(transcript_to_ass(transcript)),
+        "txt" => Ok(transcript_to_txt(transcript)),
+        "json" => serde_json::to_string_pretty(transcript)
+            .map_err(|e| format!("Failed to serialize transcript: {}", e)),
+        _ => Err("errors.asr.unsupported_output_format".to_string()),
+    }
+}
+
+/// Converts the transcript's script and cuts it into subtitles by the job's
+/// rules, writes it next to the input file in each requested format and
+/// emits `transcription-complete` with the written paths.
+fn finish_transcription(
+    app: &AppHandle,
+    transcript: &Transcript,
+    config: &TranscriptionConfig,
+) -> Result<(), String> {
+    let mut transcript = transcript.clone();
+    convert_transcript_script(&mut transcript, config)?;
+    let transcript = resegment_transcript(&transcript, &SegmentationRules::from_config(config));
+    let input_pathbuf = PathBuf::from(&config.input_file);
+    let parent_dir = input_pathbuf.parent()
+        .ok_or("Invalid input path")?;
+    let file_stem = input_pathbuf.file_stem()
+        .ok_or("Invalid file name")?
+        .to_string_lossy();
+
+    let mut output_paths = Vec::new();
+    for format in transcript_output_formats(&config.output_format)? {
+        let content = render_transcript(&transcript, format)?;
+        let path = parent_dir.join(format!("{}.{}", file_stem, format));
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {} file: {}", format.to_uppercase(), e))?;
+        output_paths.push(path.to_string_lossy().to_string());
+    }
+
+    let complete_payload = serde_json::json!({
+        "output_path": output_paths.first(),
+        "output_paths": output_paths,
+    });
+    let _ = app.emit("transcription-complete", &complete_payload);
+
+    Ok(())
+}
+
+/// Merges segment responses onto one timeline. `offsets[i]` is where
+/// segment `i` starts and where segment `i - 1` is cut: each response keeps
+/// what starts before the next one's offset, and everything from its own
+/// offset on is taken from it. The overlap past a cut only dedupes a word
+/// that spans it: the earlier response heard the whole word, so the later
+/// copy is dropped and the segment holding it is rebuilt from the words
+/// left. A segment running past the cut is trimmed to its words the same way.
+fn merge_transcriptions_with_offset(
+    segments: &[serde_json::Value],
+    offsets: &[f64],
+) -> Result<serde_json::Value, String> {
+    fn time(item: &serde_json::Value, key: &str) -> Option<f64> {
+        item.get(key).and_then(|v| v.as_f64())
+    }
+    fn shifted(items: Option<&serde_json::Value>, offset: f64) -> Vec<serde_json::Value> {
+        let items = items.and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or(&[]);
+        items.iter().map(|item| {
+            let mut new_item = item.clone();
+            if let Some(obj) = new_item.as_object_mut() {
+                for key in ["start", "end"] {
+                    if let Some(value) = obj.get(key).and_then(|v| v.as_f64()) {
+                        obj.insert(key.to_string(), serde_json::Value::from(value + offset));
+                    }
+                }
+            }
+            new_item
+        }).collect()
+    }
+
+    let mut merged_segments = Vec::new();
+    let mut merged_words: Vec<serde_json::Value> = Vec::new();
+
+    for (i, response) in segments.iter().enumerate() {
+        let offset = offsets.get(i).copied().unwrap_or(0.0);
+        let cut = offsets.get(i + 1).copied().unwrap_or(f64::MAX);
+        // Where the word the previous response kept across this offset ends
+        let resume_at = merged_words.last().and_then(|w| time(w, "end")).unwrap_or(f64::MIN);
+
+        let words = shifted(response.get("words"), offset);
+        let (kept_words, dropped_words): (Vec<_>, Vec<_>) = words.into_iter().partition(|word| {
+            let start = time(word, "start").unwrap_or(offset);
+            start < cut && start >= resume_at
+        });
+
+        for mut segment in shifted(response.get("segments"), offset) {
+            let start = time(&segment, "start").unwrap_or(offset);
+            if start >= cut {
+                continue;
+            }
+            let end = time(&segment, "end").unwrap_or(start);
+            let in_segment = |word: &&serde_json::Value| {
+                time(word, "start").is_some_and(|s| s >= start && s < end)
+            };
+            if !dropped_words.iter().any(|w| in_segment(&w)) {
+                merged_segments.push(segment);
+                continue;
+            }
+            let own_words: Vec<&serde_json::Value> = kept_words.iter().filter(in_segment).collect();
+            let Some(last_end) = own_words.last().and_then(|w| time(w, "end")) else { continue };
+            let text = join_words(own_words.iter().filter_map(|w| {
+                w.get("word").or_else(|| w.get("text")).and_then(|t| t.as_str())
+            }));
+            if let Some(obj) = segment.as_object_mut() {
+                obj.insert("end".to_string(), serde_json::Value::from(last_end.min(end)));
+                obj.insert("text".to_string(), serde_json::Value::from(text));
+            }
+            merged_segments.push(segment);
         }
+        merged_words.extend(kept_words);
     }
 
-    serde_json::from_str(&response_text)
-        .map_err(|e| format!("Failed to parse response: {}", e))
-}
-
-fn generate_srt_from_openai(response: &serde_json::Value) -> Result<String, String> {
-    let segments = response.get("segments")
-        .and_then(|v| v.as_array())
-        .ok_or("No segments in response")?;
-
-    let mut srt = String::new();
-    for (i, segment) in segments.iter().enumerate() {
-        let start = segment.get("start")
-            .and_then(|v| v.as_f64())
-            .ok_or("Missing start time")?;
-        let end = segment.get("end")
-            .and_then(|v| v.as_f64())
-            .ok_or("Missing end time")?;
-        let text = segment.get("text")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing text")?;
+    let text = if merged_segments.is_empty() {
+        merged_words.iter()
+            .filter_map(|w| w.get("text").and_then(|t| t.as_str()))
+            .collect::<String>()
+    } else {
+        merged_segments.iter()
+            .filter_map(|s| s.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
 
-        srt.push_str(&format!("{}\n", i + 1));
-        srt.push_str(&format!("{} --> {}\n", format_srt_time(start), format_srt_time(end)));
-        srt.push_str(&format!("{}\n\n", text.trim()));
+    let mut merged = serde_json::json!({
+        "segments": merged_segments,
+        "text": text,
+    });
+    if !merged_words.is_empty() {
+        merged["words"] = serde_json::Value::from(merged_words);
     }
-
-    Ok(srt)
+    Ok(merged)
 }
 
-fn generate_srt_from_elevenlabs(response: &serde_json::Value) -> Result<String, String> {
-    let words = response.get("words")
-        .and_then(|v| v.as_array())
-        .ok_or("No words in response")?;
+/// Uploads running at once in one cloud transcription job.
+const CLOUD_UPLOAD_CONCURRENCY: usize = 3;
+/// Retries of a segment upload after a rate limit or server error.
+const CLOUD_UPLOAD_MAX_RETRIES: u32 = 4;
+/// Cached segment results unused for this long are removed.
+const TRANSCRIPTION_CACHE_MAX_AGE_SECS: u64 = 7 * 24 * 60 * 60;
 
-    // Group words into subtitle segments (every 10 words or at punctuation)
-    let mut srt = String::new();
-    let mut current_segment = Vec::new();
-    let mut segment_index = 1;
+/// A transcribed segment as cached on disk.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedCloudSegment {
+    start: f64,
+    response: serde_json::Value,
+}
 
-    for word_obj in words {
-        let word = word_obj.get("text")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing word text")?;
-        let start = word_obj.get("start")
-            .and_then(|v| v.as_f64())
-            .ok_or("Missing start time")?;
-        let end = word_obj.get("end")
-            .and_then(|v| v.as_f64())
-            .ok_or("Missing end time")?;
+/// How a job's audio is sent, saved with its cached results so a resumed job
+/// cuts the same segments.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CloudSegmentPlan {
+    /// Upload the re-encoded `audio.mp3` in the cache directory rather than
+    /// the input file.
+    compact: bool,
+    /// `(start, duration)` of each segment, or `None` for a single upload.
+    segments: Option<Vec<(f64, f64)>>,
+}
 
-        current_segment.push((word, start, end));
+/// One cloud transcription job: what to send and where its results go.
+struct CloudTranscriptionJob {
+    provider: String,
+    api_key: String,
+    /// The file segments are cut from (or uploaded whole).
+    source_file: String,
+    language: String,
+    /// Planned segments, or `None` to upload `source_file` as is.
+    segments: Option<Vec<(f64, f64)>>,
+    cache_dir: PathBuf,
+}
 
-        // End segment at punctuation or after 10 words
-        let should_end = word.ends_with('.') || word.ends_with('?') || word.ends_with('!') ||
-                         word.ends_with('。') || word.ends_with('？') || word.ends_with('！') ||
-                         current_segment.len() >= 10;
+impl CloudTranscriptionJob {
+    fn cache_path(&self, index: usize) -> PathBuf {
+        self.cache_dir.join(format!("segment_{:03}.json", index))
+    }
 
-        if should_end && !current_segment.is_empty() {
-            let first_start = current_segment[0].1;
-            let last_end = current_segment[current_segment.len() - 1].2;
-            let text: String = current_segment.iter()
-                .map(|(w, _, _)| *w)
-                .collect::<Vec<_>>()
-                .join(" ");
+    fn cached_segment(&self, index: usize) -> Option<CachedCloudSegment> {
+        fs::read_to_string(self.cache_path(index)).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+}
 
-            srt.push_str(&format!("{}\n", segment_index));
-            srt.push_str(&format!("{} --> {}\n", format_srt_time(first_start), format_srt_time(last_end)));
-            srt.push_str(&format!("{}\n\n", text.trim()));
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
 
-            segment_index += 1;
-            current_segment.clear();
+/// SHA-256 of a file's contents, read in chunks.
+fn hash_file(path: &str) -> Result<String, String> {
+    use std::io::Read;
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
         }
+        context.update(&buffer[..read]);
     }
+    Ok(hex_string(context.finish().as_ref()))
+}
 
-    // Handle remaining words
-    if !current_segment.is_empty() {
-        let first_start = current_segment[0].1;
-        let last_end = current_segment[current_segment.len() - 1].2;
-        let text: String = current_segment.iter()
-            .map(|(w, _, _)| *w)
-            .collect::<Vec<_>>()
-            .join(" ");
+/// Cache key of a job's segment results: the same audio sent to the same
+/// engine, model and language.
+fn cloud_transcription_cache_key(
+    file_hash: &str,
+    provider: &str,
+    model: &str,
+    language: &str,
+) -> String {
+    let key = format!("{}:{}:{}:{}", file_hash, provider, model, language);
+    hex_string(ring::digest::digest(&ring::digest::SHA256, key.as_bytes()).as_ref())
+}
 
-        srt.push_str(&format!("{}\n", segment_index));
-        srt.push_str(&format!("{} --> {}\n", format_srt_time(first_start), format_srt_time(last_end)));
-        srt.push_str(&format!("{}\n\n", text.trim()));
-    }
+fn get_transcription_cache_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
-    Ok(srt)
-}
+    let cache_root = app_data_dir.join("tidemark").join("transcription_cache");
+    fs::create_dir_all(&cache_root)
+        .map_err(|e| format!("Failed to create transcription cache dir: {}", e))?;
 
-fn format_srt_time(seconds: f64) -> String {
-    let total_seconds = seconds as u64;
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let secs = total_seconds % 60;
-    let millis = ((seconds - total_seconds as f64) * 1000.0) as u64;
+    Ok(cache_root)
+}
 
-    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, secs, millis)
+/// Removes cached jobs not touched for `TRANSCRIPTION_CACHE_MAX_AGE_SECS`.
+fn prune_transcription_cache(cache_root: &Path) {
+    let Ok(entries) = fs::read_dir(cache_root) else { return };
+    let max_age = std::time::Duration::from_secs(TRANSCRIPTION_CACHE_MAX_AGE_SECS);
+    for entry in entries.flatten() {
+        let stale = entry.metadata().ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if stale {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
 }
 
-fn merge_transcriptions_with_offset(
-    segments: &[serde_json::Value],
-    segment_durations: &[f64]
+/// Sends one segment, retrying rate limits and server errors with backoff.
+async fn upload_cloud_segment(
+    provider: &str,
+    api_key: &str,
+    segment_path: &str,
+    language: &str,
 ) -> Result<serde_json::Value, String> {
-    let mut merged_segments = Vec::new();
-    let mut time_offset = 0.0;
-
-    for (i, response) in segments.iter().enumerate() {
-        if let Some(segs) = response.get("segments").and_then(|v| v.as_array()) {
-            for seg in segs {
-                let mut new_seg = seg.clone();
-                if let Some(obj) = new_seg.as_object_mut() {
-                    // Adjust timestamps
-                    if let Some(start) = obj.get("start").and_then(|v| v.as_f64()) {
-                        obj.insert("start".to_string(), serde_json::Value::from(start + time_offset));
-                    }
-                    if let Some(end) = obj.get("end").and_then(|v| v.as_f64()) {
-                        obj.insert("end".to_string(), serde_json::Value::from(end + time_offset));
-                    }
-                }
-                merged_segments.push(new_seg);
+    let mut retry = 0;
+    loop {
+        let result = match provider {
+            "openai" => upload_to_openai(api_key, segment_path, language).await,
+            "groq" => upload_to_groq(api_key, segment_path, language).await,
+            "elevenlabs" => upload_to_elevenlabs(api_key, segment_path, language).await,
+            _ => unreachable!()
+        };
+        match result {
+            Ok(response) => return Ok(response),
+            Err(e) if e.retryable && retry < CLOUD_UPLOAD_MAX_RETRIES => {
+                retry += 1;
+                let delay = e.retry_after.unwrap_or_else(|| retry_backoff(retry));
+                log::warn!(
+                    "[CloudASR] {} failed ({}), retry {}/{} in {:?}",
+                    segment_path, e.message, retry, CLOUD_UPLOAD_MAX_RETRIES, delay
+                );
+                tokio::time::sleep(delay).await;
             }
+            Err(e) => return Err(e.message),
         }
+    }
+}
 
-        if i < segment_durations.len() {
-            time_offset += segment_durations[i];
+/// Cuts (if needed), uploads and caches segment `index` of `job`.
+async fn transcribe_cloud_segment(
+    job: &CloudTranscriptionJob,
+    index: usize,
+) -> Result<CachedCloudSegment, String> {
+    let (segment_path, start) = match job.segments {
+        Some(ref segments) => {
+            let (start, duration) = segments[index];
+            let segment_path = job.cache_dir.join(format!("segment_{:03}.mp3", index));
+            extract_cloud_segment(&job.source_file, &segment_path, start, duration).await?;
+            (segment_path.to_string_lossy().to_string(), start)
         }
+        None => (job.source_file.clone(), 0.0),
+    };
+
+    let result = upload_cloud_segment(&job.provider, &job.api_key, &segment_path, &job.language).await;
+    if segment_path != job.source_file {
+        let _ = fs::remove_file(&segment_path);
     }
 
-    Ok(serde_json::json!({
-        "segments": merged_segments,
-        "text": merged_segments.iter()
-            .filter_map(|s| s.get("text").and_then(|t| t.as_str()))
-            .collect::<Vec<_>>()
-            .join(" ")
-    }))
+    let segment = CachedCloudSegment { start, response: result? };
+    match serde_json::to_string(&segment) {
+        Ok(content) => {
+            if let Err(e) = fs::write(job.cache_path(index), content) {
+                log::warn!("[CloudASR] Failed to cache segment {}: {}", index + 1, e);
+            }
+        }
+        Err(e) => log::warn!("[CloudASR] Failed to serialize segment {}: {}", index + 1, e),
+    }
+    Ok(segment)
 }
 
 #[tauri::command]
@@ -5585,143 +11207,156 @@ async fn start_cloud_transcription(config: TranscriptionConfig, app: AppHandle)
         _ => unreachable!()
     };
 
-    // Split file if needed
-    let segment_paths = if config.auto_segment {
-        split_audio_for_cloud(&config.input_file, max_size_mb).await?
-    } else {
-        // Check file size
-        let metadata = fs::metadata(&config.input_file)
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-        let file_size_mb = metadata.len() / (1024 * 1024);
-
-        if file_size_mb > max_size_mb {
-            return Err("errors.asr.file_too_large".to_string());
+    // Segment results are cached per job, so a failed or interrupted job
+    // re-sends only the segments it is missing.
+    let input_file = config.input_file.clone();
+    let file_hash = tokio::task::spawn_blocking(move || hash_file(&input_file))
+        .await
+        .map_err(|e| format!("Failed to hash input file: {}", e))??;
+    let cache_root = get_transcription_cache_root(&app)?;
+    prune_transcription_cache(&cache_root);
+
+    let cache_key = cloud_transcription_cache_key(
+        &file_hash,
+        &provider,
+        cloud_asr_model(&provider),
+        &config.language,
+    );
+    let cache_dir = cache_root.join(cache_key);
+    fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create transcription cache dir: {}", e))?;
+
+    // A file over the size cap is re-encoded to compact mono audio first,
+    // which may be enough on its own; if it still does not fit in one upload
+    // it is split at silences, or refused when auto segmenting is off.
+    let compact_path = cache_dir.join("audio.mp3");
+    let plan_path = cache_dir.join("plan.json");
+    let saved_plan: Option<CloudSegmentPlan> = fs::read_to_string(&plan_path).ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let plan = match saved_plan {
+        Some(plan) => plan,
+        None => {
+            let input_size_mb = fs::metadata(&config.input_file)
+                .map_err(|e| format!("Failed to get file metadata: {}", e))?
+                .len() / (1024 * 1024);
+            let plan = if input_size_mb > max_size_mb {
+                compact_audio_for_cloud(&config.input_file, &compact_path).await?;
+                let compact_size_mb = fs::metadata(&compact_path)
+                    .map_err(|e| format!("Failed to get file metadata: {}", e))?
+                    .len() / (1024 * 1024);
+                if !config.auto_segment && compact_size_mb > max_size_mb {
+                    let _ = fs::remove_file(&compact_path);
+                    return Err("errors.asr.file_too_large".to_string());
+                }
+                CloudSegmentPlan {
+                    compact: true,
+                    segments: plan_cloud_segments(&compact_path.to_string_lossy(), max_size_mb).await?,
+                }
+            } else {
+                CloudSegmentPlan { compact: false, segments: None }
+            };
+            if let Ok(content) = serde_json::to_string(&plan) {
+                let _ = fs::write(&plan_path, content);
+            }
+            plan
         }
+    };
 
-        vec![config.input_file.clone()]
+    let total_segments = plan.segments.as_ref().map_or(1, Vec::len);
+    let job = CloudTranscriptionJob {
+        provider: provider.clone(),
+        api_key,
+        source_file: if plan.compact {
+            compact_path.to_string_lossy().to_string()
+        } else {
+            config.input_file.clone()
+        },
+        language: config.language.clone(),
+        segments: plan.segments,
+        cache_dir,
     };
 
-    let total_segments = segment_paths.len();
-    let mut transcription_results = Vec::new();
-    let mut segment_durations = Vec::new();
+    let mut results: Vec<Option<CachedCloudSegment>> = (0..total_segments)
+        .map(|i| job.cached_segment(i))
+        .collect();
+    let missing: Vec<usize> = (0..total_segments).filter(|&i| results[i].is_none()).collect();
+    if plan.compact && !missing.is_empty() && !compact_path.exists() {
+        compact_audio_for_cloud(&config.input_file, &compact_path).await?;
+    }
+    if missing.len() < total_segments {
+        log::info!(
+            "[CloudASR] Resuming: {} of {} segments cached",
+            total_segments - missing.len(),
+            total_segments
+        );
+    }
 
-    // Process each segment
-    for (i, segment_path) in segment_paths.iter().enumerate() {
-        // Emit progress
+    let emit_progress = |done: usize| {
         let progress = CloudSegmentProgress {
-            current_segment: i + 1,
+            current_segment: done,
             total_segments,
-            percentage: ((i as f64) / (total_segments as f64)) * 100.0,
+            percentage: ((done as f64) / (total_segments as f64)) * 100.0,
         };
         let _ = app.emit("cloud-transcription-progress", &progress);
+    };
+    let mut done = total_segments - missing.len();
+    emit_progress(done);
+
+    // Upload the missing segments; a failure lets the others finish so their
+    // results are cached for the next attempt.
+    let mut uploads = futures_util::stream::iter(missing.into_iter().map(|index| {
+        let job = &job;
+        async move { (index, transcribe_cloud_segment(job, index).await) }
+    }))
+    .buffer_unordered(CLOUD_UPLOAD_CONCURRENCY);
 
-        // Get segment duration for offset calculation
-        let duration_output = Command::new("ffprobe")
-            .args([
-                "-v", "error",
-                "-show_entries", "format=duration",
-                "-of", "default=noprint_wrappers=1:nokey=1",
-                segment_path
-            ])
-            .output()
-            .map_err(|e| format!("Failed to get segment duration: {}", e))?;
-
-        let duration_str = String::from_utf8_lossy(&duration_output.stdout);
-        let duration: f64 = duration_str.trim().parse()
-            .unwrap_or(0.0);
-        segment_durations.push(duration);
-
-        // Upload to API
-        let result = match provider.as_str() {
-            "openai" => upload_to_openai(&api_key, segment_path, &config.language).await,
-            "groq" => upload_to_groq(&api_key, segment_path, &config.language).await,
-            "elevenlabs" => upload_to_elevenlabs(&api_key, segment_path, &config.language).await,
-            _ => unreachable!()
-        };
-
+    let mut failure: Option<(usize, String)> = None;
+    while let Some((index, result)) = uploads.next().await {
         match result {
-            Ok(response) => {
-                transcription_results.push(response);
+            Ok(segment) => {
+                results[index] = Some(segment);
+                done += 1;
+                emit_progress(done);
             }
             Err(e) => {
-                // Clean up temporary segments
-                if total_segments > 1 {
-                    for path in &segment_paths {
-                        if path != &config.input_file {
-                            let _ = fs::remove_file(path);
-                        }
-                    }
+                log::warn!("[CloudASR] Segment {} failed: {}", index + 1, e);
+                if failure.as_ref().map_or(true, |(first, _)| index < *first) {
+                    failure = Some((index, e));
                 }
-
-                // Emit error event
-                let error_payload = serde_json::json!({
-                    "message": format!("Segment {} failed: {}", i + 1, e)
-                });
-                let _ = app.emit("transcription-error", &error_payload);
-
-                return Err(format!("Segment {} transcription failed: {}", i + 1, e));
             }
         }
     }
+    drop(uploads);
 
-    // Merge results if multiple segments
-    let final_result = if transcription_results.len() > 1 {
-        merge_transcriptions_with_offset(&transcription_results, &segment_durations)?
-    } else {
-        transcription_results.into_iter().next()
-            .ok_or("No transcription result")?
-    };
-
-    // Generate output files
-    let input_pathbuf = PathBuf::from(&config.input_file);
-    let parent_dir = input_pathbuf.parent()
-        .ok_or("Invalid input path")?;
-    let file_stem = input_pathbuf.file_stem()
-        .ok_or("Invalid file name")?
-        .to_string_lossy();
-
-    let mut output_paths = Vec::new();
-
-    // Generate SRT
-    if config.output_format == "srt" || config.output_format == "both" {
-        let srt_content = match provider.as_str() {
-            "openai" | "groq" => generate_srt_from_openai(&final_result)?,
-            "elevenlabs" => generate_srt_from_elevenlabs(&final_result)?,
-            _ => unreachable!()
-        };
+    if let Some((i, e)) = failure {
+        // Emit error event
+        let error_payload = serde_json::json!({
+            "message": format!("Segment {} failed: {}", i + 1, e)
+        });
+        let _ = app.emit("transcription-error", &error_payload);
 
-        let srt_path = parent_dir.join(format!("{}.srt", file_stem));
-        fs::write(&srt_path, srt_content)
-            .map_err(|e| format!("Failed to write SRT file: {}", e))?;
-        output_paths.push(srt_path.to_string_lossy().to_string());
+        return Err(format!("Segment {} transcription failed: {}", i + 1, e));
     }
 
-    // Generate TXT
-    if config.output_format == "txt" || config.output_format == "both" {
-        let text = final_result.get("text")
-            .and_then(|v| v.as_str())
-            .ok_or("No text in response")?;
+    // Every segment is cached now; the re-encoded audio is no longer needed.
+    let _ = fs::remove_file(&compact_path);
 
-        let txt_path = parent_dir.join(format!("{}.txt", file_stem));
-        fs::write(&txt_path, text)
-            .map_err(|e| format!("Failed to write TXT file: {}", e))?;
-        output_paths.push(txt_path.to_string_lossy().to_string());
-    }
+    let (transcription_results, segment_offsets): (Vec<serde_json::Value>, Vec<f64>) = results
+        .into_iter()
+        .flatten()
+        .map(|segment| (segment.response, segment.start))
+        .unzip();
 
-    // Clean up temporary segments
-    if segment_paths.len() > 1 {
-        for path in &segment_paths {
-            if path != &config.input_file {
-                let _ = fs::remove_file(path);
-            }
-        }
-    }
+    // Merge results if multiple segments, cutting each at the next one's start
+    let final_result = if transcription_results.len() > 1 {
+        merge_transcriptions_with_offset(&transcription_results, &segment_offsets)?
+    } else {
+        transcription_results.into_iter().next()
+            .ok_or("No transcription result")?
+    };
 
-    // Emit completion event
-    let complete_payload = serde_json::json!({
-        "output_path": output_paths.join(", ")
-    });
-    let _ = app.emit("transcription-complete", &complete_payload);
+    let transcript = transcript_from_cloud_response(&provider, &final_result)?;
+    finish_transcription(&app, &transcript, &config)?;
 
     Ok(())
 }
@@ -5731,6 +11366,10 @@ async fn start_transcription(
     config: TranscriptionConfig,
     app: AppHandle,
 ) -> Result<(), String> {
+    // Reject an unknown output format or conversion before any work is done
+    transcript_output_formats(&config.output_format)?;
+    chinese_conversion(&config)?;
+
     // Route to cloud transcription if using cloud engine
     if config.engine == "openai" || config.engine == "groq" || config.engine == "elevenlabs" {
         return start_cloud_transcription(config, app).await;
@@ -5769,24 +11408,9 @@ async fn start_transcription(
         PathBuf::from("python3")
     };
 
-    // Prepare configuration with output directory
-    let mut exec_config = serde_json::to_value(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-    // Add output directory based on settings or input file location
-    if let Some(obj) = exec_config.as_object_mut() {
-        if !obj.contains_key("output_dir") {
-            let input_path = PathBuf::from(&config.input_file);
-            if let Some(parent) = input_path.parent() {
-                obj.insert(
-                    "output_dir".to_string(),
-                    serde_json::Value::String(parent.to_string_lossy().to_string())
-                );
-            }
-        }
-    }
-
-    let config_json = serde_json::to_string(&exec_config)
+    // The script reports a transcript; output files are written here, the
+    // same way as for cloud engines.
+    let config_json = serde_json::to_string(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
     // Start Python process
@@ -5821,8 +11445,16 @@ async fn start_transcription(
                     let _ = app.emit("transcription-progress", &json);
                 }
                 "complete" => {
-                    // Emit completion event
-                    let _ = app.emit("transcription-complete", &json);
+                    let written = json.get("transcript")
+                        .cloned()
+                        .ok_or_else(|| "No transcript in output".to_string())
+                        .and_then(|value| serde_json::from_value::<Transcript>(value)
+                            .map_err(|e| format!("Failed to parse transcript: {}", e)))
+                        .and_then(|transcript| finish_transcription(&app, &transcript, &config));
+                    if let Err(message) = written {
+                        let _ = app.emit("transcription-error", &serde_json::json!({ "message": message }));
+                        return Err(message);
+                    }
                 }
                 "error" => {
                     let message = json.get("message")
@@ -6242,20 +11874,41 @@ fn get_sync_state(app: AppHandle) -> Result<SyncState, String> {
     let content = fs::read_to_string(&sync_state_path)
         .map_err(|e| format!("Failed to read sync state file: {}", e))?;
 
-    let state: SyncState = serde_json::from_str(&content)
+    let mut state: SyncState = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse sync state file: {}", e))?;
 
+    // The JWT lives in the secret store; a locked store reads as signed out.
+    match load_secrets(&app) {
+        Ok(secrets) => state.jwt = secrets.sync_jwt.or(state.jwt),
+        Err(e) => {
+            log::warn!("[Secrets] Sync token unavailable: {}", e);
+            warn_if_secret_store_locked(&app, &e);
+        }
+    }
+
     Ok(state)
 }
 
 #[tauri::command]
 fn save_sync_state(app: AppHandle, state: SyncState) -> Result<(), String> {
-    let sync_state_path = get_sync_state_path(&app)?;
+    let mut secrets = load_secrets(&app)?;
+    secrets.sync_jwt = state.jwt.clone();
+    save_secrets(&app, &secrets)?;
+
+    write_sync_state_file(&app, &state)
+}
+
+/// Writes `sync_state.json` without the JWT, which goes to the secret store.
+fn write_sync_state_file(app: &AppHandle, state: &SyncState) -> Result<(), String> {
+    write_public_sync_state(&get_sync_state_path(app)?, state)
+}
 
-    let content = serde_json::to_string_pretty(&state)
+fn write_public_sync_state(path: &Path, state: &SyncState) -> Result<(), String> {
+    let public = SyncState { jwt: None, ..state.clone() };
+    let content = serde_json::to_string_pretty(&public)
         .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
 
-    fs::write(&sync_state_path, content)
+    fs::write(path, content)
         .map_err(|e| format!("Failed to write sync state file: {}", e))?;
 
     Ok(())
@@ -6713,13 +12366,15 @@ async fn check_for_updates() -> Result<UpdateStatus, String> {
 }
 
 #[tauri::command]
-fn get_available_hardware_encoders() -> Result<Vec<String>, String> {
+async fn get_available_hardware_encoders() -> Result<Vec<String>, String> {
     // Check for available hardware encoders via ffmpeg
     let mut encoders = vec!["auto".to_string()];
 
-    if let Ok(output) = Command::new("ffmpeg")
+    if let Ok(output) = TokioCommand::new("ffmpeg")
         .arg("-encoders")
+        .kill_on_drop(true)
         .output()
+        .await
     {
         if output.status.success() {
             if let Ok(encoders_output) = String::from_utf8(output.stdout) {
@@ -6916,29 +12571,7 @@ async fn fetch_twitch_channel_metadata(
     now: String,
 ) -> Result<ChannelMetadata, String> {
     // Load auth config to get Twitch token if available
-    let auth_config: AuthConfig = {
-        let auth_path = get_auth_config_path(&app).unwrap_or_default();
-        if auth_path.exists() {
-            fs::read_to_string(&auth_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or(AuthConfig {
-                    twitch_token: None,
-                    youtube_cookies_path: None,
-                    openai_api_key: None,
-                    groq_api_key: None,
-                    elevenlabs_api_key: None,
-                })
-        } else {
-            AuthConfig {
-                twitch_token: None,
-                youtube_cookies_path: None,
-                openai_api_key: None,
-                groq_api_key: None,
-                elevenlabs_api_key: None,
-            }
-        }
-    };
+    let auth_config = read_auth_config(&app).unwrap_or_default();
 
     // Use Twitch GQL to query user profile info
     // Public client ID from Twitch web app
@@ -7486,7 +13119,7 @@ fn set_monitoring_paused(paused: bool) {
 /// All recognized template variables.
 const KNOWN_VARIABLES: &[&str] = &[
     "title", "id", "channel", "channel_name", "platform",
-    "type", "date", "datetime", "resolution", "duration",
+    "type", "date", "datetime", "resolution", "duration", "part",
 ];
 
 /// Variables expanded in Phase 1 (immediate, known at trigger time).
@@ -7780,7 +13413,7 @@ fn update_pause_menu_item(tray_state: &TrayState) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let download_tasks: DownloadTasks = Arc::new(Mutex::new(HashMap::new()));
+    let download_tasks: DownloadTasks = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
@@ -7905,6 +13538,21 @@ pub fn run() {
                 });
             }
 
+            // Restore unfinished downloads from the last session, then keep
+            // the on-disk snapshot up to date.
+            clear_twitch_cookies(app.handle());
+            {
+                let persist_app = app.handle().clone();
+                let persist_tasks = app.state::<DownloadTasks>().inner().clone();
+                tokio::spawn(async move {
+                    restore_download_tasks(persist_app.clone(), persist_tasks.clone()).await;
+                    run_task_persistence(persist_app, persist_tasks).await;
+                });
+            }
+
+            // Warn ahead of time about expiring cookies of scheduled presets.
+            tokio::spawn(run_cookie_expiry_watch(app.handle().clone()));
+
             // Auto-start Twitch PubSub and YouTube RSS monitoring if configured.
             {
                 let auto_app = app.handle().clone();
@@ -7928,6 +13576,11 @@ pub fn run() {
             load_config,
             save_config,
             fetch_video_info,
+            expand_playlist,
+            enqueue_playlist_entries,
+            download_record_clip,
+            download_folder_clips,
+            export_folder_highlights,
             start_download,
             start_recording,
             pause_download,
@@ -7937,15 +13590,25 @@ pub fn run() {
             open_file,
             show_in_folder,
             get_download_tasks,
+            get_task_log,
+            set_download_priority,
+            reorder_download_queue,
             get_download_history,
             delete_history_entry,
             clear_all_history,
             check_file_exists,
             validate_twitch_token,
             import_youtube_cookies,
+            get_cookie_profiles,
+            save_cookie_profile,
+            delete_cookie_profile,
+            check_cookie_profiles,
             save_auth_config,
             save_api_keys,
             get_auth_config,
+            get_secret_store_status,
+            unlock_secret_store,
+            set_secret_passphrase,
             test_api_key,
             save_api_key,
             get_api_key,
@@ -8025,3 +13688,1160 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcode_settings(codec: &str, hardware_encoding: bool, hardware_encoder: &str) -> TranscodeSettings {
+        TranscodeSettings {
+            codec: codec.to_string(),
+            crf: 23,
+            bitrate_kbps: 0,
+            resolution: "original".to_string(),
+            hardware_encoding,
+            hardware_encoder: hardware_encoder.to_string(),
+        }
+    }
+
+    #[test]
+    fn transcode_candidates_fall_back_to_software() {
+        let available = vec!["auto".to_string(), "h264_qsv".to_string(), "h264_nvenc".to_string()];
+
+        let cases = [
+            (transcode_settings("h264", false, "auto"), vec!["libx264"]),
+            (transcode_settings("hevc", false, "h264_nvenc"), vec!["libx265"]),
+            (transcode_settings("h264", true, "auto"), vec!["h264_nvenc", "libx264"]),
+            (transcode_settings("hevc", true, "auto"), vec!["libx265"]),
+            (transcode_settings("h264", true, "hevc_amf"), vec!["hevc_amf", "libx265"]),
+        ];
+
+        for (settings, expected) in cases {
+            assert_eq!(transcode_encoder_candidates(&settings, &available), expected, "{:?}", settings);
+        }
+    }
+
+    #[test]
+    fn parse_url_recognises_supported_shapes() {
+        use ContentType::*;
+        use Platform::*;
+
+        let cases: &[(&str, Platform, ContentType, &str, Option<i64>)] = &[
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ", YouTube, Video, "dQw4w9WgXcQ", None),
+            ("https://www.youtube.com/watch?feature=share&v=dQw4w9WgXcQ", YouTube, Video, "dQw4w9WgXcQ", None),
+            ("https://m.youtube.com/watch?v=dQw4w9WgXcQ&t=90s", YouTube, Video, "dQw4w9WgXcQ", Some(90)),
+            ("https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVM", YouTube, Video, "dQw4w9WgXcQ", None),
+            ("https://youtu.be/dQw4w9WgXcQ?t=1m5s", YouTube, Video, "dQw4w9WgXcQ", Some(65)),
+            ("https://www.youtube.com/shorts/dQw4w9WgXcQ", YouTube, Video, "dQw4w9WgXcQ", None),
+            ("https://www.youtube.com/embed/dQw4w9WgXcQ?start=30", YouTube, Video, "dQw4w9WgXcQ", Some(30)),
+            ("https://www.youtube.com/live/dQw4w9WgXcQ", YouTube, Stream, "dQw4w9WgXcQ", None),
+            ("https://www.youtube.com/@SomeHandle/live", YouTube, Stream, "@SomeHandle", None),
+            ("https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv/live", YouTube, Stream, "channel/UCabcdefghijklmnopqrstuv", None),
+            ("https://www.youtube.com/playlist?list=PLabc123_-", YouTube, Playlist, "PLabc123_-", None),
+            ("https://www.twitch.tv/videos/123456789", Twitch, Video, "123456789", None),
+            ("https://www.twitch.tv/videos/123456789?t=1h2m3s", Twitch, Video, "123456789", Some(3723)),
+            ("https://m.twitch.tv/videos/123456789", Twitch, Video, "123456789", None),
+            ("https://www.twitch.tv/somechannel/clip/FunnyClipSlug-abc123", Twitch, Clip, "FunnyClipSlug-abc123", None),
+            ("https://clips.twitch.tv/FunnyClipSlug", Twitch, Clip, "FunnyClipSlug", None),
+            ("https://www.twitch.tv/somechannel", Twitch, Stream, "somechannel", None),
+            ("https://www.twitch.tv/somechannel/videos?filter=archives", Twitch, Channel, "somechannel", None),
+            ("https://www.youtube.com/@SomeHandle", YouTube, Channel, "@SomeHandle", None),
+            ("https://www.youtube.com/@SomeHandle/streams", YouTube, Channel, "@SomeHandle", None),
+            ("https://vimeo.com/76979871", Other, Video, "https://vimeo.com/76979871", None),
+        ];
+
+        for (url, platform, content_type, id, start_time) in cases {
+            let parsed = parse_url(url).unwrap_or_else(|e| panic!("{} failed: {}", url, e));
+            assert_eq!(&parsed.platform, platform, "{}", url);
+            assert_eq!(&parsed.content_type, content_type, "{}", url);
+            assert_eq!(parsed.id, *id, "{}", url);
+            assert_eq!(parsed.start_time, *start_time, "{}", url);
+        }
+    }
+
+    #[test]
+    fn parse_url_rejects_non_urls() {
+        for input in ["", "not a url", "ftp://example.com/file"] {
+            assert!(parse_url(input).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn encoder_quality_args_per_encoder() {
+        assert_eq!(encoder_quality_args("libx264", 23, 0), ["-crf", "23", "-preset", "medium"]);
+        assert_eq!(encoder_quality_args("hevc_nvenc", 28, 0), ["-rc", "vbr", "-cq", "28", "-b:v", "0"]);
+        assert_eq!(encoder_quality_args("h264_videotoolbox", 23, 0), ["-q:v", "54"]);
+        assert_eq!(encoder_quality_args("h264_qsv", 23, 4000), ["-b:v", "4000k"]);
+    }
+
+    #[test]
+    fn videotoolbox_quality_stays_in_range_at_crf_extremes() {
+        assert_eq!(encoder_quality_args("h264_videotoolbox", 0, 0), ["-q:v", "100"]);
+        assert_eq!(encoder_quality_args("h264_videotoolbox", 51, 0), ["-q:v", "1"]);
+        assert_eq!(encoder_quality_args("h264_videotoolbox", 80, 0), ["-q:v", "1"]);
+    }
+
+    #[test]
+    fn select_archive_prefers_broadcast_covering_record() {
+        let entries = vec![
+            serde_json::json!({"url": "https://www.twitch.tv/videos/3", "timestamp": 3_000, "duration": 600.0}),
+            serde_json::json!({"url": "https://www.twitch.tv/videos/2", "timestamp": 2_000, "duration": 900.0}),
+            serde_json::json!({"url": "https://www.twitch.tv/videos/1", "timestamp": 1_000, "duration": 100.0}),
+            serde_json::json!({"url": "https://www.twitch.tv/videos/0"}),
+        ];
+        let at = |ts: i64| DateTime::from_timestamp(ts, 0).unwrap();
+
+        assert_eq!(select_archive_for_record(&entries, at(2_500)).as_deref(), Some("https://www.twitch.tv/videos/2"));
+        // Past the end of every broadcast: fall back to the latest that started before.
+        assert_eq!(select_archive_for_record(&entries, at(5_000)).as_deref(), Some("https://www.twitch.tv/videos/3"));
+        assert_eq!(select_archive_for_record(&entries, at(500)), None);
+    }
+
+    #[test]
+    fn record_clip_range_clamps_to_vod() {
+        let range = record_clip_range("1:00:05", 10, 20, Some(7_200)).unwrap();
+        assert_eq!(range.start.as_deref(), Some("00:59:55"));
+        assert_eq!(range.end.as_deref(), Some("01:00:25"));
+
+        let range = record_clip_range("0:05", 10, 20, Some(15)).unwrap();
+        assert_eq!(range.start.as_deref(), Some("00:00:00"));
+        assert_eq!(range.end.as_deref(), Some("00:00:15"));
+
+        assert!(record_clip_range("2:00:00", 10, 10, Some(60)).is_err());
+        assert!(record_clip_range("soon", 10, 10, None).is_err());
+    }
+
+    #[test]
+    fn highlight_chapters_and_cue_sheet() {
+        let chapters = highlight_chapters(&[("Opening".to_string(), 20.5), ("Boss \"fight\"".to_string(), 75.0)]);
+        assert_eq!(chapters[0], HighlightChapter { title: "Opening".to_string(), start_ms: 0, end_ms: 20_500 });
+        assert_eq!((chapters[1].start_ms, chapters[1].end_ms), (20_500, 95_500));
+
+        let metadata = ffmetadata_chapters("Stream; day 1", &chapters);
+        assert!(metadata.starts_with(";FFMETADATA1\ntitle=Stream\\; day 1\n"));
+        assert!(metadata.contains("START=20500\nEND=95500\ntitle=Boss \"fight\"\n"));
+
+        let cue = cue_sheet("Day 1", "Day 1.mp4", &chapters);
+        assert!(cue.contains("FILE \"Day 1.mp4\" MP4\n"));
+        assert!(cue.contains("  TRACK 02 VIDEO\n    TITLE \"Boss 'fight'\"\n    INDEX 01 00:20:37\n"));
+    }
+
+    #[test]
+    fn precise_cut_padding_and_smart_plan() {
+        assert_eq!(precise_cut_padding(5, 30, None), (0, 40));
+        assert_eq!(precise_cut_padding(100, 130, Some(135)), (90, 135));
+
+        let keyframes = [0.0, 2.0, 4.0, 6.0, 8.0];
+        assert_eq!(smart_cut_plan(&keyframes, 1.5, 7.2), Some((2.0, 6.0)));
+        // A cut starting on a keyframe needs no re-encoded head.
+        assert_eq!(smart_cut_plan(&keyframes, 4.0, 8.0), Some((4.0, 8.0)));
+        assert_eq!(smart_cut_plan(&keyframes, 2.5, 5.5), None);
+    }
+
+    #[test]
+    fn recording_segment_names_and_limits() {
+        assert_eq!(segment_pattern("{channel}_{part}.mp4"), "{channel}_%03d.ts");
+        assert_eq!(segment_pattern("100% live.mkv"), "100%% live_part%03d.ts");
+        assert_eq!(segment_path("/rec/100%% live_part%03d.ts", 7), "/rec/100% live_part007.ts");
+        assert_eq!(strip_part_variable("stream_{part}.mp4"), "stream.mp4");
+
+        let limits = RecordingLimits { split_every_minutes: 30, split_every_gb: 1.0, ..Default::default() };
+        assert!(!limits.split_due(1799, 999_999_999));
+        assert!(limits.split_due(1800, 0));
+        assert!(limits.split_due(10, 1_000_000_000));
+        assert!(!RecordingLimits::default().split_due(u64::MAX, u64::MAX));
+
+        let caps = RecordingLimits { max_duration_minutes: 60, max_size_gb: 2.0, ..Default::default() };
+        assert!(caps.is_active() && !caps.splits());
+        assert!(!caps.cap_reached(3599, 1_000_000_000));
+        assert!(caps.cap_reached(3600, 0));
+        assert!(caps.cap_reached(10, 2_000_000_000));
+    }
+
+    #[test]
+    fn reconnect_parts_keep_first_name() {
+        let output = Path::new("/rec/stream.mp4");
+        assert_eq!(recording_part_path(output, 1), PathBuf::from("/rec/stream.mp4"));
+        assert_eq!(recording_part_path(output, 3), PathBuf::from("/rec/stream_reconnect3.mp4"));
+        assert_eq!(recording_part_path(Path::new("/rec/stream"), 2), PathBuf::from("/rec/stream_reconnect2"));
+    }
+
+    #[test]
+    fn ytdlp_failures_map_to_error_keys() {
+        let members = "ERROR: [youtube] abc: Join this channel to get access to members-only content like this video";
+        assert_eq!(ytdlp_failure_key(members), Some("members_only"));
+        assert_eq!(ytdlp_failure_key("ERROR: Sign in to confirm your age. This video may be inappropriate"), Some("age_restricted"));
+        assert_eq!(ytdlp_failure_key("ERROR: The uploader has not made this video available in your country"), Some("geo_blocked"));
+        assert_eq!(ytdlp_failure_key("WARNING: The provided YouTube account cookies are no longer valid"), Some("cookies_expired"));
+        assert_eq!(ytdlp_failure_key("ERROR: Sign in to confirm you\u{2019}re not a bot"), Some("cookies_expired"));
+        assert_eq!(ytdlp_failure_key("ERROR: [twitch] 123: Requested format is not available"), Some("format_unavailable"));
+        assert_eq!(ytdlp_failure_key("ERROR: You have requested merging of multiple formats but ffmpeg is not installed"), Some("ffmpeg_missing"));
+        assert_eq!(ytdlp_failure_key("ERROR: HTTP Error 503: Service Unavailable"), None);
+        assert_eq!(
+            ytdlp_failure_key("ERROR: [twitch:vod] v123: Unable to download m3u8 information: HTTP Error 403: Forbidden"),
+            Some("twitch_auth_failed")
+        );
+        assert_eq!(ytdlp_failure_key("ERROR: [twitch:stream] someone: someone is offline"), None);
+        assert_eq!(ytdlp_failure_key("ERROR: [youtube] abc: HTTP Error 403: Forbidden"), None);
+
+        assert_eq!(ytdlp_error_message(members, "fallback"), r#"{"key":"errors.download.members_only"}"#);
+        assert_eq!(ytdlp_error_message("ERROR: unknown", "fallback"), "fallback");
+        assert_eq!(classify_ytdlp_failure(members), FailureKind::Permanent);
+    }
+
+    #[test]
+    fn retries_only_transient_failures_with_capped_backoff() {
+        use FailureKind::*;
+
+        let cases = [
+            ("ERROR: unable to download video data: HTTP Error 503: Service Unavailable", Transient),
+            ("ERROR: [download] Got error: HTTP Error 500: Internal Server Error", Transient),
+            ("ERROR: ('Connection aborted.', ConnectionResetError(104, 'Connection reset by peer'))", Transient),
+            ("[download] Got error: 3 bytes read. Retrying fragment 12 (attempt 1 of 10)\nERROR: fragment 12 not found, unable to continue", Transient),
+            ("ERROR: Read timed out.", Transient),
+            ("ERROR: [youtube] abc: Private video. Sign in if you've been granted access to this video", Permanent),
+            ("ERROR: [youtube] abc: The uploader has not made this video available in your country", Permanent),
+            ("ERROR: unable to download webpage: HTTP Error 404: Not Found", Permanent),
+            ("ERROR: [youtube] abc: Video unavailable. This video has been removed by the uploader", Permanent),
+            ("ERROR: something nobody has seen before", Permanent),
+        ];
+        for (stderr, expected) in cases {
+            assert_eq!(classify_ytdlp_failure(stderr), expected, "{}", stderr);
+        }
+
+        let secs: Vec<u64> = (1..=8).map(|retry| retry_backoff(retry).as_secs()).collect();
+        assert_eq!(secs, vec![5, 10, 20, 40, 80, 160, 300, 300]);
+        assert_eq!(retry_backoff(0).as_secs(), 5);
+        assert_eq!(retry_backoff(u32::MAX).as_secs(), 300);
+    }
+
+    #[test]
+    fn twitch_token_is_sent_as_scoped_cookie() {
+        assert_eq!(twitch_oauth_token("OAuth abc123"), Some("abc123"));
+        assert_eq!(twitch_oauth_token(" oauth:abc123 "), Some("abc123"));
+        assert_eq!(twitch_oauth_token("abc123"), Some("abc123"));
+        assert_eq!(twitch_oauth_token("  "), None);
+
+        let auth = AuthConfig {
+            twitch_token: Some("OAuth abc123".to_string()),
+            youtube_cookies_path: Some("/tmp/cookies.txt".to_string()),
+            openai_api_key: None,
+            groq_api_key: None,
+            elevenlabs_api_key: None,
+        };
+        let dir = std::env::temp_dir().join(format!("tidemark-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let cookies_path = dir.join("twitch_cookies.txt");
+
+        let args = ytdlp_auth_args(Some(&auth), "twitch", &cookies_path);
+        assert_eq!(args, vec!["--cookies".to_string(), cookies_path.to_string_lossy().into_owned()]);
+        let content = fs::read_to_string(&cookies_path).unwrap();
+        assert!(is_netscape_cookie_file(&content));
+        assert_eq!(netscape_cookies(&content).collect::<Vec<_>>(), vec![(".twitch.tv", "auth-token", 0)]);
+        assert!(content.ends_with("\tauth-token\tabc123\n"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&cookies_path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        assert_eq!(ytdlp_auth_args(Some(&auth), "youtube", &cookies_path), vec!["--cookies", "/tmp/cookies.txt"]);
+        assert!(ytdlp_auth_args(None, "twitch", &cookies_path).is_empty());
+
+        // The run's file goes once yt-dlp is done with it.
+        drop(TwitchCookiesFile(cookies_path.clone()));
+        assert!(!cookies_path.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn cookie_files_report_soonest_expiring_session_cookie() {
+        let now = 1_700_000_000;
+        let day = 24 * 60 * 60;
+        let content = format!(
+            "# Netscape HTTP Cookie File\n\
+             .youtube.com\tTRUE\t/\tTRUE\t{}\tPREF\tf6=40000000\n\
+             .youtube.com\tTRUE\t/\tTRUE\t{}\tSAPISID\tabc\n\
+             #HttpOnly_.youtube.com\tTRUE\t/\tTRUE\t{}\t__Secure-3PSID\tdef\n\
+             .google.com\tTRUE\t/\tFALSE\t0\tSID\tghi\n",
+            now + day,
+            now + 30 * day,
+            now + 2 * day,
+        );
+        assert!(is_netscape_cookie_file(&content));
+
+        let (status, cookies) = cookie_file_status(&content, "youtube", now);
+        assert_eq!(status, "expiring");
+        let names: Vec<&str> = cookies.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["__Secure-3PSID", "SAPISID", "SID"]);
+        assert_eq!(cookies[2].expires_at, None);
+
+        assert_eq!(cookie_file_status(&content, "youtube", now - 10 * day).0, "valid");
+        assert_eq!(cookie_file_status(&content, "youtube", now + 3 * day).0, "expired");
+        assert_eq!(cookie_file_status(&content, "twitch", now).0, "no_auth_cookies");
+        assert!(!is_netscape_cookie_file("not a cookie file"));
+
+        // Only the session cookies decide the status
+        let twitch = format!(
+            ".twitch.tv\tTRUE\t/\tTRUE\t{}\tlogin\tviewer\n.twitch.tv\tTRUE\t/\tTRUE\t{}\tauth-token\tabc\n",
+            now - day,
+            now + 30 * day,
+        );
+        let (status, cookies) = cookie_file_status(&twitch, "twitch", now);
+        assert_eq!(status, "valid");
+        assert_eq!(cookies[0].name, "auth-token");
+        let without_session = format!(".youtube.com\tTRUE\t/\tTRUE\t{}\tSAPISID\tabc\n", now + 30 * day);
+        assert_eq!(cookie_file_status(&without_session, "youtube", now).0, "no_auth_cookies");
+
+        let profile = CookieProfile {
+            id: "p1".to_string(),
+            name: "Main".to_string(),
+            platform: "youtube".to_string(),
+            source: "browser".to_string(),
+            cookies_path: None,
+            browser: Some("firefox:default-release".to_string()),
+            created_at: String::new(),
+        };
+        assert_eq!(cookie_profile_args(&profile), vec!["--cookies-from-browser", "firefox:default-release"]);
+        assert_eq!(check_cookie_profile(&profile).status, "unchecked");
+
+        let profiles = || vec![profile.clone()];
+        assert_eq!(download_cookie_profile(profiles(), "p1", "youtube").map(|p| p.id), Some("p1".to_string()));
+        // A YouTube profile on a Twitch download falls back to the auth settings
+        assert!(download_cookie_profile(profiles(), "p1", "twitch").is_none());
+        assert!(download_cookie_profile(profiles(), "missing", "youtube").is_none());
+    }
+
+    #[test]
+    fn secrets_round_trip_and_reject_wrong_key() {
+        let secrets = Secrets {
+            twitch_token: Some("abc123".to_string()),
+            sync_jwt: Some("header.payload.sig".to_string()),
+            ..Secrets::default()
+        };
+        let key = derive_secret_key("correct horse", b"0123456789abcdef", 1000);
+        assert_eq!(key, derive_secret_key("correct horse", b"0123456789abcdef", 1000));
+
+        let (nonce, ciphertext) = seal_secrets(&key, &secrets).unwrap();
+        assert!(!ciphertext.contains("abc123"));
+        assert_eq!(open_secrets(&key, &nonce, &ciphertext).unwrap(), secrets);
+
+        let wrong = derive_secret_key("battery staple", b"0123456789abcdef", 1000);
+        assert_eq!(open_secrets(&wrong, &nonce, &ciphertext).unwrap_err(), "errors.secrets.decrypt_failed");
+
+        // A fresh nonce per seal: the same secrets never encrypt the same way twice.
+        let (nonce2, ciphertext2) = seal_secrets(&key, &secrets).unwrap();
+        assert_ne!((nonce, ciphertext), (nonce2, ciphertext2));
+    }
+
+    #[test]
+    fn cloud_uploads_retry_rate_limits_and_server_errors() {
+        assert!(is_retryable_upload_status(429, r#"{"error":{"type":"rate_limit_exceeded"}}"#));
+        assert!(!is_retryable_upload_status(429, r#"{"error":{"code":"insufficient_quota"}}"#));
+        assert!(is_retryable_upload_status(503, ""));
+        assert!(!is_retryable_upload_status(400, ""));
+        assert!(!is_retryable_upload_status(401, ""));
+
+        let key = cloud_transcription_cache_key("abc", "groq", "whisper-large-v3", "ja");
+        assert_eq!(key, cloud_transcription_cache_key("abc", "groq", "whisper-large-v3", "ja"));
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, cloud_transcription_cache_key("abc", "openai", "whisper-1", "ja"));
+        assert_ne!(key, cloud_transcription_cache_key("abd", "groq", "whisper-large-v3", "ja"));
+    }
+
+    #[test]
+    fn cloud_splits_land_on_silences_and_merge_drops_overlap() {
+        let stderr = "\
+[silencedetect @ 0x1] silence_start: 95.2
+[silencedetect @ 0x1] silence_end: 96.0 | silence_duration: 0.8
+[silencedetect @ 0x1] silence_start: -0.01
+[silencedetect @ 0x1] silence_end: 0.5 | silence_duration: 0.51
+[silencedetect @ 0x1] silence_start: 180.0
+[silencedetect @ 0x1] silence_end: 181.0 | silence_duration: 1";
+        let silences = parse_silences(stderr);
+        assert_eq!(silences, vec![(95.2, 96.0), (0.0, 0.5), (180.0, 181.0)]);
+
+        // 100 s pieces cut in the middle of the latest silence in reach;
+        // without silences, hard cuts at the cap less the overlap.
+        let cuts = choose_split_points(250.0, 100.0, &silences);
+        assert_eq!(cuts.len(), 2);
+        assert!((cuts[0] - 95.6).abs() < 1e-9);
+        assert!((cuts[1] - 180.5).abs() < 1e-9);
+        assert_eq!(choose_split_points(250.0, 100.0, &[]), vec![98.0, 196.0]);
+        let windows = segment_windows(250.0, &cuts);
+        assert_eq!(windows.len(), 3);
+        assert!(windows.iter().all(|(_, duration)| *duration <= 100.0));
+        assert!((windows[1].0 - cuts[0]).abs() < 1e-9);
+        assert!((windows[2].0 + windows[2].1 - 250.0).abs() < 1e-9);
+        assert!(choose_split_points(90.0, 100.0, &silences).is_empty());
+
+        // The words after 100 s are in both segments' overlap; they are kept
+        // once, from the segment that starts at 100 s.
+        let first = serde_json::json!({"segments": [
+            {"start": 0.0, "end": 4.0, "text": "hello"},
+            {"start": 100.5, "end": 100.9, "text": "there"},
+            {"start": 101.5, "end": 101.9, "text": "cut"},
+        ]});
+        let second = serde_json::json!({"segments": [
+            {"start": 0.5, "end": 0.9, "text": "there"},
+            {"start": 1.5, "end": 1.9, "text": "cut"},
+            {"start": 3.0, "end": 5.0, "text": "world"},
+        ]});
+        let merged = merge_transcriptions_with_offset(&[first, second], &[0.0, 100.0]).unwrap();
+        assert_eq!(merged["text"], "hello there cut world");
+        assert_eq!(merged["segments"][3]["start"], 103.0);
+        assert!(merged.get("words").is_none());
+    }
+
+    #[test]
+    fn cloud_merge_takes_the_later_piece_from_the_cut() {
+        // A sentence from 96 s runs past the cut at 100 s, to the end of the
+        // first piece; the second piece hears its tail and a long sentence
+        // that starts at the cut and runs past the overlap.
+        let first = serde_json::json!({
+            "segments": [
+                {"start": 0.0, "end": 4.0, "text": "hello"},
+                {"start": 96.0, "end": 102.0, "text": "one two three four"},
+            ],
+            "words": [
+                {"word": "hello", "start": 0.0, "end": 4.0},
+                {"word": "one", "start": 96.0, "end": 97.0},
+                {"word": "two", "start": 98.0, "end": 99.0},
+                {"word": "three", "start": 99.5, "end": 100.4},
+                {"word": "four", "start": 101.0, "end": 102.0},
+            ],
+        });
+        let second = serde_json::json!({
+            "segments": [
+                {"start": 0.1, "end": 9.0, "text": "three four five six"},
+            ],
+            "words": [
+                {"word": "three", "start": 0.1, "end": 0.4},
+                {"word": "four", "start": 1.0, "end": 2.0},
+                {"word": "five", "start": 3.0, "end": 4.0},
+                {"word": "six", "start": 8.0, "end": 9.0},
+            ],
+        });
+        let merged = merge_transcriptions_with_offset(&[first, second], &[0.0, 100.0]).unwrap();
+        assert_eq!(merged["text"], "hello one two three four five six");
+        let segments = merged["segments"].as_array().unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1]["text"], "one two three");
+        assert_eq!(segments[1]["end"], 100.4);
+        assert_eq!(segments[2]["text"], "four five six");
+        assert_eq!(segments[2]["end"], 109.0);
+        let words: Vec<&str> = merged["words"].as_array().unwrap().iter()
+            .map(|w| w["word"].as_str().unwrap())
+            .collect();
+        assert_eq!(words, ["hello", "one", "two", "three", "four", "five", "six"]);
+    }
+
+    #[test]
+    fn transcripts_convert_from_each_engine_and_write_every_format() {
+        let openai = serde_json::json!({
+            "language": "english",
+            "segments": [
+                { "start": 0.0, "end": 1.5, "text": " Hello there.", "avg_logprob": 0.0 },
+                { "start": 1.5, "end": 3.25, "text": " A <b> & {c}" }
+            ],
+            "words": [
+                { "word": "Hello", "start": 0.0, "end": 0.5 },
+                { "word": "there.", "start": 0.6, "end": 1.4 },
+                { "word": "A", "start": 1.6, "end": 1.7 }
+            ]
+        });
+        let transcript = transcript_from_openai(&openai).unwrap();
+        assert_eq!(transcript.language.as_deref(), Some("english"));
+        assert_eq!(transcript.segments[0].words.len(), 2);
+        assert_eq!(transcript.segments[1].words[0].text, "A");
+        assert_eq!(transcript.segments[0].confidence, Some(1.0));
+
+        let elevenlabs = serde_json::json!({
+            "language_code": "eng",
+            "words": [
+                { "text": "Hi", "start": 0.0, "end": 0.4, "type": "word", "speaker_id": "speaker_0", "logprob": 0.0 },
+                { "text": " ", "start": 0.4, "end": 0.5, "type": "spacing", "speaker_id": "speaker_0" },
+                { "text": "you", "start": 0.5, "end": 0.9, "type": "word", "speaker_id": "speaker_0" },
+                { "text": " ", "start": 0.9, "end": 1.0, "type": "spacing", "speaker_id": "speaker_1" },
+                { "text": "Yes.", "start": 1.0, "end": 1.5, "type": "word", "speaker_id": "speaker_1" }
+            ]
+        });
+        let diarized = transcript_from_elevenlabs(&elevenlabs).unwrap();
+        assert_eq!(diarized.segments.len(), 2);
+        assert_eq!(diarized.segments[0].text, "Hi you");
+        assert_eq!(diarized.segments[1].speaker.as_deref(), Some("speaker_1"));
+        assert_eq!(diarized.segments[1].words[0].text, "Yes.");
+
+        assert_eq!(
+            render_transcript(&transcript, "srt").unwrap(),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n2\n00:00:01,500 --> 00:00:03,250\nA <b> & {c}\n\n"
+        );
+        assert_eq!(
+            render_transcript(&diarized, "vtt").unwrap(),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:00.900\n<v speaker_0>Hi you\n\n00:00:01.000 --> 00:00:01.500\n<v speaker_1>Yes.\n\n"
+        );
+        let ass = render_transcript(&transcript, "ass").unwrap();
+        assert!(ass.starts_with("[Script Info]"));
+        assert!(ass.ends_with("Dialogue: 0,0:00:01.50,0:00:03.25,Default,,0,0,0,,A <b> & (c)\n"));
+        assert_eq!(render_transcript(&transcript, "txt").unwrap(), "Hello there.\nA <b> & {c}\n");
+        let json = render_transcript(&diarized, "json").unwrap();
+        assert_eq!(serde_json::from_str::<Transcript>(&json).unwrap(), diarized);
+
+        assert_eq!(transcript_output_formats("both").unwrap(), vec!["srt", "txt"]);
+        assert_eq!(transcript_output_formats("json, vtt").unwrap(), vec!["vtt", "json"]);
+        assert_eq!(transcript_output_formats("all").unwrap().len(), 5);
+        assert!(transcript_output_formats("docx").is_err());
+    }
+
+    #[test]
+    fn elevenlabs_segments_are_capped_without_segmentation_rules() {
+        let words: Vec<serde_json::Value> = (0..25)
+            .map(|i| serde_json::json!({"text": format!("w{}", i), "start": i as f64, "end": i as f64 + 0.5}))
+            .collect();
+        let transcript = transcript_from_elevenlabs(&serde_json::json!({"words": words})).unwrap();
+
+        let rules = SegmentationRules { max_seconds: None, max_chars: None, punctuation: false };
+        assert!(!rules.is_active());
+        let segments = resegment_transcript(&transcript, &rules).segments;
+        let lengths: Vec<usize> = segments.iter().map(|s| s.words.len()).collect();
+        assert_eq!(lengths, vec![10, 10, 5]);
+        assert_eq!(segments[1].text, "w10 w11 w12 w13 w14 w15 w16 w17 w18 w19");
+        assert_eq!((segments[2].start, segments[2].end), (20.0, 24.5));
+    }
+
+    #[test]
+    fn resegmenting_respects_limits_for_spaced_and_cjk_text() {
+        let word = |text: &str, start: f64, end: f64| TranscriptWord {
+            start,
+            end,
+            text: text.to_string(),
+            ..Default::default()
+        };
+        let english = Transcript {
+            language: None,
+            segments: vec![TranscriptSegment {
+                start: 0.0,
+                end: 6.0,
+                text: String::new(),
+                words: vec![
+                    word("One", 0.0, 0.4), word("two", 0.5, 0.9), word("three,", 1.0, 1.4),
+                    word("four", 1.5, 1.9), word("five", 2.0, 2.4), word("six.", 2.5, 2.9),
+                    word("Seven", 3.0, 3.4), word("eight", 3.5, 3.9),
+                ],
+                ..Default::default()
+            }],
+        };
+
+        let sentences = SegmentationRules { max_seconds: None, max_chars: None, punctuation: true };
+        let texts: Vec<String> = resegment_transcript(&english, &sentences).segments.into_iter().map(|s| s.text).collect();
+        assert_eq!(texts, vec!["One two three, four five six.", "Seven eight"]);
+
+        // Two lines of ten characters; the split falls after the comma and
+        // the lines are balanced, with an even tie going to the bottom line
+        let narrow = SegmentationRules { max_seconds: None, max_chars: Some(10), punctuation: true };
+        let cues = resegment_transcript(&english, &narrow).segments;
+        assert_eq!(cues[0].text, "One two\nthree,");
+        assert_eq!(cues[1].text, "four\nfive six.");
+        assert_eq!((cues[1].start, cues[1].end), (1.5, 2.9));
+        assert_eq!(cues[1].words.len(), 3);
+
+        let short = SegmentationRules { max_seconds: Some(1.0), max_chars: None, punctuation: false };
+        assert!(resegment_transcript(&english, &short).segments.iter().all(|s| s.end - s.start <= 1.0));
+
+        // Untimed CJK text is split by character with interpolated times
+        let chinese = Transcript {
+            language: Some("zh".to_string()),
+            segments: vec![TranscriptSegment {
+                start: 0.0,
+                end: 12.0,
+                text: "今天天氣很好，我們去公園散步吧。明天見。".to_string(),
+                ..Default::default()
+            }],
+        };
+        let rules = SegmentationRules { max_seconds: None, max_chars: Some(9), punctuation: true };
+        let cues = resegment_transcript(&chinese, &rules).segments;
+        let texts: Vec<&str> = cues.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["今天天氣很好，\n我們去公園散步吧。", "明天見。"]);
+        assert!(cues.iter().all(|s| s.words.is_empty()));
+        assert_eq!(cues[1].end, 12.0);
+        assert_eq!(transcript_to_txt(&Transcript { language: None, segments: cues }), "今天天氣很好，我們去公園散步吧。\n明天見。\n");
+
+        let off = SegmentationRules { max_seconds: None, max_chars: None, punctuation: false };
+        assert_eq!(resegment_transcript(&chinese, &off), chinese);
+    }
+
+    /// Frequent Simplified characters whose Traditional form differs; the
+    /// embedded dictionaries must convert each of them both ways.
+    const CHINESE_COMMON_CHARACTERS: &str = "\
+        这个们来说时会为国还对里后过发么没现开学问经进动当种样头长实点间\
+        两觉让话电气见听车门书无边写钱师难办总题岁东报场声万变亲务员级产\
+        认满条关热离识运节团际区选该战统观组农达处强义结传争极权单标质历\
+        华双联温备阶设织军议队确价断约验龙举养响资领杂爷脑艺势闭济导层厂\
+        术货续够亿湾转状错图爱买卖费钟输误虽险试适随轻讲读愿陆讨忆梦众鸡\
+        乡树录县诉压胜贵宝怀练饭顾乐环静烟伤纸药独础测脸晓绝获剧洁润补尝\
+        弹脚败渐挥灵鲜钢阵妈称吗惊宁训叶园坏坚荣圆龄贺紧胶尘肤渔誉丽顶厉\
+        怜阅浓扩偿钥缘残盖顺盘违邮宾凭屿灯陈寿飞颗归馆旧亏跃疗娱宫扫饰闲\
+        摆骑柜聪脉肠挂兴页烦鸟鱼马猫猪虫凤龟着却范干湾汉语华侨乌鸦启线\
+        伪强够脚爷们锅钟饺饼馒汤条鸭鹅虾蚂蚁缠绕绳结纺织针线\
+        银铁铜铝镜锁钥锤钉锯锻炼烧烫灶灯烛炉热闹嚣啰唠呗哟叽嘘";
+
+    #[test]
+    fn chinese_conversion_covers_common_characters() {
+        for simplified in CHINESE_COMMON_CHARACTERS.chars().filter(|c| !c.is_whitespace()) {
+            let simplified = simplified.to_string();
+            let traditional = convert_chinese(&simplified, "s2t");
+            assert_ne!(traditional, simplified, "no Traditional form for {}", simplified);
+            assert_eq!(convert_chinese(&traditional, "t2s"), simplified, "{} does not convert back", traditional);
+        }
+
+        // Characters that are also written unchanged in Simplified keep their
+        // form in the phrases that use it.
+        assert_eq!(convert_chinese("他看著那隻貓，著名的乾坤圖範本", "t2s"), "他看着那只猫，著名的乾坤图范本");
+    }
+
+    #[test]
+    fn chinese_conversion_uses_phrases_and_regional_forms() {
+        assert_eq!(convert_chinese("我们的头发干净了", "s2t"), "我們的頭髮乾淨了");
+        assert_eq!(convert_chinese("为什么这里的面条这么好吃", "s2tw"), "為什麼這裡的麵條這麼好吃");
+        assert_eq!(convert_chinese("为什么这里的面条这么好吃", "s2hk"), "為什麼這裏的麵條這麼好吃");
+        assert_eq!(convert_chinese("他说线上阅读", "s2hk"), "他説綫上閲讀");
+        assert_eq!(convert_chinese("為什麼這裡的麵條這麼好吃", "t2s"), "为什么这里的面条这么好吃");
+        assert_eq!(convert_chinese("他説綫上閲讀", "t2s"), "他说线上阅读");
+
+        let config = |language: &str, conversion: Option<&str>| TranscriptionConfig {
+            input_file: String::new(),
+            engine: "openai".to_string(),
+            language: language.to_string(),
+            model: String::new(),
+            output_format: "srt".to_string(),
+            hardware_mode: "auto".to_string(),
+            vad_enabled: false,
+            demucs_enabled: false,
+            enable_punctuation: false,
+            max_seconds: 0,
+            max_chars: 0,
+            traditional_chinese: false,
+            auto_segment: false,
+            chinese_conversion: conversion.map(String::from),
+        };
+        let transcript = |language: &str| Transcript {
+            language: Some(language.to_string()),
+            segments: vec![TranscriptSegment {
+                start: 0.0,
+                end: 1.0,
+                text: "国际".to_string(),
+                words: vec![TranscriptWord { start: 0.0, end: 1.0, text: "国际".to_string(), ..Default::default() }],
+                ..Default::default()
+            }],
+        };
+
+        let mut detected = transcript("chinese");
+        convert_transcript_script(&mut detected, &config("auto", Some("s2tw"))).unwrap();
+        assert_eq!(detected.segments[0].text, "國際");
+        assert_eq!(detected.segments[0].words[0].text, "國際");
+
+        // Japanese shares characters with Simplified Chinese and is left alone
+        let mut japanese = transcript("ja");
+        convert_transcript_script(&mut japanese, &config("auto", Some("s2tw"))).unwrap();
+        assert_eq!(japanese.segments[0].text, "国际");
+
+        assert_eq!(chinese_conversion(&config("zh", None)).unwrap(), None);
+        assert!(chinese_conversion(&config("zh", Some("s2jp"))).is_err());
+        let legacy = TranscriptionConfig { traditional_chinese: true, ..config("zh", None) };
+        assert_eq!(chinese_conversion(&legacy).unwrap(), Some("s2t"));
+    }
+
+    #[test]
+    fn part_files_match_only_their_own_output() {
+        assert!(is_part_file_of("Stream.mp4.part", "Stream"));
+        assert!(is_part_file_of("Stream.f137.mp4.part", "Stream"));
+        assert!(!is_part_file_of("Stream 2.mp4.part", "Stream"));
+        assert!(!is_part_file_of("Streamer.mp4.part", "Stream"));
+        assert!(!is_part_file_of("Stream.mp4", "Stream"));
+    }
+
+    #[test]
+    fn persisted_tasks_round_trip_and_restore_paused_or_queued() {
+        let dir = std::env::temp_dir().join(format!("tidemark-restore-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let saved_task = |id: &str, status: &str, is_live: bool| {
+            let mut task = test_task(status, is_live, 0);
+            task.progress.task_id = id.to_string();
+            task.config.output_folder = dir.to_string_lossy().into_owned();
+            task.config.output_filename = format!("{}.mp4", id);
+            task.finish_attempt("failed", Some("HTTP Error 503"));
+            task.attempt = 2;
+            task.scheduled_task_id = Some("sched".to_string());
+            PersistedDownloadTask::from(&task)
+        };
+
+        // The snapshot is written in the versioned wrapper; the older plain
+        // array still reads.
+        let snapshot = vec![saved_task("a", "downloading", false), saved_task("b", "queued", true)];
+        let path = dir.join("download_tasks.json");
+        write_versioned_array(&path, &snapshot).unwrap();
+        let raw: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(raw["config_version"], CURRENT_CONFIG_VERSION);
+        let read: Vec<PersistedDownloadTask> = read_versioned_array(&path).unwrap();
+        assert_eq!(serde_json::to_value(&read).unwrap(), serde_json::to_value(&snapshot).unwrap());
+        fs::write(&path, serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(read_versioned_array::<PersistedDownloadTask>(&path).unwrap().len(), 2);
+
+        // A running VOD comes back paused, or queued with auto resume, with
+        // its .part files counted and its attempts kept.
+        fs::write(dir.join("a.f137.mp4.part"), [0u8; 10]).unwrap();
+        let (task, closed) = restore_task(saved_task("a", "downloading", false), false);
+        assert_eq!((task.progress.status.as_str(), task.paused, task.progress.downloaded_bytes), ("paused", true, 10));
+        assert_eq!((task.attempt, task.attempts.len()), (2, 1));
+        assert_eq!(task.scheduled_task_id.as_deref(), Some("sched"));
+        assert!(closed.is_none());
+        let (task, _) = restore_task(saved_task("a", "downloading", false), true);
+        assert_eq!((task.progress.status.as_str(), task.paused), ("queued", false));
+
+        // Finished after the last snapshot: closed as completed.
+        fs::write(dir.join("c.mp4"), b"done").unwrap();
+        let (task, closed) = restore_task(saved_task("c", "downloading", false), false);
+        assert_eq!(task.progress.status, "completed");
+        assert_eq!(closed.unwrap().2, "completed");
+
+        // A live stream that had not started stays queued; a running one is
+        // closed, as failed when nothing was recorded.
+        let (task, closed) = restore_task(saved_task("b", "queued", true), false);
+        assert_eq!(task.progress.status, "queued");
+        assert!(closed.is_none());
+        let (task, closed) = restore_task(saved_task("d", "recording", true), false);
+        assert_eq!(task.progress.status, "failed");
+        let closed = closed.unwrap();
+        assert_eq!((closed.2.as_str(), closed.4.len()), ("failed", 2));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn recording_remux_picks_container_and_args() {
+        let paths = |input: &str, format: &str| {
+            let (target, temp) = remux_output_paths(Path::new(input), format);
+            (target.to_string_lossy().into_owned(), temp.to_string_lossy().into_owned())
+        };
+        assert_eq!(paths("/rec/live.ts", "auto"), ("/rec/live.mp4".to_string(), "/rec/live.remux.mp4".to_string()));
+        assert_eq!(paths("/rec/live.flv", "auto").0, "/rec/live.mp4");
+        assert_eq!(paths("/rec/live.mkv", "auto").0, "/rec/live.mkv");
+        assert_eq!(paths("/rec/live.ts", "MKV"), ("/rec/live.mkv".to_string(), "/rec/live.remux.mkv".to_string()));
+
+        let mp4 = remux_output_args(Path::new("/rec/live.remux.mp4"), true);
+        assert_eq!(mp4[..6], ["-map", "0:v?", "-map", "0:a?", "-c", "copy"]);
+        assert!(mp4.ends_with(&["-movflags".to_string(), "+faststart".to_string()]));
+        assert!(!remux_output_args(Path::new("/rec/live.remux.mp4"), false).contains(&"-movflags".to_string()));
+        assert!(!remux_output_args(Path::new("/rec/live.remux.mkv"), true).contains(&"-movflags".to_string()));
+    }
+
+    #[test]
+    fn recording_remux_keeps_original_when_a_step_fails() {
+        let original = MediaProbe { duration: Some(600.0), video_streams: 1, audio_streams: 1, ..Default::default() };
+        let probe = |duration: f64, video_streams: usize, audio_streams: usize| MediaProbe {
+            duration: Some(duration),
+            video_streams,
+            audio_streams,
+            ..Default::default()
+        };
+        assert!(compare_processed_output(&original, &probe(601.5, 1, 1)).is_ok());
+        assert!(compare_processed_output(&original, &probe(600.0, 1, 0)).is_err());
+        assert!(compare_processed_output(&original, &probe(590.0, 1, 1)).is_err());
+        assert!(compare_processed_output(&original, &MediaProbe { duration: None, ..probe(0.0, 1, 1) }).is_err());
+
+        let dir = std::env::temp_dir().join(format!("tidemark-remux-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("live.ts");
+        let (target, temp) = remux_output_paths(&input, "auto");
+
+        // Remux or verify failed: the partial output goes, the original stays.
+        fs::write(&input, b"original").unwrap();
+        fs::write(&temp, b"partial").unwrap();
+        let kept = settle_remuxed_output(&input, &temp, &target, Err("Duration mismatch".to_string()));
+        assert_eq!(kept, input.to_string_lossy());
+        assert!(input.exists() && !temp.exists() && !target.exists());
+
+        // Nothing to move into place: the original stays too.
+        let kept = settle_remuxed_output(&input, &temp, &target, Ok(()));
+        assert_eq!(kept, input.to_string_lossy());
+        assert!(input.exists());
+
+        // Success replaces the original.
+        fs::write(&temp, b"remuxed").unwrap();
+        let kept = settle_remuxed_output(&input, &temp, &target, Ok(()));
+        assert_eq!(kept, target.to_string_lossy());
+        assert!(!input.exists() && !temp.exists());
+        assert_eq!(fs::read(&target).unwrap(), b"remuxed");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn ytdlp_json_maps_twitch_vod_and_clip() {
+        let vod = serde_json::json!({
+            "id": "v2012345678",
+            "title": "Late night speedrun",
+            "uploader": "SpeedRunner",
+            "channel": "speedrunner_channel",
+            "thumbnail": "https://static-cdn.jtvnw.net/cf_vods/thumb.jpg",
+            "duration": 10843,
+            "timestamp": 1718000000,
+            "upload_date": "20240609",
+            "is_live": false,
+            "formats": [
+                { "format_id": "Audio_Only", "ext": "mp4", "vcodec": "none", "acodec": "mp4a.40.2" },
+                { "format_id": "720p30", "ext": "mp4", "height": 720, "filesize_approx": 1500000000 },
+                { "format_id": "1080p60", "ext": "mp4", "format_note": "Source", "height": 1080, "filesize": 4200000000i64 }
+            ]
+        });
+        let info = video_info_from_ytdlp_json(&vod, "2012345678", "twitch", "video");
+        assert_eq!((info.id.as_str(), info.title.as_str(), info.channel.as_str()), ("2012345678", "Late night speedrun", "SpeedRunner"));
+        assert_eq!((info.content_type.as_str(), info.is_live, info.duration), ("video", false, Some(10843)));
+        assert_eq!(info.created_at.as_deref(), Some("2024-06-10T06:13:20+00:00"));
+        let labels: Vec<(&str, &str, Option<i64>)> = info
+            .qualities
+            .iter()
+            .map(|q| (q.format_id.as_str(), q.quality.as_str(), q.filesize))
+            .collect();
+        assert_eq!(labels, [
+            ("Audio_Only", "Audio_Only", None),
+            ("720p30", "720p30", Some(1500000000)),
+            ("1080p60", "Source", Some(4200000000)),
+        ]);
+        assert_eq!(info.qualities[0].vcodec.as_deref(), Some("none"));
+
+        // Clips have no uploader and a fractional duration; without a
+        // timestamp the upload date is used.
+        let clip = serde_json::json!({
+            "id": "FunnyClipSlug",
+            "channel": "ClipStreamer",
+            "duration": 29.6,
+            "upload_date": "20240315",
+            "formats": [{ "format_id": "480", "ext": "mp4", "height": 480 }]
+        });
+        let info = video_info_from_ytdlp_json(&clip, "FunnyClipSlug", "twitch", "clip");
+        assert_eq!((info.title.as_str(), info.channel.as_str(), info.thumbnail.as_str()), ("Unknown", "ClipStreamer", ""));
+        assert_eq!((info.content_type.as_str(), info.duration), ("clip", Some(30)));
+        assert_eq!(info.created_at.as_deref(), Some("2024-03-15"));
+        assert_eq!(info.qualities[0].quality, "480");
+
+        // Other sites label formats by height; a live flag makes it a stream.
+        let live = serde_json::json!({
+            "is_live": true,
+            "formats": [{ "format_id": "301", "ext": "mp4", "height": 1080 }, { "format_id": "140", "ext": "m4a" }]
+        });
+        let info = video_info_from_ytdlp_json(&live, "abc", "youtube", "video");
+        assert_eq!((info.channel.as_str(), info.content_type.as_str(), info.duration, info.created_at), ("Unknown", "stream", None, None));
+        let labels: Vec<&str> = info.qualities.iter().map(|q| q.quality.as_str()).collect();
+        assert_eq!(labels, ["1080p", "audio only"]);
+    }
+
+    #[test]
+    fn recording_reconnect_parts_start_at_the_live_edge() {
+        let mut config = test_task("recording", true, 0).config;
+        config.format_id = "best".to_string();
+        config.container_format = "mkv".to_string();
+        let auth = vec!["--cookies".to_string(), "/tmp/cookies.txt".to_string()];
+
+        let first = recording_ytdlp_args(&config, 1, false, true, Some(500_000), &auth);
+        assert_eq!(first, [
+            "--newline", "--progress", "-f", "best", "--live-from-start",
+            "--limit-rate", "500000", "--cookies", "/tmp/cookies.txt",
+            "--remux-video", "mkv", config.url.as_str(),
+        ]);
+
+        let reconnect = recording_ytdlp_args(&config, 2, true, false, None, &auth);
+        assert_eq!(reconnect, [
+            "--newline", "--no-progress", "-f", "best",
+            "--cookies", "/tmp/cookies.txt", config.url.as_str(),
+        ]);
+        assert!(!recording_ytdlp_args(&config, 3, false, false, None, &[]).contains(&"--live-from-start".to_string()));
+    }
+
+    #[test]
+    fn template_date_prefers_video_date() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z").unwrap().with_timezone(&Utc);
+
+        assert_eq!(
+            template_date_vars(Some("2024-05-06T07:08:09+00:00"), now),
+            ("2024-05-06".to_string(), "2024-05-06_070809".to_string())
+        );
+        assert_eq!(
+            template_date_vars(Some("2024-05-06"), now),
+            ("2024-05-06".to_string(), "2024-05-06_000000".to_string())
+        );
+        assert_eq!(
+            template_date_vars(None, now),
+            ("2026-03-01".to_string(), "2026-03-01_120000".to_string())
+        );
+        assert_eq!(template_date_vars(Some("yesterday"), now).0, "2026-03-01");
+    }
+
+    #[test]
+    fn playlist_filters_by_date_and_title() {
+        let entry = |title: &str, date: Option<&str>| PlaylistEntry {
+            id: "id".to_string(),
+            url: "https://www.youtube.com/watch?v=id".to_string(),
+            title: title.to_string(),
+            channel: "channel".to_string(),
+            duration: None,
+            upload_date: date.map(String::from),
+            platform: "youtube".to_string(),
+        };
+        let filter = PlaylistFilter {
+            date_after: Some("2024-01-01".to_string()),
+            date_before: Some("2024-12-31".to_string()),
+            title_contains: Some("Karaoke".to_string()),
+            title_excludes: Some("members".to_string()),
+            skip_downloaded: true,
+            archive_file: None,
+        };
+
+        assert!(playlist_entry_matches(&entry("【KARAOKE】 night", Some("2024-01-01")), &filter));
+        assert!(playlist_entry_matches(&entry("karaoke", Some("2024-12-31")), &filter));
+        assert!(!playlist_entry_matches(&entry("karaoke", Some("2023-12-31")), &filter));
+        assert!(!playlist_entry_matches(&entry("karaoke", Some("2025-01-01")), &filter));
+        assert!(!playlist_entry_matches(&entry("Zatsudan", Some("2024-06-01")), &filter));
+        assert!(!playlist_entry_matches(&entry("Karaoke (Members only)", Some("2024-06-01")), &filter));
+        // Undated entries only face the title filters
+        assert!(playlist_entry_matches(&entry("karaoke", None), &filter));
+
+        let blank = PlaylistFilter {
+            date_after: Some(" ".to_string()),
+            date_before: None,
+            title_contains: Some(String::new()),
+            title_excludes: None,
+            skip_downloaded: false,
+            archive_file: None,
+        };
+        assert!(playlist_entry_matches(&entry("anything", Some("1999-01-01")), &blank));
+    }
+
+    #[test]
+    fn sanitize_filename_str_strips_unsafe_characters() {
+        assert_eq!(sanitize_filename_str("a/b\\c:d*e?f\"g<h>i|j"), "a_b_c_d_e_f_g_h_i_j");
+        assert_eq!(sanitize_filename_str("  ..title\u{7}  with   spaces.. "), "title with spaces");
+        assert_eq!(sanitize_filename_str("..."), "");
+        assert_eq!(sanitize_filename_str("con"), "_con");
+
+        let long = "歌".repeat(100);
+        let truncated = sanitize_filename_str(&long);
+        assert!(truncated.len() <= MAX_FILENAME_BYTES);
+        assert_eq!(truncated, "歌".repeat(MAX_FILENAME_BYTES / 3));
+    }
+
+    #[test]
+    fn precise_cut_offset_follows_section_start() {
+        // MP4 sections keep the requested start at 0
+        assert_eq!(section_cut_offset(10.0, Some(0.0)), 10.0);
+        // The section really starts at a keyframe 3.5s before the padded start
+        assert_eq!(section_cut_offset(10.0, Some(-3.5)), 13.5);
+        assert_eq!(section_cut_offset(10.0, Some(1.4)), 10.0);
+        assert_eq!(section_cut_offset(0.0, None), 0.0);
+    }
+
+    #[test]
+    fn piped_splits_cut_at_fragment_starts() {
+        let packet = |pid: u16| {
+            let mut packet = vec![0xff; TS_PACKET_BYTES];
+            packet[0] = 0x47;
+            packet[1] = (pid >> 8) as u8 & 0x1f;
+            packet[2] = pid as u8;
+            packet
+        };
+        let chunk = [packet(0x100), packet(0x100), packet(0), packet(0x100)].concat();
+
+        assert_eq!(split_point(&chunk, 0, 0), Some(2 * TS_PACKET_BYTES));
+        // Packet boundaries follow the stream position, not the chunk start
+        assert_eq!(split_point(&chunk[100..], 100, 0), Some(2 * TS_PACKET_BYTES - 100));
+        assert_eq!(split_point(&chunk[..2 * TS_PACKET_BYTES], 0, 0), None);
+        // Without a fragment start in reach, cut at the next packet boundary
+        assert_eq!(split_point(&chunk[..2 * TS_PACKET_BYTES], 0, SPLIT_SEARCH_BYTES), Some(0));
+        assert_eq!(split_point(&chunk[10..], 10, SPLIT_SEARCH_BYTES), Some(TS_PACKET_BYTES - 10));
+    }
+
+    #[test]
+    fn config_migration_moves_plaintext_secrets_into_store() {
+        let dir = std::env::temp_dir().join(format!("tidemark-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("auth_config.json"),
+            r#"{"twitch_token":"abc123","youtube_cookies_path":"/tmp/cookies.txt","openai_api_key":"sk-test","groq_api_key":null,"elevenlabs_api_key":null}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("sync_state.json"),
+            r#"{"jwt":"header.payload.sig","user":null,"last_synced_at":"","status":"synced"}"#,
+        )
+        .unwrap();
+
+        migrate_plaintext_secrets(&dir).unwrap();
+
+        let auth: AuthConfig = serde_json::from_str(&fs::read_to_string(dir.join("auth_config.json")).unwrap()).unwrap();
+        assert_eq!(auth.twitch_token, None);
+        assert_eq!(auth.openai_api_key, None);
+        assert_eq!(auth.youtube_cookies_path.as_deref(), Some("/tmp/cookies.txt"));
+        let sync: SyncState = serde_json::from_str(&fs::read_to_string(dir.join("sync_state.json")).unwrap()).unwrap();
+        assert_eq!(sync.jwt, None);
+        assert_eq!(sync.status, "synced");
+
+        let secrets = load_secrets_at(&dir.join("secret_store.json"), &dir.join("secret_store.key")).unwrap();
+        assert_eq!(secrets.twitch_token.as_deref(), Some("abc123"));
+        assert_eq!(secrets.openai_api_key.as_deref(), Some("sk-test"));
+        assert_eq!(secrets.sync_jwt.as_deref(), Some("header.payload.sig"));
+        assert!(!dir.join("secret_store.key.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |name: &str| fs::metadata(dir.join(name)).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode("secret_store.json"), 0o600);
+            assert_eq!(mode("secret_store.key"), 0o600);
+        }
+
+        // Nothing left in plaintext, so a second run changes nothing
+        let store = fs::read_to_string(dir.join("secret_store.json")).unwrap();
+        migrate_plaintext_secrets(&dir).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("secret_store.json")).unwrap(), store);
+
+        // An unreadable key is an error, not a reason to make a new one
+        assert!(read_or_create_key_file(&dir).is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn test_task(status: &str, is_live: bool, priority: i32) -> DownloadTask {
+        let config = DownloadConfig {
+            url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            video_info: VideoInfo {
+                id: "dQw4w9WgXcQ".to_string(),
+                title: "Test".to_string(),
+                channel: "Channel".to_string(),
+                thumbnail: String::new(),
+                duration: None,
+                platform: "youtube".to_string(),
+                content_type: if is_live { "stream" } else { "video" }.to_string(),
+                is_live,
+                qualities: Vec::new(),
+                created_at: None,
+                start_time: None,
+            },
+            format_id: "best".to_string(),
+            content_type: "video+audio".to_string(),
+            video_codec: None,
+            audio_codec: None,
+            output_filename: "test".to_string(),
+            output_folder: "/tmp".to_string(),
+            container_format: "auto".to_string(),
+            time_range: None,
+            download_archive: None,
+            precise_cut: None,
+            recording_limits: RecordingLimits::default(),
+            cookie_profile_id: None,
+        };
+        let progress = DownloadProgress {
+            task_id: String::new(),
+            status: status.to_string(),
+            title: "Test".to_string(),
+            percentage: 0.0,
+            speed: "0 B/s".to_string(),
+            eta: String::new(),
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            output_path: None,
+            error_message: None,
+            is_recording: is_live.then_some(true),
+            recorded_duration: None,
+            bitrate: None,
+            retry_attempt: None,
+            max_retries: None,
+            priority,
+            queue_position: None,
+            processing_step: None,
+            segments: Vec::new(),
+        };
+        DownloadTask::new(config, progress)
+    }
+
+    #[test]
+    fn bandwidth_split_weights_live_tasks_and_stays_within_budget() {
+        let mib = 1024 * 1024;
+        let tasks = |live: usize, vods: usize| -> Vec<(String, bool)> {
+            (0..live).map(|i| (format!("live{}", i), true))
+                .chain((0..vods).map(|i| (format!("vod{}", i), false)))
+                .collect()
+        };
+
+        let rates = split_bandwidth_budget(mib, &tasks(1, 1), true);
+        assert_eq!((rates["live0"], rates["vod0"]), (mib * 3 / 4, mib / 4));
+        let rates = split_bandwidth_budget(mib, &tasks(1, 1), false);
+        assert_eq!((rates["live0"], rates["vod0"]), (mib / 2, mib / 2));
+
+        // VODs under the floor get it; the live task takes what is left.
+        let rates = split_bandwidth_budget(mib, &tasks(1, 14), true);
+        assert!((0..14).all(|i| rates[&format!("vod{}", i)] == MIN_TASK_RATE));
+        assert_eq!(rates["live0"], mib - 14 * MIN_TASK_RATE);
+
+        // Too many tasks for the floor: it is dropped rather than overshooting.
+        for (budget, live, vods) in [(0, 1, 2), (mib, 1, 14), (mib, 2, 40), (3 * mib, 5, 7)] {
+            let rates = split_bandwidth_budget(budget, &tasks(live, vods), true);
+            assert_eq!(rates.len(), live + vods);
+            assert!(rates.values().sum::<u64>() <= budget, "{} tasks over {}", live + vods, budget);
+        }
+        assert!(split_bandwidth_budget(mib, &[], true).is_empty());
+    }
+
+    #[test]
+    fn bandwidth_plan_covers_running_tasks_only() {
+        let mut tasks = HashMap::new();
+        tasks.insert("live".to_string(), test_task("recording", true, LIVE_TASK_PRIORITY));
+        tasks.insert("vod".to_string(), test_task("downloading", false, 0));
+        tasks.insert("queued".to_string(), test_task("queued", false, 0));
+        let mut paused = test_task("downloading", false, 0);
+        paused.paused = true;
+        tasks.insert("paused".to_string(), paused);
+
+        // No limit: every running task is unthrottled.
+        let mut config = AppConfig::default();
+        let plan = plan_bandwidth(&config, &tasks);
+        assert_eq!(plan.len(), 2);
+        assert!(plan.values().all(Option::is_none));
+
+        config.download_speed_limit = 2;
+        let plan = plan_bandwidth(&config, &tasks);
+        let (live, vod) = (plan["live"].unwrap(), plan["vod"].unwrap());
+        assert!(live > vod);
+        assert!(live + vod <= 2 * 1024 * 1024);
+
+        config.prioritize_live_recordings = false;
+        let plan = plan_bandwidth(&config, &tasks);
+        assert_eq!(plan["live"], plan["vod"]);
+    }
+
+    #[test]
+    fn queue_orders_by_priority_then_arrival_and_reorders_in_place() {
+        let mut tasks = HashMap::new();
+        for (id, is_live, priority) in [("vod1", false, 0), ("vod2", false, 0), ("live", true, LIVE_TASK_PRIORITY), ("vod3", false, 0)] {
+            tasks.insert(id.to_string(), test_task("queued", is_live, priority));
+        }
+        tasks.insert("running".to_string(), test_task("downloading", false, 0));
+
+        // The live recording was queued third but starts first.
+        assert_eq!(queued_task_order(&tasks), ["live", "vod1", "vod2", "vod3"]);
+
+        let seqs = |tasks: &HashMap<String, DownloadTask>, ids: &[&str]| -> Vec<u64> {
+            ids.iter().map(|id| tasks[*id].queue_seq).collect()
+        };
+        let before = seqs(&tasks, &["vod1", "vod2", "vod3"]);
+        let ids: Vec<String> = ["vod3", "vod1", "running"].iter().map(|id| id.to_string()).collect();
+        reorder_queued_tasks(&mut tasks, &ids);
+
+        // vod3 and vod1 trade sequence numbers; vod2 and the running task keep theirs.
+        assert_eq!(seqs(&tasks, &["vod3", "vod1", "vod2"]), [before[0], before[2], before[1]]);
+        assert_eq!(queued_task_order(&tasks), ["live", "vod3", "vod2", "vod1"]);
+
+        // A reorder cannot lift a VOD above the live recording.
+        let ids: Vec<String> = ["vod1", "live"].iter().map(|id| id.to_string()).collect();
+        reorder_queued_tasks(&mut tasks, &ids);
+        assert_eq!(queued_task_order(&tasks)[0], "live");
+    }
+}
@@ -14,12 +14,24 @@ export interface AppConfig {
 
   // Download settings
   enable_transcoder: boolean;
+  transcode_codec: string; // 'h264' | 'hevc'
+  transcode_crf: number; // constant quality, used when transcode_bitrate_kbps is 0
+  transcode_bitrate_kbps: number; // 0 = use CRF
+  transcode_resolution: string; // 'original' or a maximum height such as '1080p'
   default_video_quality: string;
   output_container: string;
+  precise_cut_mode: string; // 'off' | 'boundaries' | 'full'
   max_concurrent_downloads: number;
   auto_retry: boolean;
   max_retry_count: number;
-  download_speed_limit: number; // MB/s, 0 = unlimited
+  download_speed_limit: number; // MB/s, 0 = unlimited, shared by all active tasks
+  prioritize_live_recordings: boolean;
+  auto_resume_downloads: boolean; // resume unfinished downloads on launch instead of restoring them paused
+  recording_remux: boolean; // remux finished recordings into the preset container
+  recording_faststart: boolean;
+  recording_verify: boolean; // ffprobe duration/stream check after remux
+  recording_reconnect_grace_minutes: number; // keep polling a dropped stream, 0 = off
+  recording_reconnect_concat: boolean; // join reconnect parts into one file
   show_codec_options: boolean;
 
   // Appearance settings
@@ -69,12 +81,24 @@ const defaultConfig: AppConfig = {
   language: 'zh-TW',
   timezone: 'System',
   enable_transcoder: false,
+  transcode_codec: 'h264',
+  transcode_crf: 23,
+  transcode_bitrate_kbps: 0,
+  transcode_resolution: 'original',
   default_video_quality: 'Highest',
   output_container: 'Auto',
+  precise_cut_mode: 'off',
   max_concurrent_downloads: 3,
   auto_retry: true,
   max_retry_count: 3,
   download_speed_limit: 0,
+  prioritize_live_recordings: true,
+  auto_resume_downloads: false,
+  recording_remux: true,
+  recording_faststart: true,
+  recording_verify: true,
+  recording_reconnect_grace_minutes: 5,
+  recording_reconnect_concat: true,
   show_codec_options: false,
   theme: 'system',
   animation: true,
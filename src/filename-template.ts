@@ -2,7 +2,7 @@
  * Filename Template Engine — TypeScript interface (F10.1, F10.4)
  *
  * Wraps Tauri commands for the unified filename template engine.
- * The engine supports {variable} syntax with 10 recognized variables,
+ * The engine supports {variable} syntax with 11 recognized variables,
  * two-phase expansion for scheduled downloads, OS-safe sanitization,
  * conflict resolution, and yt-dlp special character escaping.
  */
@@ -23,6 +23,7 @@ export const TEMPLATE_VARIABLES = [
   { key: 'datetime',     label: '日期時間', description: '日期與時間（YYYY-MM-DD_HHmmss）',        example: '2026-02-17_143052' },
   { key: 'resolution',   label: '畫質',     description: '畫質（如 1080p）；排程下載時為延遲變數', example: '1080p' },
   { key: 'duration',     label: '時長',     description: '時長（如 02h30m15s）；排程下載時為延遲變數', example: '01h25m30s' },
+  { key: 'part',         label: '分段',     description: '分段錄製的段落編號（如 001）；未分段時省略', example: '001' },
 ] as const;
 
 /**
@@ -49,6 +50,7 @@ export const PREVIEW_SAMPLE_VARS: Record<string, string> = {
   datetime:     '2026-02-17_143052',
   resolution:   '1080p',
   duration:     '01h25m30s',
+  part:         '001',
 };
 
 /**
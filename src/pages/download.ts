@@ -28,6 +28,26 @@ interface VideoInfo {
   content_type: string;
   is_live: boolean;
   qualities: VideoQuality[];
+  created_at?: string | null;
+  start_time?: number | null; // seconds, from a t=/start= URL parameter
+}
+
+interface PlaylistEntry {
+  id: string;
+  url: string;
+  title: string;
+  channel: string;
+  duration: number | null;
+  upload_date: string | null;
+  platform: string;
+}
+
+interface PlaylistExpansion {
+  title: string | null;
+  entries: PlaylistEntry[];
+  total: number;
+  skipped_downloaded: number;
+  filtered_out: number;
 }
 
 interface DownloadConfig {
@@ -41,6 +61,16 @@ interface DownloadConfig {
   output_folder: string;
   container_format: string;
   time_range: TimeRange | null;
+  precise_cut?: string | null; // 'boundaries' | 'full'
+  recording_limits?: RecordingLimits;
+}
+
+// Live recording split/stop limits; 0 disables a limit
+interface RecordingLimits {
+  split_every_minutes: number;
+  split_every_gb: number;
+  max_duration_minutes: number;
+  max_size_gb: number;
 }
 
 interface TimeRange {
@@ -63,6 +93,16 @@ interface DownloadProgress {
   is_recording?: boolean;
   recorded_duration?: string;
   bitrate?: string;
+  // Automatic retry state
+  retry_attempt?: number | null;
+  max_retries?: number | null;
+  // Download queue
+  priority?: number;
+  queue_position?: number | null;
+  // Post-processing step while status is 'processing'
+  processing_step?: 'remux' | 'verify' | 'transcode' | 'cut' | 'concat' | null;
+  // Segmented recordings: files written so far
+  segments?: string[];
 }
 
 let currentVideoInfo: VideoInfo | null = null;
@@ -176,9 +216,27 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
             <div class="time-range-help">
               ${t('download.settings.timeRangeHelp')}
             </div>
+            <select id="cut-mode-select" class="config-select">
+              <option value="off">${t('download.settings.cutModeOff')}</option>
+              <option value="boundaries">${t('download.settings.cutModeBoundaries')}</option>
+              <option value="full">${t('download.settings.cutModeFull')}</option>
+            </select>
             <div id="time-range-error" class="time-range-error hidden"></div>
           </div>
 
+          <div id="recording-limits-row" class="config-row hidden">
+            <label class="config-label">${t('download.settings.recordingLimits')}</label>
+            <div class="time-range-inputs">
+              <input type="number" id="split-minutes-input" class="time-input" min="0" step="1" placeholder="${t('download.settings.splitEveryMinutes')}" />
+              <input type="number" id="split-gb-input" class="time-input" min="0" step="0.1" placeholder="${t('download.settings.splitEveryGb')}" />
+              <input type="number" id="max-minutes-input" class="time-input" min="0" step="1" placeholder="${t('download.settings.maxDurationMinutes')}" />
+              <input type="number" id="max-gb-input" class="time-input" min="0" step="0.1" placeholder="${t('download.settings.maxSizeGb')}" />
+            </div>
+            <div class="time-range-help">
+              ${t('download.settings.recordingLimitsHelp')}
+            </div>
+          </div>
+
           <div class="config-row">
             <button id="start-download-btn" class="primary-button large-button">${t('download.actions.startDownload')}</button>
             <button id="record-stream-btn" class="primary-button large-button hidden" style="margin-left: 12px; background: #e91e63;">${t('download.actions.recordStream')}</button>
@@ -186,6 +244,86 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
         </div>
       </div>
 
+      <div id="playlist-section" class="playlist-section download-config hidden">
+        <h3 class="section-title">${t('download.playlist.title')}</h3>
+        <p class="playlist-hint">${t('download.playlist.hint')}</p>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.playlist.dateRange')}</label>
+          <div class="time-range-inputs">
+            <input type="date" id="playlist-date-after" class="time-input" />
+            <span class="time-separator">${t('download.settings.timeSeparator')}</span>
+            <input type="date" id="playlist-date-before" class="time-input" />
+          </div>
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.playlist.titleContains')}</label>
+          <input type="text" id="playlist-title-contains" class="config-input" />
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.playlist.titleExcludes')}</label>
+          <input type="text" id="playlist-title-excludes" class="config-input" />
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">
+            <input type="checkbox" id="playlist-skip-downloaded" checked />
+            ${t('download.playlist.skipDownloaded')}
+          </label>
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.playlist.archiveFile')}</label>
+          <input type="text" id="playlist-archive-input" class="config-input" placeholder="${t('download.playlist.archiveFilePlaceholder')}" />
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.settings.quality')}</label>
+          <select id="playlist-quality" class="config-select">
+            <option value="best">${t('download.playlist.qualityBest')}</option>
+            <option value="1080p">1080p</option>
+            <option value="720p">720p</option>
+            <option value="480p">480p</option>
+            <option value="360p">360p</option>
+          </select>
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.settings.contentType')}</label>
+          <select id="playlist-content-type" class="config-select">
+            <option value="video+audio">${t('download.settings.contentTypeVideoAudio')}</option>
+            <option value="audio_only">${t('download.settings.contentTypeAudioOnly')}</option>
+          </select>
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.settings.container')}</label>
+          <select id="playlist-container" class="config-select">
+            <option value="auto">${t('download.settings.containerAuto')}</option>
+            <option value="mp4">MP4</option>
+            <option value="mkv">MKV</option>
+          </select>
+        </div>
+
+        <div class="config-row">
+          <label class="config-label">${t('download.settings.outputFolder')}</label>
+          <div class="folder-picker">
+            <input type="text" id="playlist-folder-input" class="config-input" readonly />
+            <button id="playlist-folder-btn" class="secondary-button">${t('download.actions.folderSelect')}</button>
+          </div>
+        </div>
+
+        <div class="config-row">
+          <button id="playlist-preview-btn" class="secondary-button">${t('download.playlist.preview')}</button>
+          <button id="playlist-enqueue-btn" class="primary-button large-button" style="margin-left: 12px;" disabled>${t('download.playlist.enqueue')}</button>
+        </div>
+
+        <p id="playlist-summary" class="playlist-summary"></p>
+        <ul id="playlist-entries" class="playlist-entries"></ul>
+      </div>
+
       <div id="downloads-section" class="downloads-section">
         <h3 class="section-title">${t('download.progress.title')}</h3>
         <div id="downloads-list" class="downloads-list"></div>
@@ -224,6 +362,8 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
   const startTimeInput = container.querySelector('#start-time-input') as HTMLInputElement;
   const endTimeInput = container.querySelector('#end-time-input') as HTMLInputElement;
   const timeRangeError = container.querySelector('#time-range-error') as HTMLElement;
+  const cutModeSelect = container.querySelector('#cut-mode-select') as HTMLSelectElement;
+  cutModeSelect.value = ConfigManager.get().precise_cut_mode || 'off';
   const startDownloadBtn = container.querySelector('#start-download-btn') as HTMLButtonElement;
   const recordStreamBtn = container.querySelector('#record-stream-btn') as HTMLButtonElement;
   const downloadsList = container.querySelector('#downloads-list') as HTMLElement;
@@ -240,13 +380,23 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
     fetchBtn.disabled = true;
     fetchBtn.textContent = t('download.urlInput.fetching');
 
+    playlistSection.classList.add('hidden');
+
     try {
       const videoInfo = await invoke<VideoInfo>('fetch_video_info', { url });
       currentVideoInfo = videoInfo;
       currentUrl = url;
       displayVideoInfo(videoInfo);
+      // Prefill the range start from a timestamped link (e.g. ?t=1h2m3s)
+      if (videoInfo.start_time && !startTimeInput.value) {
+        startTimeInput.value = formatDuration(videoInfo.start_time);
+      }
     } catch (error) {
-      showError(resolveLocalizedMessage(String(error)));
+      if (String(error) === 'errors.download.is_playlist') {
+        showPlaylistSection(url);
+      } else {
+        showError(resolveLocalizedMessage(String(error)));
+      }
     } finally {
       fetchBtn.disabled = false;
       fetchBtn.textContent = t('download.urlInput.fetchButton');
@@ -340,6 +490,7 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
       output_folder: folderInput.value,
       container_format: containerSelect.value,
       time_range: timeRange,
+      precise_cut: timeRange && cutModeSelect.value !== 'off' ? cutModeSelect.value : null,
     };
 
     try {
@@ -350,6 +501,16 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
     }
   });
 
+  function readRecordingLimits(): RecordingLimits {
+    const value = (id: string) => Math.max(0, Number((container.querySelector(id) as HTMLInputElement).value) || 0);
+    return {
+      split_every_minutes: Math.floor(value('#split-minutes-input')),
+      split_every_gb: value('#split-gb-input'),
+      max_duration_minutes: Math.floor(value('#max-minutes-input')),
+      max_size_gb: value('#max-gb-input'),
+    };
+  }
+
   recordStreamBtn.addEventListener('click', async () => {
     if (!currentVideoInfo || !currentVideoInfo.is_live) return;
 
@@ -364,6 +525,7 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
       output_folder: folderInput.value,
       container_format: containerSelect.value,
       time_range: null, // No time range for live recording
+      recording_limits: readRecordingLimits(),
     };
 
     try {
@@ -408,6 +570,109 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
     }
   }
 
+  // Playlist / channel bulk download
+  const playlistSection = container.querySelector('#playlist-section') as HTMLElement;
+  const playlistFolderInput = container.querySelector('#playlist-folder-input') as HTMLInputElement;
+  const playlistPreviewBtn = container.querySelector('#playlist-preview-btn') as HTMLButtonElement;
+  const playlistEnqueueBtn = container.querySelector('#playlist-enqueue-btn') as HTMLButtonElement;
+  const playlistSummary = container.querySelector('#playlist-summary') as HTMLElement;
+  const playlistEntriesList = container.querySelector('#playlist-entries') as HTMLElement;
+  const playlistArchiveInput = container.querySelector('#playlist-archive-input') as HTMLInputElement;
+  let playlistUrl = '';
+  let playlistEntries: PlaylistEntry[] = [];
+
+  function showPlaylistSection(url: string) {
+    playlistUrl = url;
+    playlistEntries = [];
+    playlistEnqueueBtn.disabled = true;
+    playlistSummary.textContent = '';
+    playlistEntriesList.textContent = '';
+    if (!playlistFolderInput.value) {
+      playlistFolderInput.value = ConfigManager.get().default_download_folder || '~/Tidemark/Downloads';
+    }
+    playlistSection.classList.remove('hidden');
+  }
+
+  function readPlaylistFilter() {
+    const value = (id: string) => (container.querySelector(`#${id}`) as HTMLInputElement).value.trim() || null;
+    return {
+      date_after: value('playlist-date-after'),
+      date_before: value('playlist-date-before'),
+      title_contains: value('playlist-title-contains'),
+      title_excludes: value('playlist-title-excludes'),
+      skip_downloaded: (container.querySelector('#playlist-skip-downloaded') as HTMLInputElement).checked,
+      archive_file: playlistArchiveInput.value.trim() || null,
+    };
+  }
+
+  (container.querySelector('#playlist-folder-btn') as HTMLButtonElement).addEventListener('click', async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (selected) {
+      playlistFolderInput.value = selected as string;
+    }
+  });
+
+  playlistPreviewBtn.addEventListener('click', async () => {
+    if (!playlistUrl) return;
+
+    playlistPreviewBtn.disabled = true;
+    playlistEnqueueBtn.disabled = true;
+    playlistSummary.textContent = t('download.playlist.loading');
+    playlistEntriesList.textContent = '';
+
+    try {
+      const expansion = await invoke<PlaylistExpansion>('expand_playlist', {
+        url: playlistUrl,
+        filter: readPlaylistFilter(),
+      });
+      playlistEntries = expansion.entries;
+      playlistSummary.textContent = t('download.playlist.summary', {
+        title: expansion.title || playlistUrl,
+        count: expansion.entries.length,
+        total: expansion.total,
+        skipped: expansion.skipped_downloaded,
+        filtered: expansion.filtered_out,
+      });
+
+      for (const entry of expansion.entries) {
+        const item = document.createElement('li');
+        item.className = 'playlist-entry';
+        item.textContent = entry.upload_date ? `${entry.upload_date}  ${entry.title}` : entry.title;
+        playlistEntriesList.appendChild(item);
+      }
+      playlistEnqueueBtn.disabled = playlistEntries.length === 0;
+    } catch (error) {
+      playlistSummary.textContent = resolveLocalizedMessage(String(error));
+    } finally {
+      playlistPreviewBtn.disabled = false;
+    }
+  });
+
+  playlistEnqueueBtn.addEventListener('click', async () => {
+    if (playlistEntries.length === 0) return;
+
+    playlistEnqueueBtn.disabled = true;
+    try {
+      const taskIds = await invoke<string[]>('enqueue_playlist_entries', {
+        entries: playlistEntries,
+        options: {
+          quality: (container.querySelector('#playlist-quality') as HTMLSelectElement).value,
+          content_type: (container.querySelector('#playlist-content-type') as HTMLSelectElement).value,
+          output_folder: playlistFolderInput.value,
+          container_format: (container.querySelector('#playlist-container') as HTMLSelectElement).value,
+          filename_template: ConfigManager.get().default_filename_template,
+          archive_file: playlistArchiveInput.value.trim() || null,
+        },
+      });
+      playlistSummary.textContent = t('download.playlist.enqueued', { count: taskIds.length });
+      playlistEntries = [];
+      playlistEntriesList.textContent = '';
+    } catch (error) {
+      playlistSummary.textContent = resolveLocalizedMessage(String(error));
+      playlistEnqueueBtn.disabled = false;
+    }
+  });
+
   function showError(message: string) {
     errorMessage.textContent = message;
     errorMessage.classList.remove('hidden');
@@ -465,6 +730,7 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
       if (timeRangeRow) {
         timeRangeRow.style.display = 'none';
       }
+      container.querySelector('#recording-limits-row')?.classList.remove('hidden');
     } else {
       liveBadge.classList.add('hidden');
       // Show download button, hide record button for VODs
@@ -476,6 +742,7 @@ export function renderDownloadPage(container: HTMLElement, navData?: NavigationD
       if (timeRangeRow) {
         timeRangeRow.style.display = 'flex';
       }
+      container.querySelector('#recording-limits-row')?.classList.add('hidden');
     }
 
     populateQualities(info.qualities);
@@ -625,7 +892,7 @@ function createTaskCard(progress: DownloadProgress): HTMLElement {
   card.innerHTML = `
     <div class="task-header">
       <h4 class="task-title">${progress.title}</h4>
-      <span class="task-status">${getStatusText(progress.status)}</span>
+      <span class="task-status">${getStatusText(progress.status)}${progress.status === 'queued' && progress.queue_position ? ` #${progress.queue_position}` : ''}${progress.retry_attempt ? ` ${t('download.progress.retryAttempt', { attempt: progress.retry_attempt, max: progress.max_retries ?? progress.retry_attempt })}` : ''}</span>
       ${isRecording ? `<span class="live-indicator">${t('download.progress.liveRecording')}</span>` : ''}
     </div>
 
@@ -653,12 +920,21 @@ function createTaskCard(progress: DownloadProgress): HTMLElement {
             <span class="stat-label">${t('download.progress.bitrate')}</span>
             <span class="stat-value">${progress.bitrate || 'N/A'}</span>
           </div>
+          ${progress.segments && progress.segments.length > 0 ? `
+            <div class="recording-stat">
+              <span class="stat-label">${t('download.progress.segments')}</span>
+              <span class="stat-value">${progress.segments.length}</span>
+            </div>
+          ` : ''}
         </div>
       `}
     </div>
 
     <div class="task-actions">
-      ${progress.status === 'recording' ? `
+      ${progress.status === 'reconnecting' ? `
+        <p class="warning-text">${t('download.progress.reconnecting')}</p>
+      ` : ''}
+      ${progress.status === 'recording' || progress.status === 'reconnecting' ? `
         <button class="action-btn stop-recording-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.stopRecording')}</button>
         <button class="action-btn cancel-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.cancel')}</button>
       ` : ''}
@@ -666,21 +942,33 @@ function createTaskCard(progress: DownloadProgress): HTMLElement {
         <button class="action-btn pause-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.pause')}</button>
         <button class="action-btn cancel-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.cancel')}</button>
       ` : ''}
+      ${(progress.status === 'queued' || progress.status === 'retrying') && !isRecording ? `
+        <button class="action-btn pause-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.pause')}</button>
+      ` : ''}
+      ${progress.status === 'queued' ? `
+        <button class="action-btn cancel-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.cancel')}</button>
+      ` : ''}
       ${progress.status === 'paused' ? `
         <button class="action-btn resume-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.resume')}</button>
         <button class="action-btn cancel-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.cancel')}</button>
       ` : ''}
       ${progress.status === 'processing' ? `
-        <p class="processing-text">${t('download.progress.postProcessing')}</p>
+        <p class="processing-text">${progress.processing_step ? t(`download.progress.processingStep.${progress.processing_step}`, { percent: progress.percentage.toFixed(0) }) : t('download.progress.postProcessing')}</p>
       ` : ''}
       ${progress.status === 'completed' && progress.output_path ? `
         <button class="action-btn open-btn" data-path="${progress.output_path}">${t('download.progress.actions.openFile')}</button>
         <button class="action-btn folder-btn" data-path="${progress.output_path}">${t('download.progress.actions.showFolder')}</button>
         <button class="action-btn transcribe-btn" data-path="${progress.output_path}">${t('download.progress.actions.sendToTranscription')}</button>
+        ${progress.segments && progress.segments.length > 1 ? `
+          <p class="processing-text">${t('download.progress.segmentCount', { count: progress.segments.length })}</p>
+        ` : ''}
       ` : ''}
       ${progress.status === 'failed' && progress.error_message ? `
         <p class="error-text">${resolveLocalizedMessage(progress.error_message)}</p>
       ` : ''}
+      ${progress.status === 'failed' || progress.status === 'stream_interrupted' ? `
+        <button class="action-btn log-btn" data-task-id="${progress.task_id}">${t('download.progress.actions.viewLog')}</button>
+      ` : ''}
       ${progress.status === 'stream_interrupted' ? `
         <p class="warning-text">${t('download.progress.streamInterrupted')}</p>
         ${progress.output_path ? `
@@ -689,6 +977,7 @@ function createTaskCard(progress: DownloadProgress): HTMLElement {
         ` : ''}
       ` : ''}
     </div>
+    <pre class="task-log" hidden></pre>
   `;
 
   // Attach event listeners
@@ -770,6 +1059,24 @@ function createTaskCard(progress: DownloadProgress): HTMLElement {
     });
   });
 
+  card.querySelectorAll('.log-btn').forEach(btn => {
+    btn.addEventListener('click', async (e) => {
+      const taskId = (e.target as HTMLElement).dataset.taskId;
+      const logView = card.querySelector('.task-log') as HTMLPreElement;
+      if (!taskId) return;
+      if (!logView.hidden) {
+        logView.hidden = true;
+        return;
+      }
+      try {
+        logView.textContent = await invoke<string>('get_task_log', { taskId });
+      } catch (error) {
+        logView.textContent = resolveLocalizedMessage(String(error));
+      }
+      logView.hidden = false;
+    });
+  });
+
   card.querySelectorAll('.transcribe-btn').forEach(btn => {
     btn.addEventListener('click', (e) => {
       // TODO: Navigate to transcription page with this file
@@ -786,11 +1093,13 @@ function getStatusText(status: string): string {
     'queued': t('download.status.queued'),
     'downloading': t('download.status.downloading'),
     'recording': t('download.status.recording'),
+    'reconnecting': t('download.status.reconnecting'),
     'processing': t('download.status.processing'),
     'completed': t('download.status.completed'),
     'failed': t('download.status.failed'),
     'cancelled': t('download.status.cancelled'),
     'paused': t('download.status.paused'),
+    'retrying': t('download.status.retrying'),
     'stream_interrupted': t('download.status.stream_interrupted'),
   };
   return statusMap[status] || status;
@@ -15,6 +15,16 @@ interface DownloadHistory {
   started_at: string;
   completed_at: string | null;
   error_message: string | null;
+  attempts?: DownloadAttempt[];
+  segments?: string[];
+}
+
+interface DownloadAttempt {
+  attempt: number;
+  started_at: string;
+  ended_at: string;
+  status: string;
+  error_message: string | null;
 }
 
 let allHistory: DownloadHistory[] = [];
@@ -180,7 +190,7 @@ function createHistoryEntryElement(entry: DownloadHistory): HTMLElement {
 
   const platformIcon = document.createElement('span');
   platformIcon.className = 'platform-icon';
-  platformIcon.textContent = entry.platform === 'youtube' ? '🎬' : '🎮';
+  platformIcon.textContent = entry.platform === 'youtube' ? '🎬' : entry.platform === 'twitch' ? '🎮' : '🌐';
 
   const detailsDiv = document.createElement('div');
   detailsDiv.className = 'entry-details';
@@ -220,6 +230,12 @@ function createHistoryEntryElement(entry: DownloadHistory): HTMLElement {
   pathItem.querySelector('.metadata-value')?.classList.add('file-path');
   metadataDiv.appendChild(pathItem);
 
+  if (entry.segments && entry.segments.length > 1) {
+    const segmentsItem = createMetadataItem(t('history.metadata.segments'), String(entry.segments.length));
+    segmentsItem.title = entry.segments.join('\n');
+    metadataDiv.appendChild(segmentsItem);
+  }
+
   entryDiv.appendChild(metadataDiv);
 
   // Error message if present
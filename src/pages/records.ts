@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import * as CloudSync from '../sync';
 import { navigateToDownload } from '../app';
 import { ConfigManager } from '../config';
@@ -29,6 +30,29 @@ interface RecordsData {
   folder_order: string[];
 }
 
+interface RecordClipFailure {
+  record_id: string;
+  topic: string;
+  error: string;
+}
+
+interface RecordClipSummary {
+  task_ids: string[];
+  failures: RecordClipFailure[];
+}
+
+interface HighlightExportProgress {
+  job_id: string;
+  folder_id: string;
+  status: 'resolving' | 'downloading' | 'concatenating' | 'completed' | 'failed';
+  completed_clips: number;
+  total_clips: number;
+  output_path: string | null;
+  cue_path: string | null;
+  error: string | null;
+  failures: RecordClipFailure[];
+}
+
 interface RecordGroup {
   title: string;
   records: Record[];
@@ -43,12 +67,17 @@ let currentFolderId: string | null = ALL_RECORDS_ID;
 let searchQuery = '';
 let editingFolderId: string | null = null;
 let editingRecordId: string | null = null;
+
+// Running highlight exports by folder id; the listener is registered once.
+const highlightExports = new Map<string, HighlightExportProgress>();
+let highlightUnlisten: (() => void) | null = null;
 let draggedFolderId: string | null = null;
 let syncState: CloudSync.SyncState | null = null;
 let containerElement: HTMLElement | null = null;
 
 export function renderRecordsPage(container: HTMLElement) {
   containerElement = container;
+  ensureHighlightListener();
 
   // Load sync state and records
   Promise.all([
@@ -344,6 +373,20 @@ function createMainContent(): HTMLElement {
   searchBox.appendChild(searchInput);
 
   header.appendChild(searchBox);
+
+  const clipsBtn = document.createElement('button');
+  clipsBtn.id = 'download-folder-clips-btn';
+  clipsBtn.className = 'secondary-button';
+  clipsBtn.textContent = t('records.actions.downloadAllClips');
+  clipsBtn.disabled = getRecordCountForFolder(currentFolderId ?? ALL_RECORDS_ID) === 0;
+  header.appendChild(clipsBtn);
+
+  const highlightsBtn = document.createElement('button');
+  highlightsBtn.id = 'export-highlights-btn';
+  highlightsBtn.className = 'secondary-button';
+  header.appendChild(highlightsBtn);
+  updateHighlightsButton(highlightsBtn);
+
   main.appendChild(header);
 
   // Content
@@ -474,6 +517,13 @@ function createRecordElement(record: Record): HTMLElement {
   downloadBtn.textContent = '📥';
   actions.appendChild(downloadBtn);
 
+  const queueClipBtn = document.createElement('button');
+  queueClipBtn.className = 'record-action-btn queue-clip-btn';
+  queueClipBtn.dataset.recordId = record.id;
+  queueClipBtn.title = t('records.actions.queueClip');
+  queueClipBtn.textContent = '✂️';
+  actions.appendChild(queueClipBtn);
+
   const linkBtn = document.createElement('a');
   linkBtn.href = record.channel_url;
   linkBtn.target = '_blank';
@@ -604,6 +654,48 @@ function formatSecondsToTime(seconds: number): string {
   }
 }
 
+function updateHighlightsButton(btn: HTMLButtonElement) {
+  const folderId = currentFolderId ?? ALL_RECORDS_ID;
+  const job = highlightExports.get(folderId);
+  if (job) {
+    btn.disabled = true;
+    btn.textContent = job.status === 'concatenating'
+      ? t('records.highlights.concatenating')
+      : t(job.status === 'resolving' ? 'records.highlights.resolving' : 'records.highlights.downloading',
+        { completed: job.completed_clips, total: job.total_clips });
+  } else {
+    btn.disabled = getRecordCountForFolder(folderId) === 0;
+    btn.textContent = t('records.actions.exportHighlights');
+  }
+}
+
+/** Register (once) the highlight-export-progress listener. */
+async function ensureHighlightListener() {
+  if (highlightUnlisten) return; // Already registered
+
+  highlightUnlisten = await listen<HighlightExportProgress>('highlight-export-progress', (event) => {
+    const job = event.payload;
+    if (job.status === 'completed' || job.status === 'failed') {
+      highlightExports.delete(job.folder_id);
+      let message = job.status === 'completed'
+        ? t('records.highlights.completed', { path: job.output_path ?? '' })
+        : t('records.highlights.failed', { error: resolveLocalizedMessage(job.error) });
+      if (job.failures.length > 0) {
+        const details = job.failures
+          .map(f => `• ${f.topic}: ${resolveLocalizedMessage(f.error)}`)
+          .join('\n');
+        message += '\n\n' + t('records.clips.failed', { count: job.failures.length }) + '\n' + details;
+      }
+      alert(message);
+    } else {
+      highlightExports.set(job.folder_id, job);
+    }
+
+    const btn = document.getElementById('export-highlights-btn') as HTMLButtonElement | null;
+    if (btn) updateHighlightsButton(btn);
+  });
+}
+
 function attachEventListeners(container: HTMLElement) {
   // Create folder button
   const createFolderBtn = container.querySelector('#create-folder-btn');
@@ -952,4 +1044,65 @@ function attachEventListeners(container: HTMLElement) {
       }
     });
   });
+
+  // Queue clip button: resolve the VOD and queue the trimmed download directly
+  container.querySelectorAll('.queue-clip-btn').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      const recordId = (btn as HTMLElement).dataset.recordId;
+      if (!recordId) return;
+
+      const button = btn as HTMLButtonElement;
+      button.disabled = true;
+      try {
+        await invoke<string>('download_record_clip', { recordId });
+        alert(t('records.clips.queued', { count: 1 }));
+      } catch (error) {
+        alert(t('records.error.queueClipFailed', { error: resolveLocalizedMessage(String(error)) }));
+      } finally {
+        button.disabled = false;
+      }
+    });
+  });
+
+  // Export the current folder as one highlight reel
+  const highlightsBtn = container.querySelector('#export-highlights-btn') as HTMLButtonElement | null;
+  highlightsBtn?.addEventListener('click', async () => {
+    const folderId = currentFolderId ?? ALL_RECORDS_ID;
+    if (!confirm(t('records.confirm.exportHighlights', { name: getFolderTitle() }))) return;
+
+    highlightsBtn.disabled = true;
+    try {
+      await invoke<string>('export_folder_highlights', { folderId });
+    } catch (error) {
+      alert(t('records.highlights.failed', { error: resolveLocalizedMessage(String(error)) }));
+      updateHighlightsButton(highlightsBtn);
+    }
+  });
+
+  // Download all clips in the current folder
+  const folderClipsBtn = container.querySelector('#download-folder-clips-btn') as HTMLButtonElement | null;
+  folderClipsBtn?.addEventListener('click', async () => {
+    const folderId = currentFolderId ?? ALL_RECORDS_ID;
+    const count = getRecordCountForFolder(folderId);
+    if (!confirm(t('records.confirm.downloadAllClips', { count, name: getFolderTitle() }))) return;
+
+    folderClipsBtn.disabled = true;
+    folderClipsBtn.textContent = t('records.clips.resolving');
+    try {
+      const summary = await invoke<RecordClipSummary>('download_folder_clips', { folderId });
+      let message = t('records.clips.queued', { count: summary.task_ids.length });
+      if (summary.failures.length > 0) {
+        const details = summary.failures
+          .map(f => `• ${f.topic}: ${resolveLocalizedMessage(f.error)}`)
+          .join('\n');
+        message += '\n\n' + t('records.clips.failed', { count: summary.failures.length }) + '\n' + details;
+      }
+      alert(message);
+    } catch (error) {
+      alert(t('records.error.queueClipFailed', { error: resolveLocalizedMessage(String(error)) }));
+    } finally {
+      folderClipsBtn.disabled = false;
+      folderClipsBtn.textContent = t('records.actions.downloadAllClips');
+    }
+  });
 }
@@ -23,8 +23,25 @@ interface DownloadPreset {
   created_at: string;       // ISO 8601
   last_triggered_at: string | null;
   trigger_count: number;
+  recording_limits?: RecordingLimits;
+  cookie_profile_id?: string | null;
 }
 
+// Split/stop limits for the triggered recording; 0 disables a limit
+interface RecordingLimits {
+  split_every_minutes: number;
+  split_every_gb: number;
+  max_duration_minutes: number;
+  max_size_gb: number;
+}
+
+const RECORDING_LIMIT_FIELDS: { key: keyof RecordingLimits; id: string; labelKey: string; step: string }[] = [
+  { key: 'split_every_minutes',  id: 'preset-split-minutes', labelKey: 'scheduled.modal.splitEveryMinutes',  step: '1' },
+  { key: 'split_every_gb',       id: 'preset-split-gb',      labelKey: 'scheduled.modal.splitEveryGb',       step: '0.1' },
+  { key: 'max_duration_minutes', id: 'preset-max-minutes',   labelKey: 'scheduled.modal.maxDurationMinutes', step: '1' },
+  { key: 'max_size_gb',          id: 'preset-max-gb',        labelKey: 'scheduled.modal.maxSizeGb',          step: '0.1' },
+];
+
 interface ChannelInfo {
   channel_id: string;
   channel_name: string;
@@ -1233,6 +1250,39 @@ function createPresetModal(existingPreset: DownloadPreset | null): HTMLElement {
   ], existingPreset?.container_format || 'auto'));
   modalBody.appendChild(containerGroup);
 
+  // Cookie profile; the list loads after the modal opens
+  const cookieProfileSelect = createSelectElement('preset-cookie-profile', [
+    { value: '', label: t('scheduled.modal.cookieProfileDefault') },
+  ], '');
+  modalBody.appendChild(createFormGroup(t('scheduled.modal.cookieProfile'), cookieProfileSelect));
+  invoke<{ id: string; name: string; platform: string }[]>('get_cookie_profiles')
+    .then(profiles => {
+      profiles.forEach(profile => {
+        const option = document.createElement('option');
+        option.value = profile.id;
+        option.textContent = `${profile.name} (${profile.platform === 'twitch' ? 'Twitch' : 'YouTube'})`;
+        option.selected = profile.id === existingPreset?.cookie_profile_id;
+        cookieProfileSelect.appendChild(option);
+      });
+    })
+    .catch(error => console.error('Failed to load cookie profiles:', error));
+
+  // Recording limits (split into segments / stop automatically)
+  const limitsHint = document.createElement('p');
+  limitsHint.className = 'form-hint';
+  limitsHint.textContent = t('scheduled.modal.recordingLimitsHint');
+  modalBody.appendChild(limitsHint);
+  RECORDING_LIMIT_FIELDS.forEach(field => {
+    const input = document.createElement('input');
+    input.type = 'number';
+    input.className = 'form-input';
+    input.id = field.id;
+    input.min = '0';
+    input.step = field.step;
+    input.value = String(existingPreset?.recording_limits?.[field.key] ?? 0);
+    modalBody.appendChild(createFormGroup(t(field.labelKey), input));
+  });
+
   modal.appendChild(modalBody);
 
   // Modal footer with Save/Cancel buttons
@@ -1381,6 +1431,7 @@ async function handleSavePreset(existingPreset: DownloadPreset | null) {
   const contentTypeSelect = document.getElementById('preset-content-type') as HTMLSelectElement;
   const outputDirInput = document.getElementById('preset-output-dir') as HTMLInputElement;
   const containerSelect = document.getElementById('preset-container-format') as HTMLSelectElement;
+  const cookieProfileSelect = document.getElementById('preset-cookie-profile') as HTMLSelectElement;
 
   // Get template from the visual editor; fall back to global default if empty
   const globalDefault = ConfigManager.get().default_filename_template || DEFAULT_FILENAME_TEMPLATE;
@@ -1434,6 +1485,8 @@ async function handleSavePreset(existingPreset: DownloadPreset | null) {
     created_at: existingPreset?.created_at || now,
     last_triggered_at: existingPreset?.last_triggered_at ?? null,
     trigger_count: existingPreset?.trigger_count ?? 0,
+    recording_limits: readPresetRecordingLimits(),
+    cookie_profile_id: cookieProfileSelect.value || null,
   };
 
   try {
@@ -1458,6 +1511,15 @@ async function handleSavePreset(existingPreset: DownloadPreset | null) {
   }
 }
 
+function readPresetRecordingLimits(): RecordingLimits {
+  const limits: RecordingLimits = { split_every_minutes: 0, split_every_gb: 0, max_duration_minutes: 0, max_size_gb: 0 };
+  RECORDING_LIMIT_FIELDS.forEach(field => {
+    const value = Math.max(0, Number((document.getElementById(field.id) as HTMLInputElement).value) || 0);
+    limits[field.key] = field.step === '1' ? Math.floor(value) : value;
+  });
+  return limits;
+}
+
 function createFormGroup(labelText: string, inputEl: HTMLElement): HTMLElement {
   const group = document.createElement('div');
   group.className = 'form-group';
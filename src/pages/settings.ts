@@ -18,6 +18,24 @@ let currentAuthConfig: AuthConfig = {
   youtube_cookies_path: null,
 };
 
+interface CookieProfile {
+  id: string;
+  name: string;
+  platform: string; // "youtube" | "twitch"
+  source: string;   // "file" | "browser"
+  cookies_path: string | null;
+  browser: string | null;
+  created_at: string;
+}
+
+interface CookieProfileStatus {
+  profile_id: string;
+  status: string; // "valid" | "expiring" | "expired" | "no_auth_cookies" | "unreadable" | "unchecked"
+  auth_cookies: { name: string; domain: string; expires_at: string | null }[];
+}
+
+const COOKIE_BROWSERS = ['chrome', 'firefox', 'edge', 'brave', 'chromium', 'opera', 'vivaldi', 'safari'];
+
 let currentConfig: AppConfig | null = null;
 
 export function renderSettingsPage(container: HTMLElement) {
@@ -211,6 +229,58 @@ function createDownloadSection(): HTMLElement {
   );
   section.appendChild(transcoderGroup);
 
+  // Transcode codec
+  const transcodeCodecGroup = createDropdownGroupWithValues(
+    'transcode-codec',
+    '轉碼編碼格式',
+    '使用 GPU 加速設定中的硬體編碼器，失敗時改用軟體編碼',
+    currentConfig?.transcode_codec || 'h264',
+    [
+      { value: 'h264', label: 'H.264' },
+      { value: 'hevc', label: 'H.265 (HEVC)' },
+    ]
+  );
+  section.appendChild(transcodeCodecGroup);
+
+  // Transcode quality (CRF)
+  const transcodeCrfGroup = createNumberInputGroup(
+    'transcode-crf',
+    '轉碼品質 (CRF)',
+    '數值越小畫質越高',
+    currentConfig?.transcode_crf ?? 23,
+    0,
+    51
+  );
+  section.appendChild(transcodeCrfGroup);
+
+  // Transcode bitrate
+  const transcodeBitrateGroup = createNumberInputGroup(
+    'transcode-bitrate-kbps',
+    '轉碼位元率 (kbps)',
+    '0 = 使用 CRF',
+    currentConfig?.transcode_bitrate_kbps || 0,
+    0,
+    100000
+  );
+  section.appendChild(transcodeBitrateGroup);
+
+  // Transcode resolution
+  const transcodeResolutionGroup = createDropdownGroupWithValues(
+    'transcode-resolution',
+    '轉碼解析度',
+    '只會縮小，不會放大',
+    currentConfig?.transcode_resolution || 'original',
+    [
+      { value: 'original', label: '原始' },
+      { value: '2160p', label: '2160p' },
+      { value: '1440p', label: '1440p' },
+      { value: '1080p', label: '1080p' },
+      { value: '720p', label: '720p' },
+      { value: '480p', label: '480p' },
+    ]
+  );
+  section.appendChild(transcodeResolutionGroup);
+
   // Default video quality
   const qualityGroup = createDropdownGroup(
     'default-video-quality',
@@ -231,6 +301,20 @@ function createDownloadSection(): HTMLElement {
   );
   section.appendChild(containerGroup);
 
+  // Precise cut mode for time-range downloads
+  const preciseCutGroup = createDropdownGroupWithValues(
+    'precise-cut-mode',
+    '精準剪輯',
+    '指定時間範圍時，依影格精準裁切（需重新編碼）',
+    currentConfig?.precise_cut_mode || 'off',
+    [
+      { value: 'off', label: '關閉（依關鍵影格）' },
+      { value: 'boundaries', label: '只重新編碼頭尾' },
+      { value: 'full', label: '重新編碼整段' },
+    ]
+  );
+  section.appendChild(preciseCutGroup);
+
   // Max concurrent downloads
   const concurrentGroup = createNumberInputGroup(
     'max-concurrent-downloads',
@@ -273,6 +357,43 @@ function createDownloadSection(): HTMLElement {
   );
   section.appendChild(speedLimitGroup);
 
+  // Bandwidth priority for live recordings
+  const prioritizeLiveGroup = createToggleGroup(
+    'prioritize-live-recordings',
+    '優先直播錄製',
+    '速度限制下優先分配頻寬給直播錄製',
+    currentConfig?.prioritize_live_recordings !== false
+  );
+  section.appendChild(prioritizeLiveGroup);
+
+  // Resume unfinished downloads on launch
+  const autoResumeGroup = createToggleGroup(
+    'auto-resume-downloads',
+    '自動續傳未完成下載',
+    '啟動時直接續傳未完成的下載，而非以暫停狀態還原',
+    currentConfig?.auto_resume_downloads || false
+  );
+  section.appendChild(autoResumeGroup);
+
+  // Reconnect dropped live recordings
+  const reconnectGraceGroup = createNumberInputGroup(
+    'recording-reconnect-grace',
+    '直播斷線重連 (分鐘)',
+    '直播中斷後持續偵測並接續錄製的時間，0 = 不重連',
+    currentConfig?.recording_reconnect_grace_minutes ?? 5,
+    0,
+    120
+  );
+  section.appendChild(reconnectGraceGroup);
+
+  const reconnectConcatGroup = createToggleGroup(
+    'recording-reconnect-concat',
+    '合併重連片段',
+    '以 FFmpeg 將斷線前後的錄製合併為單一檔案',
+    currentConfig?.recording_reconnect_concat !== false
+  );
+  section.appendChild(reconnectConcatGroup);
+
   // Show codec options
   const codecGroup = createToggleGroup(
     'show-codec-options',
@@ -942,6 +1063,14 @@ function createAuthSection(): HTMLElement {
   const youtubeGroup = createYouTubeAuthGroup();
   section.appendChild(youtubeGroup);
 
+  // Named cookie profiles
+  const cookieProfilesGroup = createCookieProfilesGroup();
+  section.appendChild(cookieProfilesGroup);
+
+  // Secret store encryption
+  const secretStoreGroup = createSecretStoreGroup();
+  section.appendChild(secretStoreGroup);
+
   // Clear button
   const clearGroup = document.createElement('div');
   clearGroup.className = 'setting-group';
@@ -1318,6 +1447,262 @@ function createYouTubeAuthGroup(): HTMLElement {
   return group;
 }
 
+function createCookieProfilesGroup(): HTMLElement {
+  const group = document.createElement('div');
+  group.className = 'setting-group';
+
+  const groupTitle = document.createElement('h3');
+  groupTitle.className = 'setting-group-title';
+  groupTitle.textContent = 'Cookie 設定檔';
+  group.appendChild(groupTitle);
+
+  const description = document.createElement('p');
+  description.className = 'setting-description';
+  description.textContent = '為不同平台與帳號建立具名的 Cookie 設定檔，並在排程預設中選用。可匯入 cookies.txt，或由 yt-dlp 直接從瀏覽器讀取。';
+  group.appendChild(description);
+
+  const list = document.createElement('div');
+  list.id = 'cookie-profiles-list';
+  list.className = 'cookie-profile-list';
+  group.appendChild(list);
+
+  const nameGroup = document.createElement('div');
+  nameGroup.className = 'auth-input-group';
+
+  const nameInput = document.createElement('input');
+  nameInput.type = 'text';
+  nameInput.id = 'cookie-profile-name';
+  nameInput.className = 'auth-input';
+  nameInput.placeholder = '設定檔名稱，例如：主帳號';
+  nameGroup.appendChild(nameInput);
+
+  const platformSelect = document.createElement('select');
+  platformSelect.id = 'cookie-profile-platform';
+  platformSelect.className = 'setting-select';
+  [['youtube', 'YouTube'], ['twitch', 'Twitch']].forEach(([value, label]) => {
+    const option = document.createElement('option');
+    option.value = value;
+    option.textContent = label;
+    platformSelect.appendChild(option);
+  });
+  nameGroup.appendChild(platformSelect);
+
+  const sourceSelect = document.createElement('select');
+  sourceSelect.id = 'cookie-profile-source';
+  sourceSelect.className = 'setting-select';
+  [['file', 'cookies.txt 檔案'], ['browser', '從瀏覽器讀取']].forEach(([value, label]) => {
+    const option = document.createElement('option');
+    option.value = value;
+    option.textContent = label;
+    sourceSelect.appendChild(option);
+  });
+  nameGroup.appendChild(sourceSelect);
+
+  group.appendChild(nameGroup);
+
+  const fileGroup = document.createElement('div');
+  fileGroup.id = 'cookie-profile-file-group';
+  fileGroup.className = 'auth-input-group';
+
+  const pathInput = document.createElement('input');
+  pathInput.type = 'text';
+  pathInput.id = 'cookie-profile-path';
+  pathInput.className = 'auth-input';
+  pathInput.placeholder = '尚未選擇 cookies.txt';
+  pathInput.readOnly = true;
+  fileGroup.appendChild(pathInput);
+
+  const fileBtn = document.createElement('button');
+  fileBtn.id = 'cookie-profile-file-btn';
+  fileBtn.className = 'btn btn-secondary';
+  fileBtn.textContent = '選擇檔案';
+  fileGroup.appendChild(fileBtn);
+
+  group.appendChild(fileGroup);
+
+  const browserGroup = document.createElement('div');
+  browserGroup.id = 'cookie-profile-browser-group';
+  browserGroup.className = 'auth-input-group';
+  browserGroup.hidden = true;
+
+  const browserSelect = document.createElement('select');
+  browserSelect.id = 'cookie-profile-browser';
+  browserSelect.className = 'setting-select';
+  COOKIE_BROWSERS.forEach(browser => {
+    const option = document.createElement('option');
+    option.value = browser;
+    option.textContent = browser;
+    browserSelect.appendChild(option);
+  });
+  browserGroup.appendChild(browserSelect);
+
+  const browserProfileInput = document.createElement('input');
+  browserProfileInput.type = 'text';
+  browserProfileInput.id = 'cookie-profile-browser-profile';
+  browserProfileInput.className = 'auth-input';
+  browserProfileInput.placeholder = '瀏覽器設定檔名稱（選填），例如：Profile 1';
+  browserGroup.appendChild(browserProfileInput);
+
+  group.appendChild(browserGroup);
+
+  const addBtn = document.createElement('button');
+  addBtn.id = 'cookie-profile-add-btn';
+  addBtn.className = 'btn btn-primary';
+  addBtn.textContent = '新增設定檔';
+  group.appendChild(addBtn);
+
+  const statusDiv = document.createElement('div');
+  statusDiv.id = 'cookie-profile-status';
+  statusDiv.className = 'auth-status';
+  group.appendChild(statusDiv);
+
+  return group;
+}
+
+function createSecretStoreGroup(): HTMLElement {
+  const group = document.createElement('div');
+  group.className = 'setting-group';
+
+  const groupTitle = document.createElement('h3');
+  groupTitle.className = 'setting-group-title';
+  groupTitle.textContent = '認證資訊加密';
+  group.appendChild(groupTitle);
+
+  const description = document.createElement('p');
+  description.className = 'setting-description';
+  description.textContent = 'Token、API 金鑰與同步登入資訊皆加密儲存。預設使用本機金鑰檔；設定密碼後，每次啟動需輸入密碼解鎖。';
+  group.appendChild(description);
+
+  const inputGroup = document.createElement('div');
+  inputGroup.className = 'auth-input-group';
+
+  const input = document.createElement('input');
+  input.type = 'password';
+  input.id = 'secret-passphrase-input';
+  input.className = 'auth-input';
+  input.placeholder = '請輸入密碼';
+  inputGroup.appendChild(input);
+
+  const unlockBtn = document.createElement('button');
+  unlockBtn.id = 'secret-unlock-btn';
+  unlockBtn.className = 'btn btn-primary';
+  unlockBtn.textContent = '解鎖';
+  unlockBtn.hidden = true;
+  inputGroup.appendChild(unlockBtn);
+
+  const setBtn = document.createElement('button');
+  setBtn.id = 'secret-set-passphrase-btn';
+  setBtn.className = 'btn btn-primary';
+  setBtn.textContent = '設定密碼';
+  inputGroup.appendChild(setBtn);
+
+  const removeBtn = document.createElement('button');
+  removeBtn.id = 'secret-remove-passphrase-btn';
+  removeBtn.className = 'btn btn-secondary';
+  removeBtn.textContent = '改用本機金鑰';
+  removeBtn.hidden = true;
+  inputGroup.appendChild(removeBtn);
+
+  group.appendChild(inputGroup);
+
+  const statusDiv = document.createElement('div');
+  statusDiv.id = 'secret-store-status';
+  statusDiv.className = 'auth-status';
+  group.appendChild(statusDiv);
+
+  return group;
+}
+
+function cookieProfileStatusText(status: CookieProfileStatus | undefined): { type: string; text: string } {
+  const soonest = status?.auth_cookies[0];
+  const expiry = soonest?.expires_at ? new Date(soonest.expires_at).toLocaleString() : '';
+  switch (status?.status) {
+    case 'valid':
+      return { type: 'verified', text: expiry ? `✓ 有效（${soonest?.name} 於 ${expiry} 到期）` : '✓ 有效' };
+    case 'expiring':
+      return { type: 'expiring', text: `即將過期：${soonest?.name} 於 ${expiry} 到期` };
+    case 'expired':
+      return { type: 'error', text: `已過期：${soonest?.name} 於 ${expiry} 到期` };
+    case 'no_auth_cookies':
+      return { type: 'error', text: '檔案中沒有登入用的 Cookie' };
+    case 'unreadable':
+      return { type: 'error', text: '無法讀取 cookies 檔案' };
+    case 'unchecked':
+      return { type: 'unverified', text: '由 yt-dlp 從瀏覽器讀取，無法檢查到期時間' };
+    default:
+      return { type: 'unverified', text: '未檢查' };
+  }
+}
+
+async function renderCookieProfiles(list: Element | null) {
+  if (!list) return;
+
+  try {
+    const [profiles, statuses] = await Promise.all([
+      invoke<CookieProfile[]>('get_cookie_profiles'),
+      invoke<CookieProfileStatus[]>('check_cookie_profiles'),
+    ]);
+
+    list.innerHTML = '';
+    if (profiles.length === 0) {
+      const empty = document.createElement('p');
+      empty.className = 'setting-description';
+      empty.textContent = '尚未建立任何設定檔';
+      list.appendChild(empty);
+      return;
+    }
+
+    profiles.forEach(profile => {
+      const row = document.createElement('div');
+      row.className = 'cookie-profile-item';
+
+      const info = document.createElement('div');
+      info.className = 'cookie-profile-info';
+
+      const name = document.createElement('span');
+      name.className = 'cookie-profile-name';
+      name.textContent = `${profile.name}（${profile.platform === 'twitch' ? 'Twitch' : 'YouTube'}）`;
+      info.appendChild(name);
+
+      const source = document.createElement('span');
+      source.className = 'cookie-profile-source';
+      source.textContent = profile.source === 'browser'
+        ? `瀏覽器：${profile.browser ?? ''}`
+        : profile.cookies_path ?? '';
+      info.appendChild(source);
+
+      const statusDiv = document.createElement('div');
+      statusDiv.className = 'auth-status';
+      const { type, text } = cookieProfileStatusText(statuses.find(s => s.profile_id === profile.id));
+      updateStatusElement(statusDiv, type, text);
+      info.appendChild(statusDiv);
+
+      row.appendChild(info);
+
+      const deleteBtn = document.createElement('button');
+      deleteBtn.className = 'btn btn-secondary';
+      deleteBtn.textContent = '刪除';
+      deleteBtn.addEventListener('click', async () => {
+        if (!confirm(`確定要刪除設定檔「${profile.name}」嗎？使用此設定檔的排程預設將改用上方的認證資訊。`)) {
+          return;
+        }
+        try {
+          await invoke('delete_cookie_profile', { id: profile.id });
+          await renderCookieProfiles(list);
+        } catch (error) {
+          console.error('Failed to delete cookie profile:', error);
+          alert('刪除失敗');
+        }
+      });
+      row.appendChild(deleteBtn);
+
+      list.appendChild(row);
+    });
+  } catch (error) {
+    console.error('Failed to load cookie profiles:', error);
+  }
+}
+
 // Event listener functions
 function attachGeneralEventListeners(container: HTMLElement) {
   // Default download folder
@@ -1375,6 +1760,10 @@ function attachGeneralEventListeners(container: HTMLElement) {
 function attachDownloadEventListeners(container: HTMLElement) {
   // Enable transcoder
   attachToggleListener(container, 'enable-transcoder', 'enable_transcoder');
+  attachDropdownListener(container, 'transcode-codec', 'transcode_codec');
+  attachNumberInputListener(container, 'transcode-crf', 'transcode_crf');
+  attachNumberInputListener(container, 'transcode-bitrate-kbps', 'transcode_bitrate_kbps');
+  attachDropdownListener(container, 'transcode-resolution', 'transcode_resolution');
 
   // Default video quality
   attachDropdownListener(container, 'default-video-quality', 'default_video_quality');
@@ -1382,6 +1771,9 @@ function attachDownloadEventListeners(container: HTMLElement) {
   // Output container
   attachDropdownListener(container, 'output-container', 'output_container');
 
+  // Precise cut mode
+  attachDropdownListener(container, 'precise-cut-mode', 'precise_cut_mode');
+
   // Max concurrent downloads
   attachNumberInputListener(container, 'max-concurrent-downloads', 'max_concurrent_downloads');
 
@@ -1394,6 +1786,16 @@ function attachDownloadEventListeners(container: HTMLElement) {
   // Download speed limit
   attachNumberInputListener(container, 'download-speed-limit', 'download_speed_limit');
 
+  // Bandwidth priority for live recordings
+  attachToggleListener(container, 'prioritize-live-recordings', 'prioritize_live_recordings');
+
+  // Resume unfinished downloads on launch
+  attachToggleListener(container, 'auto-resume-downloads', 'auto_resume_downloads');
+
+  // Reconnect dropped live recordings
+  attachNumberInputListener(container, 'recording-reconnect-grace', 'recording_reconnect_grace_minutes');
+  attachToggleListener(container, 'recording-reconnect-concat', 'recording_reconnect_concat');
+
   // Show codec options
   attachToggleListener(container, 'show-codec-options', 'show_codec_options');
 }
@@ -1806,6 +2208,146 @@ function attachAuthEventListeners(container: HTMLElement) {
     }
   });
 
+  // Cookie profiles
+  const cookieProfilesList = container.querySelector('#cookie-profiles-list');
+  const cookieSourceSelect = container.querySelector('#cookie-profile-source') as HTMLSelectElement;
+  const cookieFileGroup = container.querySelector('#cookie-profile-file-group') as HTMLElement;
+  const cookieBrowserGroup = container.querySelector('#cookie-profile-browser-group') as HTMLElement;
+  const cookiePathInput = container.querySelector('#cookie-profile-path') as HTMLInputElement;
+  const cookieProfileStatus = container.querySelector('#cookie-profile-status');
+
+  renderCookieProfiles(cookieProfilesList);
+
+  cookieSourceSelect?.addEventListener('change', () => {
+    const fromBrowser = cookieSourceSelect.value === 'browser';
+    cookieFileGroup.hidden = fromBrowser;
+    cookieBrowserGroup.hidden = !fromBrowser;
+  });
+
+  container.querySelector('#cookie-profile-file-btn')?.addEventListener('click', async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{
+        name: 'Cookies',
+        extensions: ['txt']
+      }]
+    });
+
+    if (selected && typeof selected === 'string') {
+      cookiePathInput.value = selected;
+    }
+  });
+
+  container.querySelector('#cookie-profile-add-btn')?.addEventListener('click', async () => {
+    const nameInput = container.querySelector('#cookie-profile-name') as HTMLInputElement;
+    const platformSelect = container.querySelector('#cookie-profile-platform') as HTMLSelectElement;
+    const browserSelect = container.querySelector('#cookie-profile-browser') as HTMLSelectElement;
+    const browserProfileInput = container.querySelector('#cookie-profile-browser-profile') as HTMLInputElement;
+
+    const fromBrowser = cookieSourceSelect.value === 'browser';
+    const browserProfile = browserProfileInput.value.trim();
+    const profile: CookieProfile = {
+      id: `cookie-${Date.now()}`,
+      name: nameInput.value.trim(),
+      platform: platformSelect.value,
+      source: cookieSourceSelect.value,
+      cookies_path: fromBrowser ? null : cookiePathInput.value || null,
+      browser: fromBrowser
+        ? (browserProfile ? `${browserSelect.value}:${browserProfile}` : browserSelect.value)
+        : null,
+      created_at: new Date().toISOString(),
+    };
+
+    updateStatusElement(cookieProfileStatus, 'validating', '驗證中...');
+
+    try {
+      await invoke('save_cookie_profile', { profile });
+      nameInput.value = '';
+      cookiePathInput.value = '';
+      browserProfileInput.value = '';
+      updateStatusElement(cookieProfileStatus, 'verified', '✓ 已新增');
+      await renderCookieProfiles(cookieProfilesList);
+    } catch (error) {
+      console.error('Failed to save cookie profile:', error);
+      updateStatusElement(cookieProfileStatus, 'error', resolveLocalizedMessage(String(error)));
+    }
+  });
+
+  // Secret store passphrase
+  const secretInput = container.querySelector('#secret-passphrase-input') as HTMLInputElement;
+  const secretUnlockBtn = container.querySelector('#secret-unlock-btn') as HTMLButtonElement;
+  const secretSetBtn = container.querySelector('#secret-set-passphrase-btn') as HTMLButtonElement;
+  const secretRemoveBtn = container.querySelector('#secret-remove-passphrase-btn') as HTMLButtonElement;
+  const secretStatus = container.querySelector('#secret-store-status');
+
+  invoke<{ key_source: string; locked: boolean }>('get_secret_store_status')
+    .then(status => {
+      const usesPassphrase = status.key_source === 'passphrase';
+      if (secretUnlockBtn) secretUnlockBtn.hidden = !status.locked;
+      if (secretSetBtn) secretSetBtn.hidden = status.locked;
+      if (secretRemoveBtn) secretRemoveBtn.hidden = status.locked || !usesPassphrase;
+      if (status.locked) {
+        updateStatusElement(secretStatus, 'error', '已鎖定，請輸入密碼解鎖');
+      } else {
+        updateStatusElement(secretStatus, 'verified', usesPassphrase ? '✓ 以密碼加密' : '✓ 以本機金鑰加密');
+      }
+    })
+    .catch(error => {
+      console.error('Failed to get secret store status:', error);
+      updateStatusElement(secretStatus, 'error', resolveLocalizedMessage(String(error)));
+    });
+
+  secretUnlockBtn?.addEventListener('click', async () => {
+    const passphrase = secretInput?.value ?? '';
+    if (!passphrase) {
+      updateStatusElement(secretStatus, 'error', '請輸入密碼');
+      return;
+    }
+
+    updateStatusElement(secretStatus, 'validating', '解鎖中...');
+
+    try {
+      await invoke('unlock_secret_store', { passphrase });
+      await loadAuthConfig();
+      renderSettingsUI(container);
+    } catch (error) {
+      console.error('Failed to unlock secret store:', error);
+      updateStatusElement(secretStatus, 'error', resolveLocalizedMessage(String(error)));
+    }
+  });
+
+  secretSetBtn?.addEventListener('click', async () => {
+    const passphrase = secretInput?.value ?? '';
+    if (!passphrase) {
+      updateStatusElement(secretStatus, 'error', '請輸入密碼');
+      return;
+    }
+    if (!confirm('設定密碼後，每次啟動都需要輸入密碼才能使用已儲存的認證資訊。忘記密碼將無法復原。確定要設定嗎？')) {
+      return;
+    }
+
+    updateStatusElement(secretStatus, 'validating', '加密中...');
+
+    try {
+      await invoke('set_secret_passphrase', { passphrase });
+      secretInput.value = '';
+      renderSettingsUI(container);
+    } catch (error) {
+      console.error('Failed to set secret passphrase:', error);
+      updateStatusElement(secretStatus, 'error', resolveLocalizedMessage(String(error)));
+    }
+  });
+
+  secretRemoveBtn?.addEventListener('click', async () => {
+    try {
+      await invoke('set_secret_passphrase', { passphrase: null });
+      renderSettingsUI(container);
+    } catch (error) {
+      console.error('Failed to remove secret passphrase:', error);
+      updateStatusElement(secretStatus, 'error', resolveLocalizedMessage(String(error)));
+    }
+  });
+
   // Clear all auth
   const clearAuthBtn = container.querySelector('#clear-auth-btn');
   clearAuthBtn?.addEventListener('click', async () => {
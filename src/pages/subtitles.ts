@@ -36,6 +36,7 @@ interface TranscriptionConfig {
   max_chars: number;
   traditional_chinese: boolean;
   auto_segment: boolean;
+  chinese_conversion: string | null;
 }
 
 let selectedFile: { path: string; name: string; size: number; duration: number | null } | null = null;
@@ -127,6 +128,21 @@ export function renderSubtitlesPage(container: HTMLElement) {
                 <option value="srt">${t('subtitles.config.outputSrt')}</option>
                 <option value="txt">${t('subtitles.config.outputTxt')}</option>
                 <option value="both">${t('subtitles.config.outputBoth')}</option>
+                <option value="vtt">${t('subtitles.config.outputVtt')}</option>
+                <option value="ass">${t('subtitles.config.outputAss')}</option>
+                <option value="json">${t('subtitles.config.outputJson')}</option>
+                <option value="all">${t('subtitles.config.outputAll')}</option>
+              </select>
+            </div>
+
+            <div class="config-row">
+              <label class="config-label">${t('subtitles.config.chineseConversion')}</label>
+              <select id="whisper-chinese-conversion" class="config-select">
+                <option value="">${t('subtitles.config.chineseConversionNone')}</option>
+                <option value="s2tw">${t('subtitles.config.chineseConversionS2tw')}</option>
+                <option value="s2hk">${t('subtitles.config.chineseConversionS2hk')}</option>
+                <option value="s2t">${t('subtitles.config.chineseConversionS2t')}</option>
+                <option value="t2s">${t('subtitles.config.chineseConversionT2s')}</option>
               </select>
             </div>
 
@@ -169,6 +185,10 @@ export function renderSubtitlesPage(container: HTMLElement) {
                 <option value="srt">${t('subtitles.config.outputSrt')}</option>
                 <option value="txt">${t('subtitles.config.outputTxt')}</option>
                 <option value="both">${t('subtitles.config.outputBoth')}</option>
+                <option value="vtt">${t('subtitles.config.outputVtt')}</option>
+                <option value="ass">${t('subtitles.config.outputAss')}</option>
+                <option value="json">${t('subtitles.config.outputJson')}</option>
+                <option value="all">${t('subtitles.config.outputAll')}</option>
               </select>
             </div>
 
@@ -180,10 +200,14 @@ export function renderSubtitlesPage(container: HTMLElement) {
             </div>
 
             <div class="config-row">
-              <label class="checkbox-label">
-                <input type="checkbox" id="qwen-traditional" />
-                <span>${t('subtitles.config.traditionalChinese')}</span>
-              </label>
+              <label class="config-label">${t('subtitles.config.chineseConversion')}</label>
+              <select id="qwen-chinese-conversion" class="config-select">
+                <option value="">${t('subtitles.config.chineseConversionNone')}</option>
+                <option value="s2tw">${t('subtitles.config.chineseConversionS2tw')}</option>
+                <option value="s2hk">${t('subtitles.config.chineseConversionS2hk')}</option>
+                <option value="s2t">${t('subtitles.config.chineseConversionS2t')}</option>
+                <option value="t2s">${t('subtitles.config.chineseConversionT2s')}</option>
+              </select>
             </div>
 
             <div class="config-row">
@@ -232,9 +256,41 @@ export function renderSubtitlesPage(container: HTMLElement) {
                 <option value="srt">${t('subtitles.config.outputSrt')}</option>
                 <option value="txt">${t('subtitles.config.outputTxt')}</option>
                 <option value="both">${t('subtitles.config.outputBoth')}</option>
+                <option value="vtt">${t('subtitles.config.outputVtt')}</option>
+                <option value="ass">${t('subtitles.config.outputAss')}</option>
+                <option value="json">${t('subtitles.config.outputJson')}</option>
+                <option value="all">${t('subtitles.config.outputAll')}</option>
+              </select>
+            </div>
+
+            <div class="config-row">
+              <label class="config-label">${t('subtitles.config.chineseConversion')}</label>
+              <select id="cloud-chinese-conversion" class="config-select">
+                <option value="">${t('subtitles.config.chineseConversionNone')}</option>
+                <option value="s2tw">${t('subtitles.config.chineseConversionS2tw')}</option>
+                <option value="s2hk">${t('subtitles.config.chineseConversionS2hk')}</option>
+                <option value="s2t">${t('subtitles.config.chineseConversionS2t')}</option>
+                <option value="t2s">${t('subtitles.config.chineseConversionT2s')}</option>
               </select>
             </div>
 
+            <div class="config-row">
+              <label class="checkbox-label">
+                <input type="checkbox" id="cloud-punctuation" checked />
+                <span>${t('subtitles.config.splitAtPunctuation')}</span>
+              </label>
+            </div>
+
+            <div class="config-row">
+              <label class="config-label">${t('subtitles.config.maxSeconds')}</label>
+              <input type="number" id="cloud-max-seconds" class="config-input" value="7" min="1" max="60" />
+            </div>
+
+            <div class="config-row">
+              <label class="config-label">${t('subtitles.config.maxChars')}</label>
+              <input type="number" id="cloud-max-chars" class="config-input" value="42" min="10" max="200" />
+            </div>
+
             <div class="config-row">
               <label class="checkbox-label">
                 <input type="checkbox" id="cloud-auto-segment" checked />
@@ -735,8 +791,8 @@ async function startTranscription() {
     transcriptionUnlisteners.push(cloudProgressUnlisten);
 
     const completeUnlisten = await listen('transcription-complete', (event: any) => {
-      const { output_path } = event.payload;
-      handleTranscriptionComplete(output_path);
+      const { output_path, output_paths } = event.payload;
+      handleTranscriptionComplete(output_path, output_paths ?? [output_path]);
     });
     transcriptionUnlisteners.push(completeUnlisten);
 
@@ -804,7 +860,7 @@ function updateCloudTranscriptionProgress(currentSegment: number, totalSegments:
   }
 }
 
-function handleTranscriptionComplete(outputPath: string) {
+function handleTranscriptionComplete(outputPath: string, outputPaths: string[]) {
   // Clean up event listeners
   transcriptionUnlisteners.forEach(unlisten => unlisten());
   transcriptionUnlisteners = [];
@@ -826,7 +882,7 @@ function handleTranscriptionComplete(outputPath: string) {
   // Update result display
   const outputFilePathEl = document.getElementById('output-file-path');
   if (outputFilePathEl) {
-    outputFilePathEl.textContent = t('subtitles.result.outputFile', { path: outputPath });
+    outputFilePathEl.textContent = t('subtitles.result.outputFile', { path: outputPaths.join(', ') });
   }
 
   // Store output path for later actions
@@ -881,6 +937,7 @@ function buildTranscriptionConfig(): TranscriptionConfig {
         max_chars: 0,
         traditional_chinese: false,
         auto_segment: false,
+        chinese_conversion: (document.getElementById('whisper-chinese-conversion') as HTMLSelectElement)?.value || null,
       };
     } else {
       return {
@@ -895,8 +952,9 @@ function buildTranscriptionConfig(): TranscriptionConfig {
         enable_punctuation: (document.getElementById('qwen-punctuation') as HTMLInputElement)?.checked || false,
         max_seconds: parseInt((document.getElementById('qwen-max-seconds') as HTMLInputElement)?.value || '30'),
         max_chars: parseInt((document.getElementById('qwen-max-chars') as HTMLInputElement)?.value || '50'),
-        traditional_chinese: (document.getElementById('qwen-traditional') as HTMLInputElement)?.checked || false,
+        traditional_chinese: false,
         auto_segment: false,
+        chinese_conversion: (document.getElementById('qwen-chinese-conversion') as HTMLSelectElement)?.value || null,
       };
     }
   } else {
@@ -912,11 +970,12 @@ function buildTranscriptionConfig(): TranscriptionConfig {
       hardware_mode: 'auto',
       vad_enabled: false,
       demucs_enabled: false,
-      enable_punctuation: false,
-      max_seconds: 0,
-      max_chars: 0,
+      enable_punctuation: (document.getElementById('cloud-punctuation') as HTMLInputElement)?.checked || false,
+      max_seconds: parseInt((document.getElementById('cloud-max-seconds') as HTMLInputElement)?.value || '7'),
+      max_chars: parseInt((document.getElementById('cloud-max-chars') as HTMLInputElement)?.value || '42'),
       traditional_chinese: false,
       auto_segment: (document.getElementById('cloud-auto-segment') as HTMLInputElement)?.checked || false,
+      chinese_conversion: (document.getElementById('cloud-chinese-conversion') as HTMLSelectElement)?.value || null,
     };
   }
 }